@@ -0,0 +1,43 @@
+use core::fmt;
+use std::error::Error as StdError;
+use std::path::PathBuf;
+
+use gd_props_defs::errors::GdPropError;
+
+/// Error returned by `gdprops` commands.
+#[derive(Debug)]
+pub enum CliError {
+    Io(PathBuf, std::io::Error),
+    Prop(PathBuf, GdPropError),
+    Unregistered(String),
+    InvalidUid(PathBuf, String),
+    Invalid(usize),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            CliError::Prop(path, error) => write!(f, "{}: {}", path.display(), error),
+            CliError::Unregistered(class) => write!(
+                f,
+                "class {} is not registered; use --schemaless to convert it anyway",
+                class
+            ),
+            CliError::InvalidUid(path, uid) => {
+                write!(f, "{}: invalid UID in header: {}", path.display(), uid)
+            }
+            CliError::Invalid(count) => write!(f, "{} file(s) failed validation", count),
+        }
+    }
+}
+
+impl StdError for CliError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            CliError::Io(_, error) => Some(error),
+            CliError::Prop(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
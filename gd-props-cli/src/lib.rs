@@ -0,0 +1,357 @@
+//! Command line tool to convert, validate and inspect `.gdron` and `.gdbin` files without Godot engine.
+//!
+//! The `gdprops` binary works without knowing the Rust types of saved resources: `.gdbin` payload is self-describing
+//! MessagePack, so it can be dumped as ron, validated and scanned for External Resources. Schema-less dump keeps
+//! structs as sequences though, as the field names are not saved in `.gdbin` files.
+//!
+//! To convert files into fully readable `.gdron`, build your own binary with your resources registered:
+//!
+//! ```ignore
+//! use std::process::ExitCode;
+//!
+//! use gd_props_cli::GdPropsCli;
+//! use my_game::resources::MyResource;
+//!
+//! fn main() -> ExitCode {
+//!     GdPropsCli::new().register::<MyResource>().run()
+//! }
+//! ```
+//!
+//! Registered resources are deserialized without Godot engine running, so they can't contain bundled
+//! `Gd` pointers.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use gd_props_defs::codec::{
+    BinCodec, GdMetaHeader, GdPropCodec, GdPropCompression, GdPropSeal, RonCodec,
+};
+use gd_props_defs::errors::GdPropError;
+use gd_props_defs::traits::GdProp;
+
+mod errors;
+mod schemaless;
+#[cfg(test)]
+mod tests;
+
+pub use errors::CliError;
+
+#[derive(Parser)]
+#[command(
+    name = "gdprops",
+    version,
+    about = "Convert, validate and inspect gd-props files"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert the file between `.gdron` and `.gdbin` formats, recognized by extension.
+    Convert {
+        input: PathBuf,
+        output: PathBuf,
+        /// Allow converting `.gdron` of unregistered class. Names of enum variants are lost, unless the file is
+        /// a schema-less dump of `.gdbin` file.
+        #[arg(long)]
+        schemaless: bool,
+    },
+    /// Print the file as ron.
+    Dump { input: PathBuf },
+    /// Validate header and payload of the files.
+    Validate {
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// Class expected in the header of every file.
+        #[arg(long)]
+        class: Option<String>,
+    },
+    /// List External Resources referenced in the file.
+    Refs { input: PathBuf },
+    /// Scan the directory recursively for damaged `.gdron` and `.gdbin` files.
+    Scan { dir: PathBuf },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum FileFormat {
+    Ron,
+    Bin,
+}
+
+impl FileFormat {
+    pub(crate) fn from_path(path: &Path) -> Result<Self, CliError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(RonCodec::EXTENSION) => Ok(FileFormat::Ron),
+            Some(BinCodec::EXTENSION) => Ok(FileFormat::Bin),
+            _ => Err(CliError::Prop(
+                path.to_path_buf(),
+                GdPropError::UnrecognizedFormat(path.display().to_string()),
+            )),
+        }
+    }
+
+    /// Read the header and the payload as stored in the file, verifying its checksum.
+    pub(crate) fn read_stored(
+        &self,
+        mut content: &[u8],
+    ) -> Result<(GdMetaHeader, Vec<u8>), GdPropError> {
+        match self {
+            FileFormat::Ron => RonCodec::read_parts(&mut content),
+            FileFormat::Bin => BinCodec::read_parts(&mut content),
+        }
+    }
+
+    /// Read the header and decompressed payload of the file. Sealed files can't be read.
+    pub(crate) fn read_parts(
+        &self,
+        content: &[u8],
+    ) -> Result<(GdMetaHeader, Vec<u8>), GdPropError> {
+        let (header, stored) = self.read_stored(content)?;
+        let compressed = GdPropSeal::open(&header, stored, None)?;
+        let payload = header.compression.decompress(&compressed)?;
+        Ok((header, payload))
+    }
+
+    /// Write the header followed by already compressed payload.
+    pub(crate) fn write_parts(
+        &self,
+        header: &GdMetaHeader,
+        payload: &[u8],
+        writer: &mut Vec<u8>,
+    ) -> Result<(), GdPropError> {
+        match self {
+            FileFormat::Ron => RonCodec::write_parts(header, payload, writer),
+            FileFormat::Bin => BinCodec::write_parts(header, payload, writer),
+        }
+    }
+}
+
+/// Converts the whole file using registered Rust type.
+type TypedConverter = fn(&[u8], FileFormat, FileFormat) -> Result<Vec<u8>, GdPropError>;
+
+fn convert_typed<T: GdProp>(
+    mut input: &[u8],
+    from: FileFormat,
+    to: FileFormat,
+) -> Result<Vec<u8>, GdPropError> {
+    let (mut header, value) = match from {
+        FileFormat::Ron => RonCodec::decode::<T, _>(&mut input)?,
+        FileFormat::Bin => BinCodec::decode::<T, _>(&mut input)?,
+    };
+    // Payload is converted with current schema, `.gdron` is kept readable.
+    header.version = T::VERSION;
+    if to == FileFormat::Ron {
+        header.compression = GdPropCompression::None;
+    }
+
+    let mut output = Vec::new();
+    match to {
+        FileFormat::Ron => RonCodec::encode(&header, &value, &mut output)?,
+        FileFormat::Bin => BinCodec::encode(&header, &value, &mut output)?,
+    }
+    Ok(output)
+}
+
+/// Command line interface of `gdprops` tool.
+#[derive(Default)]
+pub struct GdPropsCli {
+    registered: HashMap<&'static str, TypedConverter>,
+}
+
+impl GdPropsCli {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register [`GdProp`] resource, so its files are converted and validated using its Rust type.
+    pub fn register<T: GdProp>(mut self) -> Self {
+        self.registered.insert(T::HEAD_IDENT, convert_typed::<T>);
+        self
+    }
+
+    /// Parse command line arguments and run the command.
+    pub fn run(self) -> ExitCode {
+        let args = Args::parse();
+        match self.run_command(args.command) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    fn run_command(&self, command: Command) -> Result<(), CliError> {
+        match command {
+            Command::Convert {
+                input,
+                output,
+                schemaless,
+            } => self.convert(&input, &output, schemaless),
+            Command::Dump { input } => self.dump(&input),
+            Command::Validate { inputs, class } => self.validate(&inputs, class.as_deref()),
+            Command::Refs { input } => self.refs(&input),
+            Command::Scan { dir } => self.scan(&dir),
+        }
+    }
+
+    fn convert(&self, input: &Path, output: &Path, schemaless: bool) -> Result<(), CliError> {
+        let from = FileFormat::from_path(input)?;
+        let to = FileFormat::from_path(output)?;
+        let content = read_file(input)?;
+
+        let (header, payload) = from
+            .read_parts(&content)
+            .map_err(|error| CliError::Prop(input.to_path_buf(), error))?;
+
+        let converted = if let Some(converter) = self.registered.get(header.gd_class.as_str()) {
+            converter(&content, from, to)
+        } else if from == FileFormat::Ron && to == FileFormat::Bin && !schemaless {
+            return Err(CliError::Unregistered(header.gd_class));
+        } else {
+            if from != to {
+                eprintln!(
+                    "warning: class {} is not registered, converting schema-less payload",
+                    header.gd_class
+                );
+            }
+            schemaless::convert(&header, &payload, from, to)
+        };
+
+        let converted = converted.map_err(|error| CliError::Prop(input.to_path_buf(), error))?;
+        std::fs::write(output, converted).map_err(|error| CliError::Io(output.to_path_buf(), error))
+    }
+
+    fn dump(&self, input: &Path) -> Result<(), CliError> {
+        let format = FileFormat::from_path(input)?;
+        let content = read_file(input)?;
+
+        let dumped = format
+            .read_parts(&content)
+            .and_then(|(header, payload)| {
+                schemaless::convert(&header, &payload, format, FileFormat::Ron)
+            })
+            .map_err(|error| CliError::Prop(input.to_path_buf(), error))?;
+
+        println!("{}", String::from_utf8_lossy(&dumped));
+        Ok(())
+    }
+
+    fn validate(&self, inputs: &[PathBuf], class: Option<&str>) -> Result<(), CliError> {
+        let mut failed = 0;
+        for input in inputs {
+            match self.validate_file(input, class) {
+                Ok(header) => println!(
+                    "OK {}: {} ({})",
+                    input.display(),
+                    header.gd_class,
+                    header.uid
+                ),
+                Err(error) => {
+                    println!("FAILED {}", error);
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(CliError::Invalid(failed));
+        }
+        Ok(())
+    }
+
+    fn validate_file(&self, input: &Path, class: Option<&str>) -> Result<GdMetaHeader, CliError> {
+        let format = FileFormat::from_path(input)?;
+        let content = read_file(input)?;
+        let with_path = |error| CliError::Prop(input.to_path_buf(), error);
+
+        let (header, payload) = format.read_parts(&content).map_err(with_path)?;
+        if let Some(class) = class {
+            header.check_class(class).map_err(with_path)?;
+        }
+        if !header.uid.starts_with("uid://") {
+            return Err(CliError::InvalidUid(input.to_path_buf(), header.uid));
+        }
+
+        if let Some(converter) = self.registered.get(header.gd_class.as_str()) {
+            converter(&content, format, format).map_err(with_path)?;
+        } else {
+            schemaless::validate(&payload, format).map_err(with_path)?;
+        }
+        Ok(header)
+    }
+
+    fn scan(&self, dir: &Path) -> Result<(), CliError> {
+        let mut files = Vec::new();
+        collect_files(dir, &mut files)?;
+        files.sort();
+
+        let mut damaged = 0;
+        for input in files.iter() {
+            if let Err(error) = self.scan_file(input) {
+                println!("DAMAGED {}", error);
+                damaged += 1;
+            }
+        }
+        println!("{} file(s) scanned, {} damaged", files.len(), damaged);
+
+        if damaged > 0 {
+            return Err(CliError::Invalid(damaged));
+        }
+        Ok(())
+    }
+
+    fn scan_file(&self, input: &Path) -> Result<(), CliError> {
+        let format = FileFormat::from_path(input)?;
+        let content = read_file(input)?;
+
+        let (header, _) = format
+            .read_stored(&content)
+            .map_err(|error| CliError::Prop(input.to_path_buf(), error))?;
+        // Payload of sealed file can't be read without the key, so only its checksum is verified.
+        if header.seal.is_some() {
+            return Ok(());
+        }
+        self.validate_file(input, None).map(|_| ())
+    }
+
+    fn refs(&self, input: &Path) -> Result<(), CliError> {
+        let format = FileFormat::from_path(input)?;
+        let content = read_file(input)?;
+
+        let references = format
+            .read_parts(&content)
+            .and_then(|(_, payload)| schemaless::payload(&payload, format).ext_references())
+            .map_err(|error| CliError::Prop(input.to_path_buf(), error))?;
+
+        for reference in references {
+            println!(
+                "{}\t{}\t{}",
+                reference.gd_class, reference.uid, reference.path
+            );
+        }
+        Ok(())
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), CliError> {
+    let entries = std::fs::read_dir(dir).map_err(|error| CliError::Io(dir.to_path_buf(), error))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|error| CliError::Io(dir.to_path_buf(), error))?
+            .path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if FileFormat::from_path(&path).is_ok() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, CliError> {
+    std::fs::read(path).map_err(|error| CliError::Io(path.to_path_buf(), error))
+}
@@ -0,0 +1,7 @@
+use std::process::ExitCode;
+
+use gd_props_cli::GdPropsCli;
+
+fn main() -> ExitCode {
+    GdPropsCli::new().run()
+}
@@ -0,0 +1,83 @@
+//! Operations on payloads of unregistered classes, done without knowing their schema.
+
+use gd_props_defs::codec::{GdMetaHeader, GdPropCompression};
+use gd_props_defs::errors::GdPropError;
+use gd_props_defs::migration::GdPropPayload;
+
+use crate::FileFormat;
+
+/// Wrap the `payload` read from the file of given `format`.
+pub(crate) fn payload(payload: &[u8], format: FileFormat) -> GdPropPayload {
+    match format {
+        FileFormat::Ron => GdPropPayload::Ron(String::from_utf8_lossy(payload).into_owned()),
+        FileFormat::Bin => GdPropPayload::Bin(payload.to_vec()),
+    }
+}
+
+/// Check if the `payload` is well-formed.
+pub(crate) fn validate(payload: &[u8], format: FileFormat) -> Result<(), GdPropError> {
+    match format {
+        FileFormat::Ron => {
+            let payload = std::str::from_utf8(payload).map_err(|error| {
+                GdPropError::FileRead(std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+            })?;
+            ron::from_str::<ron::Value>(payload)
+                .map(|_| ())
+                .map_err(GdPropError::RonDeserialize)
+        }
+        FileFormat::Bin => rmp_serde::from_slice::<rmpv::Value>(payload)
+            .map(|_| ())
+            .map_err(GdPropError::BinDeserialize),
+    }
+}
+
+/// Convert the decompressed `payload` between formats through self-describing value.
+///
+/// `.gdbin` payload keeps structs as sequences and enums as single-entry maps, so `.gdron` output has no field
+/// names. [`ron::Value`] doesn't keep names of enum variants, so converting `.gdron` back is lossless only for
+/// files produced by this conversion. Output `.gdron` is never compressed.
+pub(crate) fn convert(
+    header: &GdMetaHeader,
+    payload: &[u8],
+    from: FileFormat,
+    to: FileFormat,
+) -> Result<Vec<u8>, GdPropError> {
+    validate(payload, from)?;
+
+    let mut header = header.clone();
+    if to == FileFormat::Ron {
+        header.compression = GdPropCompression::None;
+    }
+
+    let converted = match (from, to) {
+        (FileFormat::Ron, FileFormat::Ron) | (FileFormat::Bin, FileFormat::Bin) => payload.to_vec(),
+        (FileFormat::Bin, FileFormat::Ron) => {
+            let value = rmp_serde::from_slice::<rmpv::Value>(payload)
+                .map_err(GdPropError::BinDeserialize)?;
+            ron::ser::to_string_pretty(&value, ron::ser::PrettyConfig::default())
+                .map_err(GdPropError::RonSerialize)?
+                .into_bytes()
+        }
+        (FileFormat::Ron, FileFormat::Bin) => {
+            let value = std::str::from_utf8(payload)
+                .map_err(|error| {
+                    GdPropError::FileRead(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        error,
+                    ))
+                })
+                .and_then(|payload| {
+                    ron::from_str::<ron::Value>(payload).map_err(GdPropError::RonDeserialize)
+                })?;
+            rmp_serde::to_vec(&value).map_err(GdPropError::BinSerialize)?
+        }
+    };
+
+    let mut output = Vec::new();
+    to.write_parts(
+        &header,
+        &header.compression.compress(&converted)?,
+        &mut output,
+    )?;
+    Ok(output)
+}
@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+
+use gd_props::GdProp;
+use gd_props_defs::codec::{
+    BinCodec, GdMetaHeader, GdPropCodec, GdPropCompression, GdPropSeal, RonCodec,
+};
+use gd_props_defs::errors::GdPropError;
+use gd_props_defs::traits::GdProp;
+use godot::prelude::GodotClass;
+use serde::{Deserialize, Serialize};
+
+use crate::{collect_files, convert_typed, schemaless, CliError, FileFormat, GdPropsCli};
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp, PartialEq, Debug)]
+#[class(init, base=Resource)]
+struct CliStruct {
+    name: String,
+    values: Vec<i32>,
+}
+
+fn cli_struct() -> CliStruct {
+    CliStruct {
+        name: "cli".to_string(),
+        values: vec![-1, 0, 1],
+    }
+}
+
+fn cli_header() -> GdMetaHeader {
+    GdMetaHeader {
+        gd_class: CliStruct::HEAD_IDENT.to_string(),
+        uid: "uid://cgmqkr8i6rr5v".to_string(),
+        version: CliStruct::VERSION,
+        compression: GdPropCompression::None,
+        seal: None,
+        checksum: None,
+    }
+}
+
+fn encoded(format: FileFormat) -> Vec<u8> {
+    let mut file = Vec::new();
+    match format {
+        FileFormat::Ron => RonCodec::encode(&cli_header(), &cli_struct(), &mut file).unwrap(),
+        FileFormat::Bin => BinCodec::encode(&cli_header(), &cli_struct(), &mut file).unwrap(),
+    }
+    file
+}
+
+/// Empty directory in system temporary directory, unique for the test.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gd-props-cli-{name}-{}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, file: &str, content: &[u8]) -> PathBuf {
+    let path = dir.join(file);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn typed_conversion_roundtrip() {
+    let bin =
+        convert_typed::<CliStruct>(&encoded(FileFormat::Ron), FileFormat::Ron, FileFormat::Bin)
+            .unwrap();
+    let (header, decoded) = BinCodec::decode::<CliStruct, _>(&mut bin.as_slice()).unwrap();
+    assert_eq!(header.uid, cli_header().uid);
+    assert_eq!(decoded, cli_struct());
+
+    let ron = convert_typed::<CliStruct>(&bin, FileFormat::Bin, FileFormat::Ron).unwrap();
+    let (header, decoded) = RonCodec::decode::<CliStruct, _>(&mut ron.as_slice()).unwrap();
+    assert_eq!(header.checksum, None);
+    assert_eq!(decoded, cli_struct());
+}
+
+#[test]
+fn typed_conversion_keeps_ron_uncompressed() {
+    let mut header = cli_header();
+    header.compression = GdPropCompression::Zstd;
+    let mut bin = Vec::new();
+    BinCodec::encode(&header, &cli_struct(), &mut bin).unwrap();
+
+    let ron = convert_typed::<CliStruct>(&bin, FileFormat::Bin, FileFormat::Ron).unwrap();
+    let (header, decoded) = RonCodec::decode::<CliStruct, _>(&mut ron.as_slice()).unwrap();
+    assert_eq!(header.compression, GdPropCompression::None);
+    assert_eq!(decoded, cli_struct());
+}
+
+#[test]
+fn typed_conversion_rejects_other_class() {
+    let mut header = cli_header();
+    header.gd_class = "OtherStruct".to_string();
+    let mut bin = Vec::new();
+    BinCodec::encode(&header, &cli_struct(), &mut bin).unwrap();
+
+    let result = convert_typed::<CliStruct>(&bin, FileFormat::Bin, FileFormat::Ron);
+    assert!(matches!(result, Err(GdPropError::ClassMismatch { .. })));
+}
+
+#[test]
+fn schemaless_conversion_roundtrip() {
+    let bin = encoded(FileFormat::Bin);
+    let (header, payload) = FileFormat::Bin.read_parts(&bin).unwrap();
+
+    let ron = schemaless::convert(&header, &payload, FileFormat::Bin, FileFormat::Ron).unwrap();
+    let (ron_header, ron_payload) = FileFormat::Ron.read_parts(&ron).unwrap();
+    assert_eq!(ron_header.gd_class, header.gd_class);
+    assert_eq!(ron_header.uid, header.uid);
+    // Structs are kept as sequences, as field names aren't saved in `.gdbin` files.
+    let value = ron::from_str::<ron::Value>(std::str::from_utf8(&ron_payload).unwrap()).unwrap();
+    assert!(matches!(value, ron::Value::Seq(ref fields) if fields.len() == 2));
+
+    let back =
+        schemaless::convert(&ron_header, &ron_payload, FileFormat::Ron, FileFormat::Bin).unwrap();
+    let (_, decoded) = BinCodec::decode::<CliStruct, _>(&mut back.as_slice()).unwrap();
+    assert_eq!(decoded, cli_struct());
+}
+
+#[test]
+fn schemaless_conversion_rejects_malformed_payload() {
+    let header = cli_header();
+    let result = schemaless::convert(&header, b"(name: ", FileFormat::Ron, FileFormat::Bin);
+    assert!(matches!(result, Err(GdPropError::RonDeserialize(_))));
+
+    let result = schemaless::convert(&header, &[0xc1], FileFormat::Bin, FileFormat::Ron);
+    assert!(matches!(result, Err(GdPropError::BinDeserialize(_))));
+}
+
+#[test]
+fn files_validated() {
+    let dir = temp_dir("validate");
+    let cli = GdPropsCli::new().register::<CliStruct>();
+
+    let valid = write(&dir, "valid.gdbin", &encoded(FileFormat::Bin));
+    let header = cli.validate_file(&valid, Some("CliStruct")).unwrap();
+    assert_eq!(header.uid, cli_header().uid);
+    // Unregistered class is validated without schema.
+    assert!(GdPropsCli::new().validate_file(&valid, None).is_ok());
+
+    assert!(matches!(
+        cli.validate_file(&valid, Some("OtherStruct")),
+        Err(CliError::Prop(_, GdPropError::ClassMismatch { .. }))
+    ));
+
+    let mut header = cli_header();
+    header.uid = String::new();
+    let mut without_uid = Vec::new();
+    RonCodec::encode(&header, &cli_struct(), &mut without_uid).unwrap();
+    let without_uid = write(&dir, "without_uid.gdron", &without_uid);
+    assert!(matches!(
+        cli.validate_file(&without_uid, None),
+        Err(CliError::InvalidUid(..))
+    ));
+
+    let mut malformed = encoded(FileFormat::Ron);
+    malformed.truncate(malformed.len() - 4);
+    let malformed = write(&dir, "malformed.gdron", &malformed);
+    assert!(cli.validate_file(&malformed, None).is_err());
+
+    let mut damaged = encoded(FileFormat::Bin);
+    *damaged.last_mut().unwrap() ^= 0xff;
+    let damaged = write(&dir, "damaged.gdbin", &damaged);
+    assert!(matches!(
+        cli.validate_file(&damaged, None),
+        Err(CliError::Prop(_, GdPropError::ChecksumMismatch { .. }))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sealed_files_scanned_by_checksum() {
+    let dir = temp_dir("scan");
+    let cli = GdPropsCli::new();
+
+    let mut header = cli_header();
+    header.seal = Some(GdPropSeal::Aes256Gcm { nonce: vec![0; 12] });
+    let mut sealed = Vec::new();
+    BinCodec::write_parts(&header, b"encrypted payload", &mut sealed).unwrap();
+
+    // Payload can't be read without the key, but its checksum is intact.
+    let intact = write(&dir, "intact.gdbin", &sealed);
+    assert!(cli.scan_file(&intact).is_ok());
+    assert!(cli.validate_file(&intact, None).is_err());
+
+    *sealed.last_mut().unwrap() ^= 0xff;
+    let damaged = write(&dir, "damaged.gdbin", &sealed);
+    assert!(matches!(
+        cli.scan_file(&damaged),
+        Err(CliError::Prop(_, GdPropError::ChecksumMismatch { .. }))
+    ));
+
+    let valid = write(&dir, "valid.gdron", &encoded(FileFormat::Ron));
+    assert!(cli.scan_file(&valid).is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn files_collected_recursively() {
+    let dir = temp_dir("collect");
+    std::fs::create_dir_all(dir.join("nested/deeper")).unwrap();
+    write(&dir, "first.gdron", b"");
+    write(&dir, "nested/second.gdbin", b"");
+    write(&dir, "nested/deeper/third.gdron", b"");
+    write(&dir, "nested/icon.png", b"");
+    write(&dir, "notes.txt", b"");
+
+    let mut files = Vec::new();
+    collect_files(&dir, &mut files).unwrap();
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            dir.join("first.gdron"),
+            dir.join("nested/deeper/third.gdron"),
+            dir.join("nested/second.gdbin"),
+        ]
+    );
+
+    assert!(matches!(
+        collect_files(&dir.join("missing"), &mut Vec::new()),
+        Err(CliError::Io(..))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,746 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use godot::builtin::{Array, Dictionary, GString, VariantType};
+use godot::classes::Resource;
+use godot::obj::{Gd, GodotClass, Inherits, NewGd, UserClass};
+use serde::{de, ser, Deserialize, Serialize};
+
+use crate::builtin::GdSerdeBuiltin;
+use crate::gd_meta::{GdExtResource, GdMetaExt};
+use crate::native::GdNativeResource;
+use crate::registry::{GdPropRegistry, POLY_NAME};
+use crate::subresource::GdSubResources;
+
+/// Serialize `T` in the way described by the implementing adapter. Implemented by [`GdBundled`], [`GdShared`] and
+/// [`GdExt`] for [`Gd`] pointers, and by containers of adapters for the same containers of values.
+pub trait GdSerializeAs<T: ?Sized> {
+    fn serialize_as<S>(source: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer;
+}
+
+/// Deserialize `T` in the way described by the implementing adapter. Counterpart of [`GdSerializeAs`].
+pub trait GdDeserializeAs<'de, T> {
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>;
+
+    #[doc(hidden)]
+    /// Internal method. Deserialize the value which can be left out of its container, as missing External Resources
+    /// with [`GdExtMissingPolicy::Skip`](crate::serde_gd::GdExtMissingPolicy::Skip).
+    fn deserialize_skippable<D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Self::deserialize_as(deserializer).map(Some)
+    }
+}
+
+/// Adapter to be used with `#[serde(with = "...")]` attribute, (de)serializing the field as described by `A`.
+///
+/// `A` mirrors the type of the field, with every [`Gd`] pointer replaced by one of the adapters:
+/// - [`GdBundled`] - bundled resource, like [`serde_gd::gd`](crate::serde_gd::gd),
+/// - [`GdShared`] - bundled resource shared within the file, like [`serde_gd::gd_shared`](crate::serde_gd::gd_shared),
+/// - [`GdExt`] - External Resource, like [`serde_gd::ext`](crate::serde_gd::ext),
+/// - [`GdNative`] - native Godot resource, like [`serde_gd::native`](crate::serde_gd::native),
+/// - [`GdPoly`] - bundled resource of any class registered in the plugin `R`.
+///
+/// Godot builtin types are adapted with [`GdBuiltin`], like [`serde_gd::builtin`](crate::serde_gd::builtin).
+///
+/// Supported containers are [`Option`], [`Vec`], [`HashSet`], [`HashMap`], [`BTreeMap`], `IndexMap` with `indexmap`
+/// feature, and their nested combinations. Godot's [`Array`] and [`Dictionary`] are handled with [`GdArray`] and
+/// [`GdDictionary`] adapters.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// use gd_props::serde_gd::{GdAs, GdBundled, GdExt};
+/// use godot::prelude::*;
+/// use godot::classes::Texture2D;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(init, base=Resource)]
+/// struct Item {}
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(init, base=Resource)]
+/// struct Inventory {
+///     #[serde(with = "GdAs::<Vec<Option<GdBundled>>>")]
+///     slots: Vec<Option<Gd<Item>>>,
+///     #[serde(with = "GdAs::<HashMap<String, Vec<GdBundled>>>")]
+///     stashes: HashMap<String, Vec<Gd<Item>>>,
+///     #[serde(with = "GdAs::<BTreeMap<u32, GdExt>>")]
+///     icons: BTreeMap<u32, Gd<Texture2D>>,
+/// }
+/// ```
+pub struct GdAs<A>(PhantomData<A>);
+
+impl<A> GdAs<A> {
+    pub fn serialize<S, T>(source: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: ?Sized,
+        A: GdSerializeAs<T>,
+    {
+        A::serialize_as(source, serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        A: GdDeserializeAs<'de, T>,
+    {
+        A::deserialize_as(deserializer)
+    }
+}
+
+/// Adapter of [`Gd`] pointer to rust-defined resource, bundled within the file.
+pub struct GdBundled;
+
+/// Adapter of [`Gd`] pointer to rust-defined resource, bundled within the file and shared with other pointers to the
+/// same instance. See [`serde_gd::gd_shared`](crate::serde_gd::gd_shared).
+pub struct GdShared;
+
+/// Adapter of [`Gd`] pointer to External Resource, saved in its own file.
+pub struct GdExt;
+
+/// Adapter of [`Gd`] pointer to native Godot resource, embedded within the file with its stored properties. See
+/// [`serde_gd::native`](crate::serde_gd::native).
+pub struct GdNative;
+
+/// Adapter of [`Gd`] pointer to the resource of any class registered in `gd_props_plugin` `R`, bundled within the file
+/// together with its class. The pointer can be of any class the registered ones inherit from, most often [`Resource`].
+/// See [`GdPropRegistry`] for details.
+///
+/// ## Example
+///
+/// ```no_run
+/// use gd_props::{gd_props_plugin, GdProp};
+/// use gd_props::serde_gd::{GdAs, GdPoly};
+/// use godot::prelude::*;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+/// #[class(init, base=Resource)]
+/// struct FireEffect {
+///     damage: u32,
+/// }
+///
+/// #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+/// #[class(init, base=Resource)]
+/// struct IceEffect {
+///     slow: f32,
+/// }
+///
+/// #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+/// #[class(init, base=Resource)]
+/// struct Ability {
+///     #[serde(with = "GdAs::<Vec<GdPoly<PropPlugin>>>")]
+///     effects: Vec<Gd<Resource>>,
+/// }
+///
+/// #[gd_props_plugin]
+/// #[register(FireEffect, IceEffect, Ability)]
+/// pub(crate) struct PropPlugin;
+/// ```
+pub struct GdPoly<R>(PhantomData<R>);
+
+/// Adapter of Godot builtin type implementing [`GdSerdeBuiltin`].
+pub struct GdBuiltin;
+
+/// Adapter of Godot's [`Array`] of [`Gd`] pointers, adapted with `A`.
+pub struct GdArray<A>(PhantomData<A>);
+
+/// Adapter of Godot's [`Dictionary`] with string keys and [`Gd`] pointers to class `T` as values, adapted with `A`.
+/// Keys need to be `String` or `StringName`, and are deserialized as `String`.
+pub struct GdDictionary<T, A>(PhantomData<(T, A)>);
+
+/// Value serialized with adapter `A`.
+struct SerializeWith<'a, A, T: ?Sized>(&'a T, PhantomData<A>);
+
+impl<'a, A, T: ?Sized> SerializeWith<'a, A, T> {
+    fn new(value: &'a T) -> Self {
+        Self(value, PhantomData)
+    }
+}
+
+impl<A, T> Serialize for SerializeWith<'_, A, T>
+where
+    A: GdSerializeAs<T>,
+    T: ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        A::serialize_as(self.0, serializer)
+    }
+}
+
+/// Value deserialized with adapter `A`, `None` if it was left out.
+struct DeserializeWith<A, T>(Option<T>, PhantomData<A>);
+
+impl<'de, A, T> Deserialize<'de> for DeserializeWith<A, T>
+where
+    A: GdDeserializeAs<'de, T>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        A::deserialize_skippable(deserializer).map(|value| Self(value, PhantomData))
+    }
+}
+
+impl<T> GdSerializeAs<Gd<T>> for GdBundled
+where
+    T: GodotClass + UserClass + Serialize,
+{
+    fn serialize_as<S>(source: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        source.bind().serialize(serializer)
+    }
+}
+
+impl<'de, T> GdDeserializeAs<'de, Gd<T>> for GdBundled
+where
+    T: GodotClass + UserClass + Deserialize<'de>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Gd::from_object)
+    }
+}
+
+impl<T> GdSerializeAs<Gd<T>> for GdShared
+where
+    T: GodotClass + UserClass + Serialize,
+{
+    fn serialize_as<S>(source: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        GdSubResources::serialize(source, serializer)
+    }
+}
+
+impl<'de, T> GdDeserializeAs<'de, Gd<T>> for GdShared
+where
+    T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        GdSubResources::deserialize(deserializer)
+    }
+}
+
+impl<T> GdSerializeAs<Gd<T>> for GdExt
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn serialize_as<S>(source: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        GdExtResource::ExtResource(GdMetaExt::from_resource(&source.clone().upcast()))
+            .serialize(serializer)
+    }
+}
+
+impl<'de, T> GdDeserializeAs<'de, Gd<T>> for GdExt
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        match GdExtResource::deserialize(deserializer)? {
+            GdExtResource::ExtResource(meta) => meta
+                .resolve::<T, D::Error>(false)?
+                .ok_or_else(|| de::Error::custom("cannot load resource")),
+            GdExtResource::None => Err(de::Error::custom("no meta found")),
+        }
+    }
+
+    fn deserialize_skippable<D>(deserializer: D) -> Result<Option<Gd<T>>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        match GdExtResource::deserialize(deserializer)? {
+            GdExtResource::ExtResource(meta) => meta.resolve::<T, D::Error>(true),
+            GdExtResource::None => Err(de::Error::custom("no meta found")),
+        }
+    }
+}
+
+impl<T> GdSerializeAs<Gd<T>> for GdNative
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn serialize_as<S>(source: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        GdNativeResource::serialize(source, serializer)
+    }
+}
+
+impl<'de, T> GdDeserializeAs<'de, Gd<T>> for GdNative
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        GdNativeResource::deserialize(deserializer)
+    }
+}
+
+impl<R, T> GdSerializeAs<Gd<T>> for GdPoly<R>
+where
+    R: GdPropRegistry,
+    T: GodotClass + Inherits<Resource>,
+{
+    fn serialize_as<S>(source: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        R::_int_serialize_poly(&source.clone().upcast(), serializer)
+    }
+}
+
+impl<'de, R, T> GdDeserializeAs<'de, Gd<T>> for GdPoly<R>
+where
+    R: GdPropRegistry,
+    T: GodotClass + Inherits<Resource>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let resource =
+            deserializer.deserialize_enum(POLY_NAME, R::CLASSES, PolyVisitor::<R>(PhantomData))?;
+        let class = resource.get_class();
+        resource.try_cast::<T>().map_err(|_| {
+            de::Error::custom(format!(
+                "resource of class {class} is not of class {}",
+                T::class_name()
+            ))
+        })
+    }
+}
+
+/// Visitor of the enum variant holding registered class.
+struct PolyVisitor<R>(PhantomData<R>);
+
+impl<'de, R> de::Visitor<'de> for PolyVisitor<R>
+where
+    R: GdPropRegistry,
+{
+    type Value = Gd<Resource>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "one of registered classes: {:?}", R::CLASSES)
+    }
+
+    fn visit_enum<E>(self, data: E) -> Result<Self::Value, E::Error>
+    where
+        E: de::EnumAccess<'de>,
+    {
+        let (PolyClass(class), variant) = data.variant_seed(PolyClassSeed::<R>(PhantomData))?;
+        R::_int_deserialize_poly(class, variant)
+    }
+}
+
+/// Registered class identified by the enum variant.
+struct PolyClass(&'static str);
+
+/// Identifies registered class by the name or index of the enum variant, depending on the format.
+struct PolyClassSeed<R>(PhantomData<R>);
+
+impl<'de, R> de::DeserializeSeed<'de> for PolyClassSeed<R>
+where
+    R: GdPropRegistry,
+{
+    type Value = PolyClass;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de, R> de::Visitor<'de> for PolyClassSeed<R>
+where
+    R: GdPropRegistry,
+{
+    type Value = PolyClass;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("registered class name or index")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        R::CLASSES
+            .iter()
+            .find(|class| **class == value)
+            .map(|class| PolyClass(class))
+            .ok_or_else(|| de::Error::unknown_variant(value, R::CLASSES))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match std::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(de::Error::invalid_value(
+                de::Unexpected::Bytes(value),
+                &self,
+            )),
+        }
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        R::CLASSES
+            .get(value as usize)
+            .map(|class| PolyClass(class))
+            .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+}
+
+impl<T> GdSerializeAs<T> for GdBuiltin
+where
+    T: GdSerdeBuiltin,
+{
+    fn serialize_as<S>(source: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        source.serialize_builtin(serializer)
+    }
+}
+
+impl<'de, T> GdDeserializeAs<'de, T> for GdBuiltin
+where
+    T: GdSerdeBuiltin,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        T::deserialize_builtin(deserializer)
+    }
+}
+
+impl<A, T> GdSerializeAs<Option<T>> for Option<A>
+where
+    A: GdSerializeAs<T>,
+{
+    fn serialize_as<S>(source: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match source {
+            Some(value) => serializer.serialize_some(&SerializeWith::<A, T>::new(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de, A, T> GdDeserializeAs<'de, Option<T>> for Option<A>
+where
+    A: GdDeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let value = Option::<DeserializeWith<A, T>>::deserialize(deserializer)?;
+        Ok(value.and_then(|value| value.0))
+    }
+}
+
+impl<A, T> GdSerializeAs<Vec<T>> for Vec<A>
+where
+    A: GdSerializeAs<T>,
+{
+    fn serialize_as<S>(source: &Vec<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_seq(source.iter().map(SerializeWith::<A, T>::new))
+    }
+}
+
+impl<'de, A, T> GdDeserializeAs<'de, Vec<T>> for Vec<A>
+where
+    A: GdDeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let values = Vec::<DeserializeWith<A, T>>::deserialize(deserializer)?;
+        Ok(values.into_iter().filter_map(|value| value.0).collect())
+    }
+}
+
+impl<A, T> GdSerializeAs<HashSet<T>> for HashSet<A>
+where
+    A: GdSerializeAs<T>,
+{
+    fn serialize_as<S>(source: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_seq(source.iter().map(SerializeWith::<A, T>::new))
+    }
+}
+
+impl<'de, A, T> GdDeserializeAs<'de, HashSet<T>> for HashSet<A>
+where
+    A: GdDeserializeAs<'de, T>,
+    T: Hash + Eq,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<HashSet<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let values = Vec::<DeserializeWith<A, T>>::deserialize(deserializer)?;
+        Ok(values.into_iter().filter_map(|value| value.0).collect())
+    }
+}
+
+impl<K, A, T> GdSerializeAs<HashMap<K, T>> for HashMap<K, A>
+where
+    K: Serialize,
+    A: GdSerializeAs<T>,
+{
+    fn serialize_as<S>(source: &HashMap<K, T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_map(
+            source
+                .iter()
+                .map(|(key, value)| (key, SerializeWith::<A, T>::new(value))),
+        )
+    }
+}
+
+impl<'de, K, A, T> GdDeserializeAs<'de, HashMap<K, T>> for HashMap<K, A>
+where
+    K: Deserialize<'de> + Hash + Eq,
+    A: GdDeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<HashMap<K, T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let values = HashMap::<K, DeserializeWith<A, T>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.0?)))
+            .collect())
+    }
+}
+
+impl<K, A, T> GdSerializeAs<BTreeMap<K, T>> for BTreeMap<K, A>
+where
+    K: Serialize,
+    A: GdSerializeAs<T>,
+{
+    fn serialize_as<S>(source: &BTreeMap<K, T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_map(
+            source
+                .iter()
+                .map(|(key, value)| (key, SerializeWith::<A, T>::new(value))),
+        )
+    }
+}
+
+impl<'de, K, A, T> GdDeserializeAs<'de, BTreeMap<K, T>> for BTreeMap<K, A>
+where
+    K: Deserialize<'de> + Ord,
+    A: GdDeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<BTreeMap<K, T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let values = BTreeMap::<K, DeserializeWith<A, T>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.0?)))
+            .collect())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, A, T> GdSerializeAs<indexmap::IndexMap<K, T>> for indexmap::IndexMap<K, A>
+where
+    K: Serialize,
+    A: GdSerializeAs<T>,
+{
+    fn serialize_as<S>(source: &indexmap::IndexMap<K, T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_map(
+            source
+                .iter()
+                .map(|(key, value)| (key, SerializeWith::<A, T>::new(value))),
+        )
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<'de, K, A, T> GdDeserializeAs<'de, indexmap::IndexMap<K, T>> for indexmap::IndexMap<K, A>
+where
+    K: Deserialize<'de> + Hash + Eq,
+    A: GdDeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<indexmap::IndexMap<K, T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let values = indexmap::IndexMap::<K, DeserializeWith<A, T>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.0?)))
+            .collect())
+    }
+}
+
+impl<A, T> GdSerializeAs<Array<Gd<T>>> for GdArray<A>
+where
+    A: GdSerializeAs<Gd<T>>,
+    T: GodotClass,
+{
+    fn serialize_as<S>(source: &Array<Gd<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let values: Vec<Gd<T>> = source.iter_shared().collect();
+        serializer.collect_seq(values.iter().map(SerializeWith::<A, Gd<T>>::new))
+    }
+}
+
+impl<'de, A, T> GdDeserializeAs<'de, Array<Gd<T>>> for GdArray<A>
+where
+    A: GdDeserializeAs<'de, Gd<T>>,
+    T: GodotClass,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Array<Gd<T>>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let values = Vec::<DeserializeWith<A, Gd<T>>>::deserialize(deserializer)?;
+        Ok(values.into_iter().filter_map(|value| value.0).collect())
+    }
+}
+
+impl<T, A> GdSerializeAs<Dictionary> for GdDictionary<T, A>
+where
+    T: GodotClass,
+    A: GdSerializeAs<Gd<T>>,
+{
+    fn serialize_as<S>(source: &Dictionary, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let entries = source
+            .iter_shared()
+            .map(|(key, value)| {
+                if !matches!(
+                    key.get_type(),
+                    VariantType::STRING | VariantType::STRING_NAME
+                ) {
+                    return Err(ser::Error::custom(format!(
+                        "key {key} of type {:?} is not a string",
+                        key.get_type()
+                    )));
+                }
+                let value = value.try_to::<Gd<T>>().map_err(|_| {
+                    ser::Error::custom(format!(
+                        "value under key {key} is not of class {}",
+                        T::class_name()
+                    ))
+                })?;
+                Ok((key.to_string(), value))
+            })
+            .collect::<Result<Vec<_>, S::Error>>()?;
+        serializer.collect_map(
+            entries
+                .iter()
+                .map(|(key, value)| (key, SerializeWith::<A, Gd<T>>::new(value))),
+        )
+    }
+}
+
+impl<'de, T, A> GdDeserializeAs<'de, Dictionary> for GdDictionary<T, A>
+where
+    T: GodotClass,
+    A: GdDeserializeAs<'de, Gd<T>>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Dictionary, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(DictionaryVisitor::<T, A>(PhantomData))
+    }
+}
+
+/// Visitor keeping the order of [`Dictionary`] entries.
+struct DictionaryVisitor<T, A>(PhantomData<(T, A)>);
+
+impl<'de, T, A> de::Visitor<'de> for DictionaryVisitor<T, A>
+where
+    T: GodotClass,
+    A: GdDeserializeAs<'de, Gd<T>>,
+{
+    type Value = Dictionary;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of Godot objects")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: de::MapAccess<'de>,
+    {
+        let mut dictionary = Dictionary::new();
+        while let Some((key, value)) = map.next_entry::<String, DeserializeWith<A, Gd<T>>>()? {
+            if let Some(value) = value.0 {
+                dictionary.set(GString::from(key), value);
+            }
+        }
+        Ok(dictionary)
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::{BufWriter, Write};
+
+use godot::builtin::GString;
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{DirAccess, FileAccess};
+use godot::global::Error;
+use godot::tools::GFile;
+
+use crate::errors::GdPropError;
+
+/// Write the file located at `path` through a sibling temporary file, which replaces the target only after `write`
+/// succeeded, so a crash mid-save never leaves the target half-written.
+///
+/// If `backups` is greater than `0`, previous content of the target is kept in rotating `.bak` files, see
+/// [`backup_path`].
+pub(crate) fn write_atomic<F>(path: &GString, backups: u32, write: F) -> Result<(), GdPropError>
+where
+    F: FnOnce(&mut BufWriter<GFile>) -> Result<(), GdPropError>,
+{
+    let temp_path = GString::from(format!("{}.tmp", path));
+
+    let written = GFile::open(temp_path.clone(), ModeFlags::WRITE)
+        .map_err(GdPropError::FileWrite)
+        .and_then(|gfile| {
+            let mut writer = BufWriter::new(gfile);
+            write(&mut writer)?;
+            writer.flush().map_err(GdPropError::FileWrite)
+        });
+    if let Err(error) = written {
+        DirAccess::remove_absolute(temp_path);
+        return Err(error);
+    }
+
+    if backups > 0 && FileAccess::file_exists(path.clone()) {
+        rotate_backups(path, backups)?;
+    }
+    move_file(&temp_path, path)
+}
+
+/// Path of the `index`-th backup of the file located at `path`, eg. `save.gdbin.1.bak`. Backup with index `1` is the
+/// most recent one.
+fn backup_path(path: &GString, index: u32) -> GString {
+    GString::from(format!("{}.{}.bak", path, index))
+}
+
+/// Shift existing backups by one, dropping the oldest, and copy the current content of the file as the most recent.
+fn rotate_backups(path: &GString, backups: u32) -> Result<(), GdPropError> {
+    for index in (1..backups).rev() {
+        let older = backup_path(path, index);
+        if FileAccess::file_exists(older.clone()) {
+            move_file(&older, &backup_path(path, index + 1))?;
+        }
+    }
+
+    let recent = backup_path(path, 1);
+    match DirAccess::copy_absolute(path.clone(), recent.clone()) {
+        Error::OK => Ok(()),
+        error => Err(GdPropError::FileMove {
+            from: path.to_string(),
+            to: recent.to_string(),
+            error,
+        }),
+    }
+}
+
+fn move_file(from: &GString, to: &GString) -> Result<(), GdPropError> {
+    match DirAccess::rename_absolute(from.clone(), to.clone()) {
+        Error::OK => Ok(()),
+        error => Err(GdPropError::FileMove {
+            from: from.to_string(),
+            to: to.to_string(),
+            error,
+        }),
+    }
+}
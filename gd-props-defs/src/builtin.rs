@@ -0,0 +1,602 @@
+use godot::builtin::{
+    Aabb, Basis, Color, Dictionary, GString, NodePath, PackedByteArray, PackedColorArray,
+    PackedFloat32Array, PackedFloat64Array, PackedInt32Array, PackedInt64Array, PackedStringArray,
+    PackedVector2Array, PackedVector3Array, Plane, Projection, Quaternion, Rect2, Rect2i,
+    StringName, Transform2D, Transform3D, Variant, VariantArray, Vector2, Vector2i, Vector3,
+    Vector3i, Vector4, Vector4i,
+};
+use serde::{de, ser, Deserialize, Serialize};
+
+/// Godot builtin type which can be serialized with [`serde_gd::builtin`](crate::serde_gd::builtin) module.
+///
+/// Math types are serialized as structs with their fields, eg. `(x: 1.0, y: 2.0)` for [`Vector2`] in `.gdron` file,
+/// string types as strings and packed arrays as sequences of their elements. [`Variant`] is serialized as an enum
+/// variant named after the type it holds, eg. `Int(5)` or `Vector2((x: 1.0, y: 2.0))`. [`VariantArray`] is serialized
+/// as a sequence of such variants and [`Dictionary`] as a sequence of their key-value pairs. Variants holding
+/// objects, callables, signals and RIDs can't be serialized.
+pub trait GdSerdeBuiltin: Sized {
+    fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer;
+
+    fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>;
+}
+
+/// Serializable mirrors of Godot builtin types.
+pub(crate) mod repr {
+    use godot::builtin::meta::ToGodot;
+    use godot::builtin::{self, real, VariantType};
+    use serde::{Deserialize, Serialize};
+
+    /// Mirror of the type with named fields, converted field by field.
+    macro_rules! mirror {
+        ($name:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+            #[derive(Serialize, Deserialize)]
+            pub(crate) struct $name {
+                $(pub $field: $ty,)*
+            }
+
+            impl From<builtin::$name> for $name {
+                fn from(value: builtin::$name) -> Self {
+                    Self { $($field: value.$field.into(),)* }
+                }
+            }
+
+            impl From<$name> for builtin::$name {
+                fn from(value: $name) -> Self {
+                    Self { $($field: value.$field.into(),)* }
+                }
+            }
+        };
+    }
+
+    mirror!(Vector2 { x: real, y: real });
+    mirror!(Vector2i { x: i32, y: i32 });
+    mirror!(Vector3 {
+        x: real,
+        y: real,
+        z: real
+    });
+    mirror!(Vector3i {
+        x: i32,
+        y: i32,
+        z: i32
+    });
+    mirror!(Vector4 {
+        x: real,
+        y: real,
+        z: real,
+        w: real
+    });
+    mirror!(Vector4i {
+        x: i32,
+        y: i32,
+        z: i32,
+        w: i32
+    });
+    mirror!(Quaternion {
+        x: real,
+        y: real,
+        z: real,
+        w: real
+    });
+    mirror!(Color {
+        r: f32,
+        g: f32,
+        b: f32,
+        a: f32
+    });
+    mirror!(Rect2 {
+        position: Vector2,
+        size: Vector2
+    });
+    mirror!(Rect2i {
+        position: Vector2i,
+        size: Vector2i
+    });
+    mirror!(Aabb {
+        position: Vector3,
+        size: Vector3
+    });
+    mirror!(Plane {
+        normal: Vector3,
+        d: real
+    });
+    mirror!(Transform2D {
+        a: Vector2,
+        b: Vector2,
+        origin: Vector2
+    });
+    mirror!(Transform3D {
+        basis: Basis,
+        origin: Vector3
+    });
+
+    #[derive(Serialize, Deserialize)]
+    pub(crate) struct Basis {
+        pub rows: [Vector3; 3],
+    }
+
+    impl From<builtin::Basis> for Basis {
+        fn from(value: builtin::Basis) -> Self {
+            Self {
+                rows: value.rows.map(Into::into),
+            }
+        }
+    }
+
+    impl From<Basis> for builtin::Basis {
+        fn from(value: Basis) -> Self {
+            Self {
+                rows: value.rows.map(Into::into),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub(crate) struct Projection {
+        pub cols: [Vector4; 4],
+    }
+
+    impl From<builtin::Projection> for Projection {
+        fn from(value: builtin::Projection) -> Self {
+            Self {
+                cols: value.cols.map(Into::into),
+            }
+        }
+    }
+
+    impl From<Projection> for builtin::Projection {
+        fn from(value: Projection) -> Self {
+            Self {
+                cols: value.cols.map(Into::into),
+            }
+        }
+    }
+
+    /// Mirror of [`builtin::Variant`], tagged with the type it holds.
+    #[derive(Serialize, Deserialize)]
+    pub(crate) enum Variant {
+        Nil,
+        Bool(bool),
+        Int(i64),
+        Float(f64),
+        String(String),
+        StringName(String),
+        NodePath(String),
+        Vector2(Vector2),
+        Vector2i(Vector2i),
+        Vector3(Vector3),
+        Vector3i(Vector3i),
+        Vector4(Vector4),
+        Vector4i(Vector4i),
+        Quaternion(Quaternion),
+        Color(Color),
+        Rect2(Rect2),
+        Rect2i(Rect2i),
+        Aabb(Aabb),
+        Plane(Plane),
+        Basis(Basis),
+        Transform2D(Transform2D),
+        Transform3D(Transform3D),
+        Projection(Projection),
+        Array(Vec<Variant>),
+        Dictionary(Vec<(Variant, Variant)>),
+        PackedByteArray(Vec<u8>),
+        PackedInt32Array(Vec<i32>),
+        PackedInt64Array(Vec<i64>),
+        PackedFloat32Array(Vec<f32>),
+        PackedFloat64Array(Vec<f64>),
+        PackedStringArray(Vec<String>),
+        PackedVector2Array(Vec<Vector2>),
+        PackedVector3Array(Vec<Vector3>),
+        PackedColorArray(Vec<Color>),
+    }
+
+    /// Elements of the array, converted to their mirrors.
+    pub(crate) fn array(array: &builtin::VariantArray) -> Result<Vec<Variant>, String> {
+        array
+            .iter_shared()
+            .map(|element| Variant::try_from(&element))
+            .collect()
+    }
+
+    /// Entries of the dictionary, converted to their mirrors.
+    pub(crate) fn dictionary(
+        dictionary: &builtin::Dictionary,
+    ) -> Result<Vec<(Variant, Variant)>, String> {
+        dictionary
+            .iter_shared()
+            .map(|(key, value)| Ok((Variant::try_from(&key)?, Variant::try_from(&value)?)))
+            .collect()
+    }
+
+    pub(crate) fn to_array(elements: Vec<Variant>) -> builtin::VariantArray {
+        elements.into_iter().map(builtin::Variant::from).collect()
+    }
+
+    pub(crate) fn to_dictionary(entries: Vec<(Variant, Variant)>) -> builtin::Dictionary {
+        let mut dictionary = builtin::Dictionary::new();
+        for (key, value) in entries {
+            dictionary.set(builtin::Variant::from(key), builtin::Variant::from(value));
+        }
+        dictionary
+    }
+
+    /// Elements of the packed array, converted to their mirrors.
+    pub(crate) fn elements<T: Copy, R: From<T>>(slice: &[T]) -> Vec<R> {
+        slice.iter().copied().map(R::from).collect()
+    }
+
+    /// Packed array of the mirrored elements.
+    pub(crate) fn packed<T, R, P>(elements: Vec<R>) -> P
+    where
+        T: From<R>,
+        P: for<'a> From<&'a [T]>,
+    {
+        let elements: Vec<T> = elements.into_iter().map(T::from).collect();
+        P::from(elements.as_slice())
+    }
+
+    impl TryFrom<&builtin::Variant> for Variant {
+        type Error = String;
+
+        fn try_from(variant: &builtin::Variant) -> Result<Self, Self::Error> {
+            let mirror = match variant.get_type() {
+                VariantType::NIL => Variant::Nil,
+                VariantType::BOOL => Variant::Bool(variant.to()),
+                VariantType::INT => Variant::Int(variant.to()),
+                VariantType::FLOAT => Variant::Float(variant.to()),
+                VariantType::STRING => {
+                    Variant::String(variant.to::<builtin::GString>().to_string())
+                }
+                VariantType::STRING_NAME => {
+                    Variant::StringName(variant.to::<builtin::StringName>().to_string())
+                }
+                VariantType::NODE_PATH => {
+                    Variant::NodePath(variant.to::<builtin::NodePath>().to_string())
+                }
+                VariantType::VECTOR2 => Variant::Vector2(variant.to::<builtin::Vector2>().into()),
+                VariantType::VECTOR2I => {
+                    Variant::Vector2i(variant.to::<builtin::Vector2i>().into())
+                }
+                VariantType::VECTOR3 => Variant::Vector3(variant.to::<builtin::Vector3>().into()),
+                VariantType::VECTOR3I => {
+                    Variant::Vector3i(variant.to::<builtin::Vector3i>().into())
+                }
+                VariantType::VECTOR4 => Variant::Vector4(variant.to::<builtin::Vector4>().into()),
+                VariantType::VECTOR4I => {
+                    Variant::Vector4i(variant.to::<builtin::Vector4i>().into())
+                }
+                VariantType::QUATERNION => {
+                    Variant::Quaternion(variant.to::<builtin::Quaternion>().into())
+                }
+                VariantType::COLOR => Variant::Color(variant.to::<builtin::Color>().into()),
+                VariantType::RECT2 => Variant::Rect2(variant.to::<builtin::Rect2>().into()),
+                VariantType::RECT2I => Variant::Rect2i(variant.to::<builtin::Rect2i>().into()),
+                VariantType::AABB => Variant::Aabb(variant.to::<builtin::Aabb>().into()),
+                VariantType::PLANE => Variant::Plane(variant.to::<builtin::Plane>().into()),
+                VariantType::BASIS => Variant::Basis(variant.to::<builtin::Basis>().into()),
+                VariantType::TRANSFORM2D => {
+                    Variant::Transform2D(variant.to::<builtin::Transform2D>().into())
+                }
+                VariantType::TRANSFORM3D => {
+                    Variant::Transform3D(variant.to::<builtin::Transform3D>().into())
+                }
+                VariantType::PROJECTION => {
+                    Variant::Projection(variant.to::<builtin::Projection>().into())
+                }
+                VariantType::ARRAY => Variant::Array(array(&variant.to())?),
+                VariantType::DICTIONARY => Variant::Dictionary(dictionary(&variant.to())?),
+                VariantType::PACKED_BYTE_ARRAY => Variant::PackedByteArray(
+                    variant.to::<builtin::PackedByteArray>().as_slice().to_vec(),
+                ),
+                VariantType::PACKED_INT32_ARRAY => Variant::PackedInt32Array(
+                    variant
+                        .to::<builtin::PackedInt32Array>()
+                        .as_slice()
+                        .to_vec(),
+                ),
+                VariantType::PACKED_INT64_ARRAY => Variant::PackedInt64Array(
+                    variant
+                        .to::<builtin::PackedInt64Array>()
+                        .as_slice()
+                        .to_vec(),
+                ),
+                VariantType::PACKED_FLOAT32_ARRAY => Variant::PackedFloat32Array(
+                    variant
+                        .to::<builtin::PackedFloat32Array>()
+                        .as_slice()
+                        .to_vec(),
+                ),
+                VariantType::PACKED_FLOAT64_ARRAY => Variant::PackedFloat64Array(
+                    variant
+                        .to::<builtin::PackedFloat64Array>()
+                        .as_slice()
+                        .to_vec(),
+                ),
+                VariantType::PACKED_STRING_ARRAY => Variant::PackedStringArray(
+                    variant
+                        .to::<builtin::PackedStringArray>()
+                        .as_slice()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                ),
+                VariantType::PACKED_VECTOR2_ARRAY => Variant::PackedVector2Array(elements(
+                    variant.to::<builtin::PackedVector2Array>().as_slice(),
+                )),
+                VariantType::PACKED_VECTOR3_ARRAY => Variant::PackedVector3Array(elements(
+                    variant.to::<builtin::PackedVector3Array>().as_slice(),
+                )),
+                VariantType::PACKED_COLOR_ARRAY => Variant::PackedColorArray(elements(
+                    variant.to::<builtin::PackedColorArray>().as_slice(),
+                )),
+                other => return Err(format!("Variant of type {other:?} can't be serialized")),
+            };
+            Ok(mirror)
+        }
+    }
+
+    impl From<Variant> for builtin::Variant {
+        fn from(mirror: Variant) -> Self {
+            match mirror {
+                Variant::Nil => builtin::Variant::nil(),
+                Variant::Bool(value) => value.to_variant(),
+                Variant::Int(value) => value.to_variant(),
+                Variant::Float(value) => value.to_variant(),
+                Variant::String(value) => builtin::GString::from(value).to_variant(),
+                Variant::StringName(value) => builtin::StringName::from(value).to_variant(),
+                Variant::NodePath(value) => builtin::NodePath::from(value).to_variant(),
+                Variant::Vector2(value) => builtin::Vector2::from(value).to_variant(),
+                Variant::Vector2i(value) => builtin::Vector2i::from(value).to_variant(),
+                Variant::Vector3(value) => builtin::Vector3::from(value).to_variant(),
+                Variant::Vector3i(value) => builtin::Vector3i::from(value).to_variant(),
+                Variant::Vector4(value) => builtin::Vector4::from(value).to_variant(),
+                Variant::Vector4i(value) => builtin::Vector4i::from(value).to_variant(),
+                Variant::Quaternion(value) => builtin::Quaternion::from(value).to_variant(),
+                Variant::Color(value) => builtin::Color::from(value).to_variant(),
+                Variant::Rect2(value) => builtin::Rect2::from(value).to_variant(),
+                Variant::Rect2i(value) => builtin::Rect2i::from(value).to_variant(),
+                Variant::Aabb(value) => builtin::Aabb::from(value).to_variant(),
+                Variant::Plane(value) => builtin::Plane::from(value).to_variant(),
+                Variant::Basis(value) => builtin::Basis::from(value).to_variant(),
+                Variant::Transform2D(value) => builtin::Transform2D::from(value).to_variant(),
+                Variant::Transform3D(value) => builtin::Transform3D::from(value).to_variant(),
+                Variant::Projection(value) => builtin::Projection::from(value).to_variant(),
+                Variant::Array(elements) => to_array(elements).to_variant(),
+                Variant::Dictionary(entries) => to_dictionary(entries).to_variant(),
+                Variant::PackedByteArray(elements) => {
+                    builtin::PackedByteArray::from(elements.as_slice()).to_variant()
+                }
+                Variant::PackedInt32Array(elements) => {
+                    builtin::PackedInt32Array::from(elements.as_slice()).to_variant()
+                }
+                Variant::PackedInt64Array(elements) => {
+                    builtin::PackedInt64Array::from(elements.as_slice()).to_variant()
+                }
+                Variant::PackedFloat32Array(elements) => {
+                    builtin::PackedFloat32Array::from(elements.as_slice()).to_variant()
+                }
+                Variant::PackedFloat64Array(elements) => {
+                    builtin::PackedFloat64Array::from(elements.as_slice()).to_variant()
+                }
+                Variant::PackedStringArray(elements) => {
+                    packed::<builtin::GString, _, builtin::PackedStringArray>(elements).to_variant()
+                }
+                Variant::PackedVector2Array(elements) => {
+                    packed::<builtin::Vector2, _, builtin::PackedVector2Array>(elements)
+                        .to_variant()
+                }
+                Variant::PackedVector3Array(elements) => {
+                    packed::<builtin::Vector3, _, builtin::PackedVector3Array>(elements)
+                        .to_variant()
+                }
+                Variant::PackedColorArray(elements) => {
+                    packed::<builtin::Color, _, builtin::PackedColorArray>(elements).to_variant()
+                }
+            }
+        }
+    }
+}
+
+/// Builtin types serialized through their mirrors in [`repr`] module.
+macro_rules! impl_mirrored {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl GdSerdeBuiltin for $name {
+                fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ser::Serializer,
+                {
+                    repr::$name::from(*self).serialize(serializer)
+                }
+
+                fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    repr::$name::deserialize(deserializer).map(Self::from)
+                }
+            }
+        )*
+    };
+}
+
+impl_mirrored!(
+    Vector2,
+    Vector2i,
+    Vector3,
+    Vector3i,
+    Vector4,
+    Vector4i,
+    Quaternion,
+    Color,
+    Rect2,
+    Rect2i,
+    Aabb,
+    Plane,
+    Basis,
+    Transform2D,
+    Transform3D,
+    Projection,
+);
+
+/// String types, serialized as strings.
+macro_rules! impl_string {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl GdSerdeBuiltin for $name {
+                fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ser::Serializer,
+                {
+                    serializer.serialize_str(&self.to_string())
+                }
+
+                fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    String::deserialize(deserializer).map(Self::from)
+                }
+            }
+        )*
+    };
+}
+
+impl_string!(GString, StringName, NodePath);
+
+/// Packed arrays of primitive elements, serialized as sequences.
+macro_rules! impl_packed {
+    ($($name:ident: $element:ty),* $(,)?) => {
+        $(
+            impl GdSerdeBuiltin for $name {
+                fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ser::Serializer,
+                {
+                    serializer.collect_seq(self.as_slice())
+                }
+
+                fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    Vec::<$element>::deserialize(deserializer)
+                        .map(|elements| Self::from(elements.as_slice()))
+                }
+            }
+        )*
+    };
+}
+
+impl_packed!(
+    PackedByteArray: u8,
+    PackedInt32Array: i32,
+    PackedInt64Array: i64,
+    PackedFloat32Array: f32,
+    PackedFloat64Array: f64,
+);
+
+/// Packed arrays of builtin elements, serialized as sequences of their mirrors.
+macro_rules! impl_packed_mirrored {
+    ($($name:ident: $element:ident),* $(,)?) => {
+        $(
+            impl GdSerdeBuiltin for $name {
+                fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ser::Serializer,
+                {
+                    repr::elements::<_, repr::$element>(self.as_slice()).serialize(serializer)
+                }
+
+                fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    Vec::<repr::$element>::deserialize(deserializer)
+                        .map(repr::packed::<$element, _, Self>)
+                }
+            }
+        )*
+    };
+}
+
+impl_packed_mirrored!(
+    PackedVector2Array: Vector2,
+    PackedVector3Array: Vector3,
+    PackedColorArray: Color,
+);
+
+impl GdSerdeBuiltin for PackedStringArray {
+    fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_seq(self.as_slice().iter().map(ToString::to_string))
+    }
+
+    fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer).map(repr::packed::<GString, _, Self>)
+    }
+}
+
+impl GdSerdeBuiltin for Variant {
+    fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        repr::Variant::try_from(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        repr::Variant::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl GdSerdeBuiltin for VariantArray {
+    fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        repr::array(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Vec::<repr::Variant>::deserialize(deserializer).map(repr::to_array)
+    }
+}
+
+impl GdSerdeBuiltin for Dictionary {
+    fn serialize_builtin<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        repr::dictionary(self)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    fn deserialize_builtin<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Vec::<(repr::Variant, repr::Variant)>::deserialize(deserializer).map(repr::to_dictionary)
+    }
+}
@@ -0,0 +1,592 @@
+use std::io::{BufRead, Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::errors::GdPropError;
+use crate::gdprop::GdProp;
+use crate::migration::GdPropPayload;
+use crate::subresource::GdSubResources;
+
+pub use crate::compression::GdPropCompression;
+pub use crate::gd_meta::{GdMetaExt, GdMetaHeader};
+pub use crate::integrity::GdPropChecksum;
+pub use crate::security::{GdPropKey, GdPropSeal};
+
+/// Format of `gd-props` file, recognized by its extension.
+///
+/// Every format is handled by its own [`GdPropCodec`]. Besides always available `.gdron` and `.gdbin`, additional
+/// formats are enabled with cargo features:
+///
+/// | format        | extension | feature    | codec              |
+/// |---------------|-----------|------------|--------------------|
+/// | [ron]         | `.gdron`  |            | [`RonCodec`]       |
+/// | MessagePack   | `.gdbin`  |            | [`BinCodec`]       |
+/// | JSON          | `.gdjson` | `json`     | `JsonCodec`        |
+/// | CBOR          | `.gdcbor` | `cbor`     | `CborCodec`        |
+/// | bincode       | `.gdbc`   | `bincode`  | `BincodeCodec`     |
+/// | postcard      | `.gdpc`   | `postcard` | `PostcardCodec`    |
+///
+/// Formats handled by the plugin are selected with `#[formats]` attribute of `#[gd_props_plugin]` macro.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum GdPropFormat {
+    GdRon,
+    GdBin,
+    #[cfg(feature = "json")]
+    GdJson,
+    #[cfg(feature = "cbor")]
+    GdCbor,
+    #[cfg(feature = "bincode")]
+    GdBincode,
+    #[cfg(feature = "postcard")]
+    GdPostcard,
+    None,
+}
+
+impl GdPropFormat {
+    /// Formats handled by the plugin if none were selected.
+    pub const DEFAULT: &'static [GdPropFormat] = &[GdPropFormat::GdRon, GdPropFormat::GdBin];
+
+    /// All formats enabled with cargo features.
+    pub const ALL: &'static [GdPropFormat] = &[
+        GdPropFormat::GdRon,
+        GdPropFormat::GdBin,
+        #[cfg(feature = "json")]
+        GdPropFormat::GdJson,
+        #[cfg(feature = "cbor")]
+        GdPropFormat::GdCbor,
+        #[cfg(feature = "bincode")]
+        GdPropFormat::GdBincode,
+        #[cfg(feature = "postcard")]
+        GdPropFormat::GdPostcard,
+    ];
+
+    /// Extension of the files in given format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GdPropFormat::GdRon => RonCodec::EXTENSION,
+            GdPropFormat::GdBin => BinCodec::EXTENSION,
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => JsonCodec::EXTENSION,
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => CborCodec::EXTENSION,
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => BincodeCodec::EXTENSION,
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => PostcardCodec::EXTENSION,
+            GdPropFormat::None => "",
+        }
+    }
+
+    /// Recognize the format of file located at `path` among all enabled formats.
+    pub fn recognize_format(path: &str) -> Self {
+        Self::recognize_among(path, Self::ALL)
+    }
+
+    /// Recognize the format of file located at `path` among given `formats`.
+    pub fn recognize_among(path: &str, formats: &[GdPropFormat]) -> Self {
+        formats
+            .iter()
+            .copied()
+            .find(|format| {
+                path.strip_suffix(format.extension())
+                    .is_some_and(|stem| stem.ends_with('.'))
+            })
+            .unwrap_or(GdPropFormat::None)
+    }
+}
+
+/// Encoding and decoding of `gd-props` files, independent of Godot engine.
+///
+/// Methods operate on any [`std::io`] reader or writer, so they can be used in plain `cargo test`, build scripts or
+/// server tools. Godot-bound methods of [`GdProp`] are built on top of them.
+///
+/// ## Example
+/// ```no_run
+/// use gd_props::codec::{BinCodec, GdMetaHeader, GdPropCodec, RonCodec};
+/// # use gd_props::GdProp;
+/// # use godot::prelude::GodotClass;
+/// # use serde::{Serialize, Deserialize};
+/// # #[derive(GodotClass, GdProp, Serialize, Deserialize)]
+/// # #[class(init, base=Resource)]
+/// # struct MyResource { value: u32 }
+///
+/// let ron_file = std::fs::read("my_resource.gdron").unwrap();
+/// let (header, resource) = RonCodec::decode::<MyResource, _>(&mut ron_file.as_slice()).unwrap();
+///
+/// let mut bin_file = Vec::new();
+/// BinCodec::encode(&header, &resource, &mut bin_file).unwrap();
+/// ```
+pub trait GdPropCodec {
+    /// Extension of the files handled by the codec.
+    const EXTENSION: &'static str;
+
+    /// Whether [`GdPropChecksum`] of the payload is saved in the header. Disabled for formats meant to be edited by
+    /// hand.
+    const CHECKSUM: bool = true;
+
+    /// Whether the payload is compressed with [`GdProp::COMPRESSION`] of the saved resource. Disabled for formats meant
+    /// to be edited by hand.
+    const COMPRESSED: bool = true;
+
+    /// Write the file header.
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError>;
+
+    /// Read the file header. Reader is left at the beginning of the payload.
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError>;
+
+    /// Write the serialized `value` as payload.
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError>;
+
+    /// Deserialize the payload into `T`.
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError>;
+
+    /// Read the payload without deserializing it.
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError>;
+
+    /// Write whole file: the `header` followed by the serialized `value`, compressed as flagged in the `header`.
+    fn encode<T: Serialize, W: Write>(
+        header: &GdMetaHeader,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), GdPropError> {
+        Self::encode_with_key(header, value, None, writer)
+    }
+
+    /// Write whole file, sealing the payload with the `key` if provided. Seal and checksum of the `header` are replaced
+    /// by the new ones.
+    fn encode_with_key<T: Serialize, W: Write>(
+        header: &GdMetaHeader,
+        value: &T,
+        key: Option<&GdPropKey>,
+        writer: &mut W,
+    ) -> Result<(), GdPropError> {
+        let mut header = header.clone();
+        header.seal = None;
+        header.checksum = None;
+
+        if header.compression.is_none() && key.is_none() && !Self::CHECKSUM {
+            Self::write_header(&header, writer)?;
+            GdSubResources::scope(|| Self::write_body(value, writer))?;
+        } else {
+            let mut payload = Vec::new();
+            GdSubResources::scope(|| Self::write_body(value, &mut payload))?;
+            Self::write_sealed(&header, &payload, key, writer)?;
+        }
+        writer.flush().map_err(GdPropError::FileWrite)
+    }
+
+    /// Write the `header` followed by already serialized `payload`, compressed as flagged in the `header` and sealed
+    /// with the `key` if provided.
+    fn write_sealed<W: Write>(
+        header: &GdMetaHeader,
+        payload: &[u8],
+        key: Option<&GdPropKey>,
+        writer: &mut W,
+    ) -> Result<(), GdPropError> {
+        let mut header = header.clone();
+        header.seal = None;
+        let mut payload = header.compression.compress(payload)?;
+        if let Some(key) = key {
+            payload = key.seal(&mut header, payload)?;
+        }
+        Self::write_parts(&header, &payload, writer)
+    }
+
+    /// Read the header and the payload ready to be deserialized: with its checksum and seal verified with the `key`,
+    /// and decompressed.
+    fn read_opened<R: BufRead>(
+        reader: &mut R,
+        key: Option<&GdPropKey>,
+    ) -> Result<(GdMetaHeader, Vec<u8>), GdPropError> {
+        let header = Self::read_header(reader)?;
+        let mut stored = Vec::new();
+        reader
+            .read_to_end(&mut stored)
+            .map_err(GdPropError::FileRead)?;
+        let compressed = Self::open_stored(&header, stored, key)?;
+        let payload = header.compression.decompress(&compressed)?;
+        Ok((header, payload))
+    }
+
+    /// Verify the `stored` payload of the file with given `header`, returning it without the seal. Sealed payloads
+    /// are verified with their seal first, so tampering is reported as such rather than as damaged file; checksum is
+    /// verified only for unsealed ones.
+    fn open_stored(
+        header: &GdMetaHeader,
+        stored: Vec<u8>,
+        key: Option<&GdPropKey>,
+    ) -> Result<Vec<u8>, GdPropError> {
+        if header.seal.is_none() {
+            header.verify_checksum(&stored)?;
+        }
+        GdPropSeal::open(header, stored, key)
+    }
+
+    /// Read whole file containing [`GdProp`] resource. The payload is decompressed if flagged in the header and
+    /// migrated if it was saved with older [`GdProp::VERSION`].
+    fn decode<T: GdProp, R: BufRead>(reader: &mut R) -> Result<(GdMetaHeader, T), GdPropError> {
+        Self::decode_with_key(reader, None)
+    }
+
+    /// Read whole file containing [`GdProp`] resource, verifying its checksum and its seal with the `key`. See
+    /// [`GdPropSeal::open`] for details.
+    fn decode_with_key<T: GdProp, R: BufRead>(
+        reader: &mut R,
+        key: Option<&GdPropKey>,
+    ) -> Result<(GdMetaHeader, T), GdPropError> {
+        let header = Self::read_header(reader)?;
+        header.check_class(T::HEAD_IDENT)?;
+
+        let value = if header.compression.is_none()
+            && header.seal.is_none()
+            && header.checksum.is_none()
+            && key.is_none()
+        {
+            Self::decode_body(&header, reader)?
+        } else {
+            let mut stored = Vec::new();
+            reader
+                .read_to_end(&mut stored)
+                .map_err(GdPropError::FileRead)?;
+            let compressed = Self::open_stored(&header, stored, key)?;
+            let payload = header.compression.decompress(&compressed)?;
+            Self::decode_body(&header, &mut payload.as_slice())?
+        };
+        Ok((header, value))
+    }
+
+    /// Deserialize uncompressed payload of the file with given `header`, migrating it if needed.
+    fn decode_body<T: GdProp, R: BufRead>(
+        header: &GdMetaHeader,
+        reader: &mut R,
+    ) -> Result<T, GdPropError> {
+        GdSubResources::scope(|| {
+            if header.version == T::VERSION {
+                Self::read_body(reader)
+            } else {
+                T::_int_from_payload(header.version, Self::read_payload(reader)?)
+            }
+        })
+    }
+
+    /// Change compression of the whole `file` content, keeping the rest of the header and the payload intact. Sealed
+    /// files can't be recompressed.
+    fn recompress(file: &[u8], compression: GdPropCompression) -> Result<Vec<u8>, GdPropError> {
+        let (mut header, stored) = Self::read_parts(&mut &file[..])?;
+        let compressed = GdPropSeal::open(&header, stored, None)?;
+        let payload = header.compression.decompress(&compressed)?;
+
+        header.compression = compression;
+        let mut recompressed = Vec::new();
+        Self::write_parts(&header, &compression.compress(&payload)?, &mut recompressed)?;
+        Ok(recompressed)
+    }
+
+    /// Write the `header` followed by the `payload` as stored in the file: already compressed and sealed. Checksum of
+    /// the `payload` is saved in the header if the codec uses [`GdPropCodec::CHECKSUM`].
+    fn write_parts<W: Write>(
+        header: &GdMetaHeader,
+        payload: &[u8],
+        writer: &mut W,
+    ) -> Result<(), GdPropError> {
+        let mut header = header.clone();
+        header.checksum = Self::CHECKSUM.then(|| GdPropChecksum::of(payload));
+        Self::write_header(&header, writer)?;
+        writer.write_all(payload).map_err(GdPropError::FileWrite)
+    }
+
+    /// Read the header and the payload as stored in the file, verifying its checksum if present in the header.
+    fn read_parts<R: BufRead>(reader: &mut R) -> Result<(GdMetaHeader, Vec<u8>), GdPropError> {
+        let header = Self::read_header(reader)?;
+        let mut payload = Vec::new();
+        reader
+            .read_to_end(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        header.verify_checksum(&payload)?;
+        Ok((header, payload))
+    }
+}
+
+/// Codec of `.gdron` files: header in a single line, followed by pretty-printed [ron] payload.
+pub struct RonCodec;
+
+impl GdPropCodec for RonCodec {
+    const EXTENSION: &'static str = "gdron";
+    const CHECKSUM: bool = false;
+    const COMPRESSED: bool = false;
+
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError> {
+        let serialized = ron::to_string(header).map_err(|_| GdPropError::HeaderSerialize)?;
+        writeln!(writer, "{}", serialized).map_err(GdPropError::FileWrite)
+    }
+
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError> {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(GdPropError::FileRead)?;
+        ron::from_str(&line).map_err(GdPropError::HeaderDeserialize)
+    }
+
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError> {
+        ron::ser::to_writer_pretty(writer, value, ron::ser::PrettyConfig::default())
+            .map_err(GdPropError::RonSerialize)
+    }
+
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError> {
+        ron::de::from_reader(reader).map_err(GdPropError::RonDeserialize)
+    }
+
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError> {
+        let mut payload = String::new();
+        reader
+            .read_to_string(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        Ok(GdPropPayload::Ron(payload))
+    }
+}
+
+/// Codec of `.gdbin` files: header with class and UID stored as pascal strings, followed by [MessagePack](rmp_serde)
+/// payload.
+pub struct BinCodec;
+
+impl BinCodec {
+    /// Byte marking the extended header, written right after the `uid`. It is never used in MessagePack, so it can't
+    /// be mistaken for the beginning of the payload in files saved without the extended header.
+    const EXT_MARKER: u8 = 0xc1;
+
+    fn write_pascal_string<W: Write>(value: &str, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&(value.len() as u32).to_le_bytes())?;
+        writer.write_all(value.as_bytes())
+    }
+
+    fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+        let mut bytes = [0; 4];
+        reader.read_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_pascal_string<R: Read>(reader: &mut R) -> std::io::Result<String> {
+        let len = Self::read_u32(reader)?;
+        let mut bytes = vec![0; len as usize];
+        reader.read_exact(&mut bytes)?;
+        String::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl GdPropCodec for BinCodec {
+    const EXTENSION: &'static str = "gdbin";
+
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError> {
+        let ext = rmp_serde::to_vec_named(header).map_err(|_| GdPropError::HeaderSerialize)?;
+
+        Self::write_pascal_string(&header.gd_class, writer)
+            .and_then(|_| Self::write_pascal_string(&header.uid, writer))
+            .and_then(|_| writer.write_all(&[Self::EXT_MARKER]))
+            .and_then(|_| writer.write_all(&(ext.len() as u32).to_le_bytes()))
+            .and_then(|_| writer.write_all(&ext))
+            .map_err(GdPropError::FileWrite)
+    }
+
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError> {
+        let gd_class = Self::read_pascal_string(reader).map_err(GdPropError::FileRead)?;
+        let uid = Self::read_pascal_string(reader).map_err(GdPropError::FileRead)?;
+
+        let next = reader.fill_buf().map_err(GdPropError::FileRead)?;
+        if next.first() != Some(&Self::EXT_MARKER) {
+            // Header saved before versioning was introduced.
+            return Ok(GdMetaHeader {
+                gd_class,
+                uid,
+                version: 0,
+                compression: GdPropCompression::None,
+                seal: None,
+                checksum: None,
+            });
+        }
+        reader.consume(1);
+
+        let ext_len = Self::read_u32(reader).map_err(GdPropError::FileRead)?;
+        let mut ext = vec![0; ext_len as usize];
+        reader.read_exact(&mut ext).map_err(GdPropError::FileRead)?;
+        rmp_serde::from_slice(&ext).map_err(GdPropError::BinHeaderDeserialize)
+    }
+
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError> {
+        let mut serializer = rmp_serde::Serializer::new(writer);
+        value
+            .serialize(&mut serializer)
+            .map_err(GdPropError::BinSerialize)
+    }
+
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError> {
+        rmp_serde::from_read(reader).map_err(GdPropError::BinDeserialize)
+    }
+
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError> {
+        let mut payload = Vec::new();
+        reader
+            .read_to_end(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        Ok(GdPropPayload::Bin(payload))
+    }
+}
+
+/// Codec of `.gdjson` files: header in a single line, followed by pretty-printed [JSON](serde_json) payload.
+///
+/// JSON objects can be keyed only by strings, so maps with other keys can't be saved in this format.
+#[cfg(feature = "json")]
+pub struct JsonCodec;
+
+#[cfg(feature = "json")]
+impl GdPropCodec for JsonCodec {
+    const EXTENSION: &'static str = "gdjson";
+    const CHECKSUM: bool = false;
+    const COMPRESSED: bool = false;
+
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError> {
+        let serialized = serde_json::to_string(header).map_err(|_| GdPropError::HeaderSerialize)?;
+        writeln!(writer, "{}", serialized).map_err(GdPropError::FileWrite)
+    }
+
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError> {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(GdPropError::FileRead)?;
+        serde_json::from_str(&line)
+            .map_err(|error| GdPropError::FormatDeserialize(Self::EXTENSION, error.into()))
+    }
+
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError> {
+        serde_json::to_writer_pretty(writer, value)
+            .map_err(|error| GdPropError::FormatSerialize(Self::EXTENSION, error.into()))
+    }
+
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError> {
+        serde_json::from_reader(reader)
+            .map_err(|error| GdPropError::FormatDeserialize(Self::EXTENSION, error.into()))
+    }
+
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError> {
+        let mut payload = String::new();
+        reader
+            .read_to_string(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        Ok(GdPropPayload::Json(payload))
+    }
+}
+
+/// Codec of `.gdcbor` files: header shared with [`BinCodec`], followed by [CBOR](ciborium) payload.
+#[cfg(feature = "cbor")]
+pub struct CborCodec;
+
+#[cfg(feature = "cbor")]
+impl GdPropCodec for CborCodec {
+    const EXTENSION: &'static str = "gdcbor";
+
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError> {
+        BinCodec::write_header(header, writer)
+    }
+
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError> {
+        BinCodec::read_header(reader)
+    }
+
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError> {
+        ciborium::into_writer(value, writer)
+            .map_err(|error| GdPropError::FormatSerialize(Self::EXTENSION, error.into()))
+    }
+
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError> {
+        ciborium::from_reader(reader)
+            .map_err(|error| GdPropError::FormatDeserialize(Self::EXTENSION, error.into()))
+    }
+
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError> {
+        let mut payload = Vec::new();
+        reader
+            .read_to_end(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        Ok(GdPropPayload::Cbor(payload))
+    }
+}
+
+/// Codec of `.gdbc` files: header shared with [`BinCodec`], followed by [bincode] payload.
+///
+/// The payload isn't self-describing, so it can't be inspected without knowing its schema.
+#[cfg(feature = "bincode")]
+pub struct BincodeCodec;
+
+#[cfg(feature = "bincode")]
+impl GdPropCodec for BincodeCodec {
+    const EXTENSION: &'static str = "gdbc";
+
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError> {
+        BinCodec::write_header(header, writer)
+    }
+
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError> {
+        BinCodec::read_header(reader)
+    }
+
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError> {
+        bincode::serialize_into(writer, value)
+            .map_err(|error| GdPropError::FormatSerialize(Self::EXTENSION, error))
+    }
+
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError> {
+        bincode::deserialize_from(reader)
+            .map_err(|error| GdPropError::FormatDeserialize(Self::EXTENSION, error))
+    }
+
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError> {
+        let mut payload = Vec::new();
+        reader
+            .read_to_end(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        Ok(GdPropPayload::Bincode(payload))
+    }
+}
+
+/// Codec of `.gdpc` files: header shared with [`BinCodec`], followed by compact [postcard] payload.
+///
+/// The payload isn't self-describing, so it can't be inspected without knowing its schema.
+#[cfg(feature = "postcard")]
+pub struct PostcardCodec;
+
+#[cfg(feature = "postcard")]
+impl GdPropCodec for PostcardCodec {
+    const EXTENSION: &'static str = "gdpc";
+
+    fn write_header<W: Write>(header: &GdMetaHeader, writer: &mut W) -> Result<(), GdPropError> {
+        BinCodec::write_header(header, writer)
+    }
+
+    fn read_header<R: BufRead>(reader: &mut R) -> Result<GdMetaHeader, GdPropError> {
+        BinCodec::read_header(reader)
+    }
+
+    fn write_body<T: Serialize, W: Write>(value: &T, writer: &mut W) -> Result<(), GdPropError> {
+        let serialized = postcard::to_stdvec(value)
+            .map_err(|error| GdPropError::FormatSerialize(Self::EXTENSION, error.into()))?;
+        writer
+            .write_all(&serialized)
+            .map_err(GdPropError::FileWrite)
+    }
+
+    fn read_body<T: DeserializeOwned, R: BufRead>(reader: &mut R) -> Result<T, GdPropError> {
+        let mut payload = Vec::new();
+        reader
+            .read_to_end(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        postcard::from_bytes(&payload)
+            .map_err(|error| GdPropError::FormatDeserialize(Self::EXTENSION, error.into()))
+    }
+
+    fn read_payload<R: BufRead>(reader: &mut R) -> Result<GdPropPayload, GdPropError> {
+        let mut payload = Vec::new();
+        reader
+            .read_to_end(&mut payload)
+            .map_err(GdPropError::FileRead)?;
+        Ok(GdPropPayload::Postcard(payload))
+    }
+}
@@ -0,0 +1,97 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::GdPropError;
+
+/// Compression of the file payload, flagged in the file header.
+///
+/// Payloads are compressed while saving and transparently decompressed while loading. Every algorithm needs its
+/// cargo feature to be enabled: `zstd`, `lz4` or `deflate`. Files compressed with an algorithm which feature isn't
+/// enabled can't be read and return [`GdPropError::CompressionUnsupported`].
+///
+/// Compression can be set for all saves of given resource with `#[gdprop(compression = Zstd)]` attribute, or only for
+/// `.gdbin` files created during export with `#[export_compression(Zstd)]` attribute of `#[gd_props_plugin]` macro.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GdPropCompression {
+    /// Payload is saved as is.
+    #[default]
+    None,
+    /// [Zstandard](https://facebook.github.io/zstd/) compression, behind `zstd` feature.
+    Zstd,
+    /// [LZ4](https://lz4.org/) compression, behind `lz4` feature.
+    Lz4,
+    /// Deflate compression, behind `deflate` feature.
+    Deflate,
+}
+
+impl GdPropCompression {
+    /// Compression level used for [`GdPropCompression::Zstd`].
+    #[cfg(feature = "zstd")]
+    const ZSTD_LEVEL: i32 = 3;
+
+    pub fn is_none(&self) -> bool {
+        *self == GdPropCompression::None
+    }
+
+    /// Compress the `payload`.
+    pub fn compress(&self, payload: &[u8]) -> Result<Vec<u8>, GdPropError> {
+        match self {
+            GdPropCompression::None => Ok(payload.to_vec()),
+            #[cfg(feature = "zstd")]
+            GdPropCompression::Zstd => zstd::encode_all(payload, Self::ZSTD_LEVEL)
+                .map_err(|error| GdPropError::Compression(*self, error.into())),
+            #[cfg(feature = "lz4")]
+            GdPropCompression::Lz4 => Ok(lz4_flex::compress_prepend_size(payload)),
+            #[cfg(feature = "deflate")]
+            GdPropCompression::Deflate => {
+                use std::io::Write;
+
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(payload)
+                    .and_then(|_| encoder.finish())
+                    .map_err(|error| GdPropError::Compression(*self, error.into()))
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(GdPropError::CompressionUnsupported(*self)),
+        }
+    }
+
+    /// Decompress the `payload`.
+    pub fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, GdPropError> {
+        match self {
+            GdPropCompression::None => Ok(payload.to_vec()),
+            #[cfg(feature = "zstd")]
+            GdPropCompression::Zstd => zstd::decode_all(payload)
+                .map_err(|error| GdPropError::Compression(*self, error.into())),
+            #[cfg(feature = "lz4")]
+            GdPropCompression::Lz4 => lz4_flex::decompress_size_prepended(payload)
+                .map_err(|error| GdPropError::Compression(*self, error.into())),
+            #[cfg(feature = "deflate")]
+            GdPropCompression::Deflate => {
+                use std::io::Read;
+
+                let mut decompressed = Vec::new();
+                flate2::read::DeflateDecoder::new(payload)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|error| GdPropError::Compression(*self, error.into()))?;
+                Ok(decompressed)
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(GdPropError::CompressionUnsupported(*self)),
+        }
+    }
+}
+
+impl fmt::Display for GdPropCompression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GdPropCompression::None => write!(f, "none"),
+            GdPropCompression::Zstd => write!(f, "zstd"),
+            GdPropCompression::Lz4 => write!(f, "lz4"),
+            GdPropCompression::Deflate => write!(f, "deflate"),
+        }
+    }
+}
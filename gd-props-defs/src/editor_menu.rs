@@ -0,0 +1,208 @@
+use godot::builtin::{Callable, GString, StringName};
+use godot::classes::resource_loader::CacheMode;
+use godot::classes::{
+    EditorInterface, FileAccess, PopupMenu, Resource, ResourceLoader, ResourceSaver, ResourceUid,
+};
+use godot::global::Error;
+use godot::obj::Gd;
+
+use crate::codec::GdPropFormat;
+use crate::gdprop_io::{GdPropLoader, GdPropSaver};
+use crate::utils::RefCountedSingleton;
+
+/// Actions added by the generated `EditorPlugin` to context menus of FileSystem dock, run on the selected files.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GdPropFileAction {
+    ConvertToBin,
+    ConvertToRon,
+    Validate,
+    ShowReferences,
+    RegenerateUid,
+}
+
+impl GdPropFileAction {
+    pub const ALL: [GdPropFileAction; 5] = [
+        GdPropFileAction::ConvertToBin,
+        GdPropFileAction::ConvertToRon,
+        GdPropFileAction::Validate,
+        GdPropFileAction::ShowReferences,
+        GdPropFileAction::RegenerateUid,
+    ];
+
+    /// Id of the first action in the menu. FileSystem dock uses ids below it for its own options, and ids from 1000 for
+    /// resource conversions.
+    const FIRST_ID: i32 = 900;
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GdPropFileAction::ConvertToBin => "Convert to .gdbin",
+            GdPropFileAction::ConvertToRon => "Convert to .gdron",
+            GdPropFileAction::Validate => "Validate",
+            GdPropFileAction::ShowReferences => "Show references",
+            GdPropFileAction::RegenerateUid => "Regenerate UID",
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        Self::FIRST_ID + Self::ALL.iter().position(|action| action == self).unwrap() as i32
+    }
+
+    pub fn from_id(id: i64) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.id() as i64 == id)
+    }
+
+    /// Whether the action can be run on file located at `path`, handled by plugin supporting `formats`.
+    pub fn applies_to(&self, path: &str, formats: &[GdPropFormat]) -> bool {
+        let format = GdPropFormat::recognize_among(path, formats);
+        if format == GdPropFormat::None {
+            return false;
+        }
+        match self {
+            GdPropFileAction::ConvertToBin => {
+                format != GdPropFormat::GdBin && formats.contains(&GdPropFormat::GdBin)
+            }
+            GdPropFileAction::ConvertToRon => {
+                format != GdPropFormat::GdRon && formats.contains(&GdPropFormat::GdRon)
+            }
+            _ => true,
+        }
+    }
+
+    /// Run the action on file located at `path`, returning the message describing its result or the cause of failure.
+    pub fn run<L, S>(&self, path: GString) -> Result<String, String>
+    where
+        L: GdPropLoader,
+        S: GdPropSaver,
+    {
+        match self {
+            GdPropFileAction::ConvertToBin => Self::convert(path, GdPropFormat::GdBin),
+            GdPropFileAction::ConvertToRon => Self::convert(path, GdPropFormat::GdRon),
+            GdPropFileAction::Validate => {
+                Self::load(path).map(|resource| format!("valid {}", resource.get_class()))
+            }
+            GdPropFileAction::ShowReferences => {
+                let references = L::singleton_refcount()
+                    .bind()
+                    ._int_try_get_dependencies(path)
+                    .map_err(|error| error.to_string())?;
+                if references.is_empty() {
+                    return Ok("no references".to_string());
+                }
+                let mut message = format!("{} references:", references.len());
+                for reference in references {
+                    let missing =
+                        if ResourceLoader::singleton().exists(GString::from(&reference.path)) {
+                            ""
+                        } else {
+                            " - missing"
+                        };
+                    message.push_str(&format!(
+                        "\n  {} ({}, {}){missing}",
+                        reference.path, reference.gd_class, reference.uid
+                    ));
+                }
+                Ok(message)
+            }
+            GdPropFileAction::RegenerateUid => {
+                let mut resource_uid = ResourceUid::singleton();
+                let uid = resource_uid.create_id();
+                S::singleton_refcount()
+                    .bind_mut()
+                    ._int_try_set_uid(path, uid)
+                    .map_err(|error| error.to_string())?;
+                Ok(format!("new UID {}", resource_uid.id_to_text(uid)))
+            }
+        }
+    }
+
+    /// Context menus of FileSystem dock: of the tree and of the file list.
+    pub fn file_menus() -> Vec<Gd<PopupMenu>> {
+        let Some(dock) = EditorInterface::singleton().get_file_system_dock() else {
+            return Vec::new();
+        };
+        dock.get_children()
+            .iter_shared()
+            .filter_map(|child| child.try_cast::<PopupMenu>().ok())
+            .collect()
+    }
+
+    /// Connect `about_to_popup` and `id_pressed` signals of FileSystem dock context menus to methods of the plugin.
+    pub fn connect_menus(fill: &Callable, run: &Callable) {
+        for mut menu in Self::file_menus() {
+            menu.connect(StringName::from("about_to_popup"), fill.clone());
+            menu.connect(StringName::from("id_pressed"), run.clone());
+        }
+    }
+
+    pub fn disconnect_menus(fill: &Callable, run: &Callable) {
+        for mut menu in Self::file_menus() {
+            menu.disconnect(StringName::from("about_to_popup"), fill.clone());
+            menu.disconnect(StringName::from("id_pressed"), run.clone());
+        }
+    }
+
+    /// Add actions which can be run on any of the selected files to FileSystem dock context menus, which don't have
+    /// them already. The dock clears the menu before it's filled, so the actions reflect the current selection.
+    pub fn fill_menus(formats: &[GdPropFormat]) {
+        let selected = EditorInterface::singleton().get_selected_paths();
+        let actions = Self::ALL
+            .iter()
+            .filter(|action| {
+                selected
+                    .as_slice()
+                    .iter()
+                    .any(|path| action.applies_to(&path.to_string(), formats))
+            })
+            .collect::<Vec<_>>();
+        if actions.is_empty() {
+            return;
+        }
+
+        for mut menu in Self::file_menus() {
+            if actions
+                .iter()
+                .any(|action| menu.get_item_index(action.id()) != -1)
+            {
+                continue;
+            }
+            menu.add_separator();
+            for action in &actions {
+                menu.add_item_ex(GString::from(action.label()))
+                    .id(action.id())
+                    .done();
+            }
+        }
+    }
+
+    fn convert(path: GString, format: GdPropFormat) -> Result<String, String> {
+        let str_path = path.to_string();
+        let stem = str_path
+            .rsplit_once('.')
+            .map_or(str_path.as_str(), |(stem, _)| stem);
+        let target = GString::from(format!("{stem}.{}", format.extension()));
+        if FileAccess::file_exists(target.clone()) {
+            return Err(format!("{target} already exists"));
+        }
+
+        let resource = Self::load(path)?;
+        match ResourceSaver::singleton()
+            .save_ex(resource)
+            .path(target.clone())
+            .done()
+        {
+            Error::OK => Ok(format!("converted to {target}")),
+            error => Err(format!("can't save {target}: {error:?}")),
+        }
+    }
+
+    fn load(path: GString) -> Result<Gd<Resource>, String> {
+        ResourceLoader::singleton()
+            .load_ex(path)
+            .cache_mode(CacheMode::IGNORE)
+            .done()
+            .ok_or_else(|| "can't be loaded, see errors above".to_string())
+    }
+}
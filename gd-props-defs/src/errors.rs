@@ -1,15 +1,119 @@
 use core::fmt;
+use std::error::Error as StdError;
 
-use ron::error::SpannedError;
+use godot::global::Error;
+use ron::error::{Position, SpannedError};
 
+use crate::compression::GdPropCompression;
+
+/// Error returned by `gd-props` operations on `.gdron` and `.gdbin` files.
+///
+/// Godot-facing methods map it into [`godot::global::Error`] with [`GdPropError::to_godot_error`], while their `try_`
+/// counterparts return it directly, so the actual cause can be handled in Rust.
 #[derive(Debug)]
 pub enum GdPropError {
     OpenFileRead,
     OpenFileWrite,
+    UnrecognizedFormat(String),
     HeaderDeserialize(SpannedError),
+    BinHeaderDeserialize(rmp_serde::decode::Error),
     HeaderSerialize,
     FileRead(std::io::Error),
     FileWrite(std::io::Error),
+    RonSerialize(ron::Error),
+    RonDeserialize(SpannedError),
+    BinSerialize(rmp_serde::encode::Error),
+    BinDeserialize(rmp_serde::decode::Error),
+    ClassMismatch {
+        expected: String,
+        found: String,
+    },
+    UidConflict {
+        uid: String,
+        path: String,
+    },
+    VersionUnsupported {
+        found: u32,
+        current: u32,
+    },
+    Migration(String),
+    FormatSerialize(&'static str, Box<dyn StdError + Send + Sync>),
+    FormatDeserialize(&'static str, Box<dyn StdError + Send + Sync>),
+    NotSelfDescribing(&'static str),
+    Compression(GdPropCompression, Box<dyn StdError + Send + Sync>),
+    CompressionUnsupported(GdPropCompression),
+    KeyMissing,
+    Tampered,
+    SealFailed,
+    LengthMismatch {
+        expected: u64,
+        found: u64,
+    },
+    ChecksumMismatch {
+        expected: u32,
+        found: u32,
+    },
+    FileMove {
+        from: String,
+        to: String,
+        error: Error,
+    },
+    VersionMismatch {
+        found: u32,
+        current: u32,
+    },
+    DeltaBaseMismatch {
+        base: u32,
+    },
+}
+
+impl GdPropError {
+    /// Position in `.gdron` file at which the error occured, if known.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            GdPropError::HeaderDeserialize(spanned) | GdPropError::RonDeserialize(spanned) => {
+                Some(spanned.position)
+            }
+            _ => None,
+        }
+    }
+
+    /// Godot [`Error`] describing the cause most closely.
+    pub fn to_godot_error(&self) -> Error {
+        match self {
+            GdPropError::OpenFileRead => Error::ERR_FILE_CANT_OPEN,
+            GdPropError::OpenFileWrite
+            | GdPropError::HeaderSerialize
+            | GdPropError::FileWrite(_)
+            | GdPropError::FileMove { .. } => Error::ERR_FILE_CANT_WRITE,
+            GdPropError::UnrecognizedFormat(_) | GdPropError::VersionUnsupported { .. } => {
+                Error::ERR_FILE_UNRECOGNIZED
+            }
+            GdPropError::HeaderDeserialize(_)
+            | GdPropError::BinHeaderDeserialize(_)
+            | GdPropError::ClassMismatch { .. }
+            | GdPropError::Compression(..)
+            | GdPropError::LengthMismatch { .. }
+            | GdPropError::ChecksumMismatch { .. } => Error::ERR_FILE_CORRUPT,
+            GdPropError::FileRead(_)
+            | GdPropError::RonDeserialize(_)
+            | GdPropError::BinDeserialize(_)
+            | GdPropError::Migration(_)
+            | GdPropError::FormatDeserialize(..) => Error::ERR_FILE_CANT_READ,
+            GdPropError::RonSerialize(_)
+            | GdPropError::BinSerialize(_)
+            | GdPropError::FormatSerialize(..)
+            | GdPropError::SealFailed => Error::ERR_CANT_CREATE,
+            GdPropError::UidConflict { .. } => Error::ERR_ALREADY_EXISTS,
+            GdPropError::NotSelfDescribing(_) | GdPropError::CompressionUnsupported(_) => {
+                Error::ERR_UNAVAILABLE
+            }
+            GdPropError::KeyMissing | GdPropError::Tampered => Error::ERR_UNAUTHORIZED,
+            GdPropError::VersionMismatch { .. } | GdPropError::DeltaBaseMismatch { .. } => {
+                Error::ERR_INVALID_DATA
+            }
+        }
+    }
 }
 
 impl fmt::Display for GdPropError {
@@ -17,12 +121,127 @@ impl fmt::Display for GdPropError {
         match self {
             GdPropError::OpenFileRead => write!(f, "can't open file for reading"),
             GdPropError::OpenFileWrite => write!(f, "can't open file for writing"),
+            GdPropError::UnrecognizedFormat(path) => write!(f, "unrecognized format of: {}", path),
             GdPropError::HeaderDeserialize(spanned) => {
                 write!(f, "can't deserialize header: {}", spanned)
             }
+            GdPropError::BinHeaderDeserialize(error) => {
+                write!(f, "can't deserialize binary header: {}", error)
+            }
             GdPropError::HeaderSerialize => write!(f, "can't serialize header"),
             GdPropError::FileRead(error) => write!(f, "can't read file: {}", error),
             GdPropError::FileWrite(error) => write!(f, "can't write to file: {}", error),
+            GdPropError::RonSerialize(error) => write!(f, "can't serialize to ron: {}", error),
+            GdPropError::RonDeserialize(spanned) => {
+                write!(f, "can't deserialize from ron: {}", spanned)
+            }
+            GdPropError::BinSerialize(error) => write!(f, "can't serialize to bin: {}", error),
+            GdPropError::BinDeserialize(error) => {
+                write!(f, "can't deserialize from bin: {}", error)
+            }
+            GdPropError::ClassMismatch { expected, found } => write!(
+                f,
+                "file contains class {}, while expected: {}",
+                found, expected
+            ),
+            GdPropError::UidConflict { uid, path } => write!(
+                f,
+                "other resource of UID {} already exists at: {}",
+                uid, path
+            ),
+            GdPropError::VersionUnsupported { found, current } => write!(
+                f,
+                "file saved with version {} is newer than current version {}",
+                found, current
+            ),
+            GdPropError::Migration(message) => write!(f, "can't migrate payload: {}", message),
+            GdPropError::FormatSerialize(extension, error) => {
+                write!(f, "can't serialize to {}: {}", extension, error)
+            }
+            GdPropError::FormatDeserialize(extension, error) => {
+                write!(f, "can't deserialize from {}: {}", extension, error)
+            }
+            GdPropError::NotSelfDescribing(extension) => write!(
+                f,
+                "{} payload can't be read without knowing its schema",
+                extension
+            ),
+            GdPropError::Compression(compression, error) => {
+                write!(f, "can't process {} compression: {}", compression, error)
+            }
+            GdPropError::CompressionUnsupported(compression) => write!(
+                f,
+                "{} compression is unsupported, enable its cargo feature",
+                compression
+            ),
+            GdPropError::KeyMissing => {
+                write!(f, "file is sealed and can't be loaded without a key")
+            }
+            GdPropError::Tampered => write!(
+                f,
+                "file was tampered with: its seal is missing or doesn't match the key"
+            ),
+            GdPropError::SealFailed => write!(f, "can't seal the payload"),
+            GdPropError::LengthMismatch { expected, found } => write!(
+                f,
+                "payload has {} bytes, while header expects {}: file is truncated or damaged",
+                found, expected
+            ),
+            GdPropError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "payload checksum {:08x} doesn't match {:08x} from header: file is damaged",
+                found, expected
+            ),
+            GdPropError::FileMove { from, to, error } => {
+                write!(f, "can't move {} to {}: {:?}", from, to, error)
+            }
+            GdPropError::VersionMismatch { found, current } => write!(
+                f,
+                "packet sent with version {} doesn't match current version {}",
+                found, current
+            ),
+            GdPropError::DeltaBaseMismatch { base } => write!(
+                f,
+                "delta packet based on {:08x} doesn't apply to the last unpacked resource, full packet is needed",
+                base
+            ),
         }
     }
 }
+
+impl StdError for GdPropError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            GdPropError::HeaderDeserialize(spanned) | GdPropError::RonDeserialize(spanned) => {
+                Some(spanned)
+            }
+            GdPropError::BinHeaderDeserialize(error) | GdPropError::BinDeserialize(error) => {
+                Some(error)
+            }
+            GdPropError::FileRead(error) | GdPropError::FileWrite(error) => Some(error),
+            GdPropError::RonSerialize(error) => Some(error),
+            GdPropError::BinSerialize(error) => Some(error),
+            GdPropError::FormatSerialize(_, error)
+            | GdPropError::FormatDeserialize(_, error)
+            | GdPropError::Compression(_, error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Problem with `.gdron` file found by the exporter of `gd_props_plugin` during project export. Such file is left out
+/// of exported project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GdExportIssue {
+    /// Path of the `.gdron` file.
+    pub path: String,
+    /// Class of the resource saved in the file, as recognized by Godot.
+    pub class: String,
+    pub message: String,
+}
+
+impl fmt::Display for GdExportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.path, self.class, self.message)
+    }
+}
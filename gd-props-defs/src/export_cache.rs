@@ -0,0 +1,155 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use godot::builtin::GString;
+use godot::classes::{DirAccess, FileAccess, ResourceLoader, ResourceUid};
+use godot::log::godot_warn;
+
+use crate::atomic::write_atomic;
+use crate::codec::GdPropCompression;
+use crate::errors::GdPropError;
+use crate::gd_meta::GdMetaExt;
+
+/// Cache of `.gdbin` files translated from `.gdron` files during export, kept in `.godot` directory of the project, so
+/// files which didn't change since the last export aren't translated again.
+///
+/// Entries are keyed with hash of the `.gdron` content together with everything else the translation depends on: the
+/// build of the extension and the current state of referenced External Resources. Stale entries are never used, and
+/// are removed with [`GdExportCache::prune`]. Entry files are named with fingerprint of the build and the key.
+pub(crate) struct GdExportCache;
+
+impl GdExportCache {
+    const DIR: &'static str = "res://.godot/gd_props/export_cache";
+
+    /// Time in seconds after which entry which wasn't used during export is removed.
+    const MAX_UNUSED_AGE: u64 = 7 * 24 * 60 * 60;
+
+    /// Key of translation of `.gdron` `content` of class `class` with schema `version` into `.gdbin` file compressed
+    /// with `compression`, made by extension build with given `fingerprint`.
+    pub(crate) fn key(
+        fingerprint: u64,
+        class: &str,
+        version: u32,
+        compression: GdPropCompression,
+        content: &[u8],
+    ) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        fingerprint.hash(&mut hasher);
+        class.hash(&mut hasher);
+        version.hash(&mut hasher);
+        compression.to_string().hash(&mut hasher);
+        content.hash(&mut hasher);
+        if let Ok(payload) = std::str::from_utf8(content) {
+            for reference in GdMetaExt::find_in_ron(payload) {
+                Self::hash_reference(&reference, &mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Hash the state of the file the `reference` is resolved to during translation. Path and UID of the loaded file
+    /// are saved in translated reference, and its class is read from the file.
+    fn hash_reference(reference: &GdMetaExt, hasher: &mut DefaultHasher) {
+        let resource_uid = ResourceUid::singleton();
+        let id = resource_uid.text_to_id(GString::from(&reference.uid));
+        let path = if resource_uid.has_id(id) {
+            resource_uid.get_id_path(id)
+        } else {
+            GString::from(&reference.path)
+        };
+        path.to_string().hash(hasher);
+        ResourceLoader::singleton()
+            .get_resource_uid(path.clone())
+            .hash(hasher);
+        if FileAccess::file_exists(path.clone()) {
+            FileAccess::get_modified_time(path).hash(hasher);
+        }
+    }
+
+    /// Cached `.gdbin` file, if it was saved with the `key` by extension build with given `fingerprint`. The entry is
+    /// saved again, so it's kept by [`GdExportCache::prune`] as recently used.
+    pub(crate) fn get(fingerprint: u64, key: u64) -> Option<Vec<u8>> {
+        let path = Self::path(fingerprint, key);
+        if !FileAccess::file_exists(path.clone()) {
+            return None;
+        }
+        let bytes = FileAccess::get_file_as_bytes(path);
+        if bytes.is_empty() {
+            return None;
+        }
+        let bytes = bytes.to_vec();
+        if let Err(error) = Self::put(fingerprint, key, &bytes) {
+            godot_warn!("can't refresh export cache entry {key:016x}: {error}");
+        }
+        Some(bytes)
+    }
+
+    /// Save the `.gdbin` file with the `key`, made by extension build with given `fingerprint`.
+    pub(crate) fn put(fingerprint: u64, key: u64, bytes: &[u8]) -> Result<(), GdPropError> {
+        DirAccess::make_dir_recursive_absolute(GString::from(Self::DIR));
+        write_atomic(&Self::path(fingerprint, key), 0, |writer| {
+            writer.write_all(bytes).map_err(GdPropError::FileWrite)
+        })
+    }
+
+    /// Remove entries saved by extension builds other than the one with given `fingerprint`, as they won't be used
+    /// again. Entries of the current build which weren't `used` during the current export are removed if they were
+    /// saved earlier than [`GdExportCache::MAX_UNUSED_AGE`] ago, as recent ones can be used by exports of other
+    /// presets.
+    pub(crate) fn prune(fingerprint: u64, used: &[u64]) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        Self::remove_entries(|path, entry| match entry {
+            Some((entry_fingerprint, key)) if entry_fingerprint == fingerprint => {
+                !used.contains(&key)
+                    && now.saturating_sub(FileAccess::get_modified_time(path.clone()))
+                        > Self::MAX_UNUSED_AGE
+            }
+            _ => true,
+        });
+    }
+
+    /// Remove all entries saved by extension build with given `fingerprint`.
+    pub(crate) fn clear(fingerprint: u64) {
+        Self::remove_entries(|_, entry| {
+            entry.is_some_and(|(entry_fingerprint, _)| entry_fingerprint == fingerprint)
+        });
+    }
+
+    /// Remove files of the cache for which `predicate` holds. It's given path of the file, and its fingerprint and
+    /// key if the file is a cache entry.
+    fn remove_entries<F>(predicate: F)
+    where
+        F: Fn(&GString, Option<(u64, u64)>) -> bool,
+    {
+        let dir = GString::from(Self::DIR);
+        if !DirAccess::dir_exists_absolute(dir.clone()) {
+            return;
+        }
+        for file in DirAccess::get_files_at(dir).as_slice() {
+            let file = file.to_string();
+            let entry = file
+                .strip_suffix(".gdbin")
+                .and_then(|name| name.split_once('_'))
+                .and_then(|(fingerprint, key)| {
+                    Some((
+                        u64::from_str_radix(fingerprint, 16).ok()?,
+                        u64::from_str_radix(key, 16).ok()?,
+                    ))
+                });
+            let path = GString::from(format!("{}/{file}", Self::DIR));
+            if predicate(&path, entry) {
+                DirAccess::remove_absolute(path);
+            }
+        }
+    }
+
+    fn path(fingerprint: u64, key: u64) -> GString {
+        GString::from(format!("{}/{fingerprint:016x}_{key:016x}.gdbin", Self::DIR))
+    }
+}
@@ -1,24 +1,302 @@
 use std::io::Read;
 
-use godot::builtin::{GString, PackedByteArray};
+use godot::builtin::meta::ToGodot;
+use godot::builtin::{
+    dict, Array, Dictionary, GString, PackedByteArray, PackedStringArray, StringName, Variant,
+    VariantType,
+};
 use godot::classes::file_access::ModeFlags;
 use godot::classes::{
-    DirAccess, EditorExportPlugin, FileAccess, IEditorExportPlugin, Object, ResourceLoader,
-    ResourceUid,
+    EditorExportPlugin, IEditorExportPlugin, Object, ResourceLoader, ResourceUid,
 };
-use godot::log::godot_error;
+use godot::global::PropertyHint;
+use godot::log::{godot_error, godot_warn};
 use godot::obj::bounds::MemRefCounted;
 use godot::obj::cap::GodotDefault;
-use godot::obj::{Bounds, GodotClass, Inherits, UserClass};
-use godot::tools::{save, GFile};
+use godot::obj::{Bounds, GodotClass, Inherits, UserClass, WithBaseField};
+use godot::tools::GFile;
 
+use crate::codec::{GdPropCompression, GdPropKey};
+use crate::errors::GdExportIssue;
+use crate::export_cache::GdExportCache;
 use crate::gdprop::GdProp;
 
+/// Export option choosing [`GdRonExport`] for debug exports.
+const OPTION_DEBUG_RON: &str = "gd_props/debug_gdron_export";
+/// Export option choosing [`GdRonExport`] for release exports.
+const OPTION_RELEASE_RON: &str = "gd_props/release_gdron_export";
+/// Export option choosing compression used with [`GdRonExport::ConvertCompressed`].
+const OPTION_COMPRESSION: &str = "gd_props/compression";
+/// Export option listing paths of files and directories left out of release exports.
+const OPTION_DEBUG_ONLY: &str = "gd_props/debug_only_paths";
+/// Export option choosing whether problems with `.gdron` files are logged as errors, rather than warnings. Export
+/// succeeds either way.
+const OPTION_REPORT_AS_ERRORS: &str = "gd_props/report_as_errors";
+/// Export option choosing whether translated `.gdron` files are reused from [`GdExportCache`].
+const OPTION_USE_CACHE: &str = "gd_props/use_export_cache";
+
+/// Compressions available in [`OPTION_COMPRESSION`], in the order of the hint.
+const EXPORT_COMPRESSIONS: [GdPropCompression; 3] = [
+    GdPropCompression::Zstd,
+    GdPropCompression::Lz4,
+    GdPropCompression::Deflate,
+];
+
+/// Handling of `.gdron` files during export, chosen in export preset separately for debug and release exports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GdRonExport {
+    /// `.gdron` files are exported as they are.
+    Keep,
+    /// `.gdron` files are translated into `.gdbin` files.
+    #[default]
+    Convert,
+    /// `.gdron` files are translated into `.gdbin` files, compressed with compression chosen in export preset.
+    ConvertCompressed,
+}
+
+impl GdRonExport {
+    const VARIANTS: [Self; 3] = [Self::Keep, Self::Convert, Self::ConvertCompressed];
+    const HINT: &'static str = "Keep,Convert,Convert and compress";
+
+    /// Handling of `.gdron` files if export preset doesn't choose it, for plugin with given default compression of
+    /// exported files.
+    pub fn default_for(compression: GdPropCompression) -> Self {
+        if compression.is_none() {
+            Self::Convert
+        } else {
+            Self::ConvertCompressed
+        }
+    }
+
+    /// Index of the variant in export option.
+    pub fn ord(self) -> i64 {
+        Self::VARIANTS
+            .iter()
+            .position(|variant| *variant == self)
+            .unwrap_or_default() as i64
+    }
+
+    /// Variant at the index of export option, if it's valid.
+    pub fn from_ord(ord: i64) -> Option<Self> {
+        usize::try_from(ord)
+            .ok()
+            .and_then(|index| Self::VARIANTS.get(index).copied())
+    }
+}
+
 #[derive(Default)]
 #[doc(hidden)]
 pub struct ExporterState {
     remaps: Vec<RemapData>,
+    report: Vec<GdExportIssue>,
     debug: bool,
+    ron_export: GdRonExport,
+    compression: GdPropCompression,
+    debug_only: Vec<String>,
+    report_as_errors: bool,
+    fingerprint: u64,
+    use_cache: bool,
+    cache_used: Vec<u64>,
+    cache_hits: usize,
+}
+
+impl ExporterState {
+    /// Configure current export with options of its preset, read with `option`. Options missing in the preset fall
+    /// back to defaults of plugin exporting with `default_compression`, and built into extension with `fingerprint`.
+    pub fn configure<F>(
+        &mut self,
+        is_debug: bool,
+        default_compression: GdPropCompression,
+        fingerprint: u64,
+        option: F,
+    ) where
+        F: Fn(&str) -> Variant,
+    {
+        self.debug = is_debug;
+        let ron_export = option(if is_debug {
+            OPTION_DEBUG_RON
+        } else {
+            OPTION_RELEASE_RON
+        })
+        .try_to::<i64>()
+        .ok()
+        .and_then(GdRonExport::from_ord)
+        .unwrap_or_else(|| GdRonExport::default_for(default_compression));
+        let compression = option(OPTION_COMPRESSION)
+            .try_to::<i64>()
+            .ok()
+            .and_then(|ord| EXPORT_COMPRESSIONS.get(usize::try_from(ord).ok()?).copied())
+            .unwrap_or(default_compression);
+
+        self.ron_export = ron_export;
+        self.compression = match ron_export {
+            GdRonExport::ConvertCompressed => compression,
+            _ => GdPropCompression::None,
+        };
+        self.debug_only = if is_debug {
+            Vec::new()
+        } else {
+            option(OPTION_DEBUG_ONLY)
+                .try_to::<PackedStringArray>()
+                .map(|paths| {
+                    paths
+                        .as_slice()
+                        .iter()
+                        .map(ToString::to_string)
+                        .filter(|path| !path.is_empty())
+                        .map(|path| {
+                            // Entries other than files are directories, so they don't match siblings sharing the prefix.
+                            if is_prop_file(&path) || path.ends_with('/') {
+                                path
+                            } else {
+                                format!("{path}/")
+                            }
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        self.report_as_errors = option(OPTION_REPORT_AS_ERRORS)
+            .try_to::<bool>()
+            .unwrap_or(true);
+        self.fingerprint = fingerprint;
+        self.use_cache = option(OPTION_USE_CACHE).try_to::<bool>().unwrap_or(true);
+        self.cache_used.clear();
+        self.cache_hits = 0;
+    }
+
+    /// Handling of `.gdron` files in current export.
+    pub fn ron_export(&self) -> GdRonExport {
+        self.ron_export
+    }
+
+    /// Compression of `.gdbin` files translated from `.gdron` files in current export, overriding the one of the
+    /// resource if set.
+    pub fn compression(&self) -> GdPropCompression {
+        self.compression
+    }
+
+    /// Whether the file is left out of current export, as it's debug-only `.gdron` or `.gdbin` file and the export is
+    /// release.
+    pub fn is_stripped(&self, path: &str) -> bool {
+        is_prop_file(path)
+            && self.debug_only.iter().any(|entry| {
+                if entry.ends_with('/') {
+                    path.starts_with(entry.as_str())
+                } else {
+                    path == entry
+                }
+            })
+    }
+
+    /// Problems found with `.gdron` files during the current or the last export.
+    pub fn report(&self) -> &[GdExportIssue] {
+        &self.report
+    }
+
+    /// Number of `.gdron` files which translations were reused from [`GdExportCache`] during the current export.
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    /// Remove entries of [`GdExportCache`] which are no longer used, if the cache is used by current export.
+    pub fn prune_cache(&self) {
+        if self.use_cache {
+            GdExportCache::prune(self.fingerprint, &self.cache_used);
+        }
+    }
+
+    /// Remove all entries of [`GdExportCache`] saved by the build of the extension current export is configured with.
+    pub fn clear_cache(&self) {
+        GdExportCache::clear(self.fingerprint);
+    }
+
+    /// Give UIDs of `.gdron` files processed during current export back to them, in reverse order of the transfers.
+    pub fn undo_remaps(&mut self) {
+        while let Some(remap) = self.remaps.pop() {
+            remap.undo_uid();
+        }
+    }
+
+    /// Translate `.gdron` file of class `T` into bytes of `.gdbin` file in memory, sealing it with the `key` of the
+    /// saver. Translations of unsealed files are reused from [`GdExportCache`] if neither the file, the files it
+    /// references nor the extension changed, unless export preset disables the cache. Problems are recorded in the
+    /// report instead of failing, and `None` is returned for the file to be skipped.
+    pub fn process_ron_file<T>(
+        &mut self,
+        ron_path: GString,
+        bin_path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Option<PackedByteArray>
+    where
+        T: GdProp,
+    {
+        let class = GString::from(T::HEAD_IDENT);
+        let Some(content) = read_file_to_bytes(ron_path.clone()) else {
+            self.report_issue(&ron_path, &class, "can't read the file".to_string());
+            return None;
+        };
+        let compression = match self.compression {
+            GdPropCompression::None => T::COMPRESSION,
+            compression => compression,
+        };
+        // Sealed files can't be reused, as the seal is made anew with every save.
+        let cache_key = (self.use_cache && key.is_none()).then(|| {
+            GdExportCache::key(
+                self.fingerprint,
+                T::HEAD_IDENT,
+                T::VERSION,
+                compression,
+                content.as_slice(),
+            )
+        });
+        if let Some(cache_key) = cache_key {
+            self.cache_used.push(cache_key);
+        }
+
+        let cached =
+            cache_key.and_then(|cache_key| GdExportCache::get(self.fingerprint, cache_key));
+        let bytes = match cached {
+            Some(bytes) => {
+                self.cache_hits += 1;
+                bytes
+            }
+            None => {
+                match T::_int_translate_ron_to_bin(&ron_path, content.as_slice(), compression, key)
+                {
+                    Ok(bytes) => {
+                        if let Some(cache_key) = cache_key {
+                            if let Err(error) =
+                                GdExportCache::put(self.fingerprint, cache_key, &bytes)
+                            {
+                                godot_warn!("can't cache translated {}: {}", ron_path, error);
+                            }
+                        }
+                        bytes
+                    }
+                    Err(error) => {
+                        self.report_issue(&ron_path, &class, error.to_string());
+                        return None;
+                    }
+                }
+            }
+        };
+
+        let remap_data = RemapData::new(&ron_path, &bin_path);
+        remap_data.transfer_uid();
+        self.remaps.push(remap_data);
+
+        Some(PackedByteArray::from(bytes.as_slice()))
+    }
+
+    /// Record problem with `.gdron` file, which is left out of exported project.
+    pub fn report_issue(&mut self, path: &GString, class: &GString, message: String) {
+        self.report.push(GdExportIssue {
+            path: path.to_string(),
+            class: class.to_string(),
+            message,
+        });
+    }
 }
 
 /// Trait containing most of the logic necessary for [EditorExportPlugin] to be able to handle
@@ -34,8 +312,20 @@ where
         + Inherits<Object>
         + IEditorExportPlugin
         + Bounds<Memory = MemRefCounted>
-        + GodotDefault,
+        + GodotDefault
+        + WithBaseField,
 {
+    /// Default compression of `.gdbin` files created from `.gdron` files during export. Set with
+    /// `#[export_compression]` attribute of `#[gd_props_plugin]` macro. If set, `.gdron` files are converted and
+    /// compressed by default, unless export preset chooses otherwise.
+    const EXPORT_COMPRESSION: GdPropCompression = GdPropCompression::None;
+
+    /// Fingerprint of the extension build, generated by `#[gd_props_plugin]` macro from name and version of the crate
+    /// defining the plugin and declarations of registered resources. Translations of `.gdron` files cached by builds
+    /// with other fingerprint aren't reused during export, as registered resources could have changed. Changes of
+    /// types used in fields of registered resources, which aren't registered themselves, don't change the fingerprint.
+    const BUILD_FINGERPRINT: u64 = 0;
+
     #[doc(hidden)]
     fn _int_state_mut(&mut self) -> &mut ExporterState;
 
@@ -49,6 +339,103 @@ where
         &mut self._int_state_mut().debug
     }
 
+    /// Problems found with `.gdron` files during the last export. Every file with a problem is left out of exported
+    /// project, and the problem is logged as an error, or a warning if `report_as_errors` option is disabled. Godot
+    /// doesn't allow export plugins to fail the export, so it still succeeds.
+    fn export_report(&mut self) -> &[GdExportIssue] {
+        self._int_state_mut().report()
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Options added to export presets, chosen separately in every preset.
+    fn _int_export_options() -> Array<Dictionary> {
+        let ron_export = Self::_int_default_ron_export().ord();
+        let compression = EXPORT_COMPRESSIONS
+            .iter()
+            .position(|compression| *compression == Self::EXPORT_COMPRESSION)
+            .unwrap_or_default() as i64;
+
+        let mut options = Array::new();
+        for (name, default) in [
+            (OPTION_DEBUG_RON, ron_export),
+            (OPTION_RELEASE_RON, ron_export),
+        ] {
+            options.push(export_option(
+                name,
+                VariantType::INT,
+                PropertyHint::ENUM,
+                GdRonExport::HINT,
+                default.to_variant(),
+            ));
+        }
+        options.push(export_option(
+            OPTION_COMPRESSION,
+            VariantType::INT,
+            PropertyHint::ENUM,
+            "Zstd,Lz4,Deflate",
+            compression.to_variant(),
+        ));
+        options.push(export_option(
+            OPTION_DEBUG_ONLY,
+            VariantType::PACKED_STRING_ARRAY,
+            PropertyHint::NONE,
+            "",
+            PackedStringArray::new().to_variant(),
+        ));
+        options.push(export_option(
+            OPTION_REPORT_AS_ERRORS,
+            VariantType::BOOL,
+            PropertyHint::NONE,
+            "",
+            true.to_variant(),
+        ));
+        options.push(export_option(
+            OPTION_USE_CACHE,
+            VariantType::BOOL,
+            PropertyHint::NONE,
+            "",
+            true.to_variant(),
+        ));
+        options
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Handling of `.gdron` files if export preset doesn't choose it.
+    fn _int_default_ron_export() -> GdRonExport {
+        GdRonExport::default_for(Self::EXPORT_COMPRESSION)
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Whether `.gdron` files are translated into `.gdbin` in current export.
+    fn _int_converts_ron(&mut self) -> bool {
+        self._int_state_mut().ron_export() != GdRonExport::Keep
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Whether the file is left out of current export, as it is debug-only and the export is release.
+    fn _int_is_stripped(&mut self, path: GString) -> bool {
+        self._int_state_mut().is_stripped(&path.to_string())
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Log problems found since the `from`-th one as errors, or as warnings if export preset doesn't
+    /// report them as errors.
+    fn _int_publish_issues(&mut self, from: usize) {
+        let state = self._int_state_mut();
+        let as_errors = state.report_as_errors;
+        let messages = state.report[from..]
+            .iter()
+            .map(|issue| format!("can't export {issue}"))
+            .collect::<Vec<_>>();
+        for message in messages {
+            if as_errors {
+                godot_error!("[{}]: {}", Self::class_name(), message);
+            } else {
+                godot_warn!("[{}]: {}", Self::class_name(), message);
+            }
+        }
+    }
+
     #[doc(hidden)]
     fn _int_ron_to_bin_change_path(path: GString) -> GString {
         let stringified = path.to_string();
@@ -70,72 +457,165 @@ where
         path.to_string().ends_with(".gdbin")
     }
 
-    // #[doc(hidden)]
-    // fn _int_verify_processable(
-    //     &self,
-    //     type_: GString,
-    //     resource: Gd<Resource>
-    // ) {
-    //     let loader_extensions = ResourceLoader::singleton().get_recognized_extensions_for_type(type_.clone());
-    //     let saver_extensions = ResourceSaver::singleton().get_recognized_extensions(resource);
-
-    //     if !GdPropFormat::verify_supported_extensions(&loader_extensions) {
-    //         godot_warn!("ResourceLoader doesn't recognize GdProp file extensions for resource of type: {type_}. Make sure that custom format loader have been registered");
-    //     }
-    //     if !GdPropFormat::verify_supported_extensions(&saver_extensions) {
-    //         godot_warn!("ResourceSaver doesn't recognize GdProp file extensions for resource of type: {type_}. Make sure that custom format saver have been registered");
-    //     }
-    // }
-
     #[doc(hidden)]
-    fn _int_process_ron_file<T>(&mut self, ron_path: GString, bin_path: GString) -> PackedByteArray
+    /// Internal method. Translate `.gdron` file of class `T` into bytes of `.gdbin` file with
+    /// [`ExporterState::process_ron_file`], reporting problems found with it.
+    fn _int_process_ron_file<T>(
+        &mut self,
+        ron_path: GString,
+        bin_path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Option<PackedByteArray>
     where
         T: GdProp,
     {
-        let mut loader = ResourceLoader::singleton();
-        let res = loader.load(ron_path.clone()).expect("can't get ron file");
-        let remap_data = RemapData::new(&ron_path, &bin_path);
-        remap_data.transfer_uid();
-        save(res, bin_path.clone());
-
-        self._int_remaps().push(remap_data);
+        let reported = self._int_state_mut().report.len();
+        let bytes = self
+            ._int_state_mut()
+            .process_ron_file::<T>(ron_path, bin_path, key);
+        self._int_publish_issues(reported);
+        bytes
+    }
 
-        FileAccess::get_file_as_bytes(bin_path.clone())
+    #[doc(hidden)]
+    /// Internal method. Report `.gdron` file which class isn't registered in the plugin, so it can't be exported.
+    fn _int_report_unregistered(&mut self, ron_path: GString, class: GString) {
+        let message = if class.is_empty() {
+            "can't recognize class of the resource".to_string()
+        } else {
+            "class isn't registered in the plugin".to_string()
+        };
+        let reported = self._int_state_mut().report.len();
+        self._int_state_mut()
+            .report_issue(&ron_path, &class, message);
+        self._int_publish_issues(reported);
     }
 
     #[doc(hidden)]
     fn _int_read_file_to_bytes(path: GString) -> Option<PackedByteArray> {
-        if let Ok(mut file) = GFile::open(path, ModeFlags::READ) {
-            let mut buf = Vec::with_capacity(file.length() as usize);
-            let result = file.read_to_end(&mut buf);
-
-            if let Err(err) = result {
-                godot_error!("Error while reading file: {err}");
-                return None;
-            }
-
-            let mut array = PackedByteArray::new();
-            array.extend(buf);
-            return Some(array);
-        }
-        None
+        read_file_to_bytes(path)
     }
 
     #[doc(hidden)]
     fn _int_export_begin(&mut self, is_debug: bool) {
         self._int_remaps().clear();
-        *self._int_debug() = is_debug;
+        self._int_state_mut().report.clear();
+
+        let plugin = self.to_gd().upcast::<EditorExportPlugin>();
+        self._int_state_mut().configure(
+            is_debug,
+            Self::EXPORT_COMPRESSION,
+            Self::BUILD_FINGERPRINT,
+            |name| plugin.get_option(StringName::from(name)),
+        );
     }
 
     #[doc(hidden)]
     fn _int_export_end(&mut self) {
-        while let Some(remap) = self._int_remaps().pop() {
-            remap.undo_uid();
-            DirAccess::remove_absolute(remap.bin_path.clone());
+        let state = self._int_state_mut();
+        state.undo_remaps();
+        state.prune_cache();
+        if state.report.is_empty() {
+            return;
+        }
+        let as_errors = state.report_as_errors;
+        let files = state
+            .report
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let summary = format!(
+            "{} .gdron file(s) left out of exported project:\n{}",
+            state.report.len(),
+            files
+        );
+        if as_errors {
+            godot_error!("[{}]: {}", Self::class_name(), summary);
+        } else {
+            godot_warn!("[{}]: {}", Self::class_name(), summary);
         }
     }
 }
 
+/// Offset basis of FNV-1a hash, used for fingerprints as it can be computed in `const` context and is stable between
+/// builds.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+const fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut index = 0;
+    while index < bytes.len() {
+        hash ^= bytes[index] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        index += 1;
+    }
+    hash
+}
+
+/// Fingerprint of the resource schema, hashed from its `declaration`. Used by `#[derive(GdProp)]`.
+#[doc(hidden)]
+pub const fn schema_fingerprint(declaration: &str) -> u64 {
+    fnv1a(FNV_OFFSET, declaration.as_bytes())
+}
+
+/// Fingerprint of the extension build, combining name and version of the `package` defining the plugin with
+/// `schemas` fingerprints of registered resources. Used by `#[gd_props_plugin]` macro.
+#[doc(hidden)]
+pub const fn build_fingerprint(package: &str, version: &str, schemas: &[u64]) -> u64 {
+    let mut hash = fnv1a(FNV_OFFSET, package.as_bytes());
+    // Separator, so name and version can't be shifted into each other.
+    hash = fnv1a(hash, &[0]);
+    hash = fnv1a(hash, version.as_bytes());
+    let mut index = 0;
+    while index < schemas.len() {
+        hash = fnv1a(hash, &schemas[index].to_le_bytes());
+        index += 1;
+    }
+    hash
+}
+
+/// Whether the file at `path` is `.gdron` or `.gdbin` file.
+fn is_prop_file(path: &str) -> bool {
+    path.ends_with(".gdron") || path.ends_with(".gdbin")
+}
+
+fn read_file_to_bytes(path: GString) -> Option<PackedByteArray> {
+    if let Ok(mut file) = GFile::open(path, ModeFlags::READ) {
+        let mut buf = Vec::with_capacity(file.length() as usize);
+        let result = file.read_to_end(&mut buf);
+
+        if let Err(err) = result {
+            godot_error!("Error while reading file: {err}");
+            return None;
+        }
+
+        let mut array = PackedByteArray::new();
+        array.extend(buf);
+        return Some(array);
+    }
+    None
+}
+
+/// Export option in the form expected from [`IEditorExportPlugin::get_export_options`].
+fn export_option(
+    name: &str,
+    type_: VariantType,
+    hint: PropertyHint,
+    hint_string: &str,
+    default: Variant,
+) -> Dictionary {
+    dict! {
+        "option": dict! {
+            "name": name,
+            "type": type_.ord(),
+            "hint": hint.ord(),
+            "hint_string": hint_string,
+        },
+        "default_value": default,
+    }
+}
+
 #[doc(hidden)]
 pub struct RemapData {
     ron_path: GString,
@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use godot::builtin::meta::ToGodot;
+use godot::builtin::{dict, Dictionary, GString, StringName};
+use godot::classes::{ClassDb, Resource};
+use godot::obj::{Gd, GodotClass, Inherits};
+use serde::de;
+
+use crate::gd_meta::GdMetaExt;
+
+/// Handling of External Resources which can't be loaded while deserializing the resource, eg. because the referenced
+/// file was removed. Set for the resource with `#[gdprop(missing_ext = Skip)]` attribute.
+///
+/// References which couldn't be loaded are listed by [`broken_references`], regardless of the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GdExtMissingPolicy {
+    /// Fail loading the whole resource.
+    #[default]
+    Error,
+    /// Leave missing references out: they are skipped in `ext_array` and `ext_hashmap` collections, and `ext_option`
+    /// fields are set to `None`. Fields annotated with `ext` can't be left out, so they still fail loading.
+    Skip,
+    /// Substitute every missing reference with a new instance of the expected class. The placeholder keeps the original
+    /// reference, so it is saved back unchanged.
+    Placeholder,
+}
+
+thread_local! {
+    /// Policies of the resources being deserialized on the current thread, with references found to be broken.
+    static CONTEXTS: RefCell<Vec<(GdExtMissingPolicy, Vec<GdMetaExt>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Broken references found during last load of every file.
+static BROKEN: OnceLock<Mutex<HashMap<String, Vec<GdMetaExt>>>> = OnceLock::new();
+
+/// Meta of the placeholder resource, keeping the reference it was substituted for.
+const PLACEHOLDER_META: &str = "_gd_props_missing_ext";
+
+/// External Resources which couldn't be loaded during the last load of `gd-props` file located at `path`. They were
+/// handled according to [`GdExtMissingPolicy`] of its resource.
+pub fn broken_references(path: &str) -> Vec<GdMetaExt> {
+    BROKEN
+        .get()
+        .and_then(|broken| broken.lock().ok()?.get(path).cloned())
+        .unwrap_or_default()
+}
+
+impl GdExtMissingPolicy {
+    /// Deserialize the file located at `path` with the policy applied to its External Resources, saving broken ones
+    /// for [`broken_references`].
+    pub(crate) fn enter<F, R>(self, path: &GString, load: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let (loaded, broken) = self.apply(load);
+        if let Ok(mut all_broken) = BROKEN.get_or_init(Default::default).lock() {
+            if broken.is_empty() {
+                all_broken.remove(&path.to_string());
+            } else {
+                all_broken.insert(path.to_string(), broken);
+            }
+        }
+        loaded
+    }
+
+    /// Deserialize the resource which isn't loaded from a file, eg. from bytes, with the policy applied to its External
+    /// Resources. Broken ones aren't saved for [`broken_references`], as there is no file to list them for.
+    pub(crate) fn enter_pathless<F, R>(self, load: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.apply(load).0
+    }
+
+    /// Run `load` with the policy applied, returning its result with references found to be broken.
+    fn apply<F, R>(self, load: F) -> (R, Vec<GdMetaExt>)
+    where
+        F: FnOnce() -> R,
+    {
+        CONTEXTS.with_borrow_mut(|contexts| contexts.push((self, Vec::new())));
+        let loaded = load();
+        let broken = CONTEXTS
+            .with_borrow_mut(|contexts| contexts.pop())
+            .map(|(_, broken)| broken)
+            .unwrap_or_default();
+        (loaded, broken)
+    }
+
+    /// Policy of currently deserialized resource. References deserialized outside of `gd-props` file load fail on
+    /// error.
+    fn current() -> Self {
+        CONTEXTS.with_borrow(|contexts| {
+            contexts
+                .last()
+                .map_or(GdExtMissingPolicy::Error, |(policy, _)| *policy)
+        })
+    }
+}
+
+impl GdMetaExt {
+    /// Load the referenced resource of class `T`, handling it with current [`GdExtMissingPolicy`] if it can't be
+    /// loaded. `None` is returned only if the reference can be left out, as indicated by `skippable`.
+    pub(crate) fn resolve<T, E>(&self, skippable: bool) -> Result<Option<Gd<T>>, E>
+    where
+        T: GodotClass + Inherits<Resource>,
+        E: de::Error,
+    {
+        if let Some(resource) = self
+            .try_load()
+            .and_then(|resource| resource.try_cast::<T>().ok())
+        {
+            return Ok(Some(resource));
+        }
+
+        CONTEXTS.with_borrow_mut(|contexts| {
+            if let Some((_, broken)) = contexts.last_mut() {
+                broken.push(self.clone());
+            }
+        });
+
+        match GdExtMissingPolicy::current() {
+            GdExtMissingPolicy::Skip if skippable => Ok(None),
+            GdExtMissingPolicy::Placeholder => match self.placeholder::<T>() {
+                Some(placeholder) => Ok(Some(placeholder)),
+                None => Err(E::custom(format!(
+                    "cannot create placeholder of class {} for External Resource: {}",
+                    T::class_name(),
+                    self.path
+                ))),
+            },
+            _ => Err(E::custom(format!(
+                "cannot load External Resource of class {}: {}",
+                T::class_name(),
+                self.path
+            ))),
+        }
+    }
+
+    /// New instance of class `T` substituted for the reference.
+    fn placeholder<T>(&self) -> Option<Gd<T>>
+    where
+        T: GodotClass + Inherits<Resource>,
+    {
+        let instance =
+            ClassDb::singleton().instantiate(StringName::from(T::class_name().to_string()));
+        let placeholder = instance.try_to::<Gd<T>>().ok()?;
+        let reference = dict! {
+            "gd_class": self.gd_class.clone(),
+            "uid": self.uid.clone(),
+            "path": self.path.clone(),
+        };
+        placeholder
+            .clone()
+            .upcast::<Resource>()
+            .set_meta(PLACEHOLDER_META.into(), reference.to_variant());
+        Some(placeholder)
+    }
+
+    /// Reference kept by the placeholder substituted for it.
+    pub(crate) fn from_placeholder(resource: &Gd<Resource>) -> Option<Self> {
+        if !resource.has_meta(PLACEHOLDER_META.into()) {
+            return None;
+        }
+        let reference = resource
+            .get_meta(PLACEHOLDER_META.into())
+            .try_to::<Dictionary>()
+            .ok()?;
+        let field = |name: &str| -> Option<String> {
+            Some(reference.get(name)?.try_to::<GString>().ok()?.to_string())
+        };
+        Some(Self {
+            gd_class: field("gd_class")?,
+            uid: field("uid")?,
+            path: field("path")?,
+        })
+    }
+}
@@ -0,0 +1,195 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use godot::builtin::GString;
+use godot::classes::{Resource, ResourceLoader, ResourceUid};
+use godot::global::Error;
+use godot::obj::{Gd, GodotClass, Inherits};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::gd_meta::{GdExtResource, GdMetaExt};
+
+/// Handle to External Resource, loaded with [`ResourceLoader`] only when accessed for the first time.
+///
+/// It is serialized the same as [`Gd`] annotated with [`serde_gd::ext`](crate::serde_gd::ext), so one can be swapped for
+/// the other without changing saved files. Unlike it, deserializing the handle doesn't load the referenced resource,
+/// so resources referencing many others, like item databases, can be loaded without pulling all of them into memory.
+///
+/// ## Example
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// use gd_props::serde_gd::GdExtRef;
+/// use godot::prelude::*;
+/// use godot::classes::Texture2D;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(init, base=Resource)]
+/// struct ItemDatabase {
+///     icons: HashMap<String, GdExtRef<Texture2D>>,
+/// }
+///
+/// impl ItemDatabase {
+///     fn icon(&self, item: &str) -> Option<Gd<Texture2D>> {
+///         self.icons.get(item)?.get()
+///     }
+/// }
+/// ```
+pub struct GdExtRef<T>
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    meta: GdMetaExt,
+    state: RefCell<ExtRefState<T>>,
+}
+
+enum ExtRefState<T: GodotClass> {
+    Unloaded,
+    /// Requested to be loaded on other thread under given path.
+    Requested(GString),
+    Loaded(Gd<T>),
+}
+
+impl<T> GdExtRef<T>
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    /// Reference the `resource`, which needs to be saved in a file. The handle keeps it loaded.
+    pub fn new(resource: Gd<T>) -> Self {
+        Self {
+            meta: GdMetaExt::from_resource(&resource.clone().upcast()),
+            state: RefCell::new(ExtRefState::Loaded(resource)),
+        }
+    }
+
+    /// Reference the resource saved at `path`, without loading it.
+    pub fn from_path(path: impl Into<GString>) -> Self {
+        let path = path.into();
+        let uid = ResourceLoader::singleton().get_resource_uid(path.clone());
+        Self {
+            meta: GdMetaExt {
+                gd_class: T::class_name().to_string(),
+                uid: ResourceUid::singleton().id_to_text(uid).to_string(),
+                path: path.to_string(),
+            },
+            state: RefCell::new(ExtRefState::Unloaded),
+        }
+    }
+
+    /// Class, UID and path of the referenced resource.
+    pub fn meta(&self) -> &GdMetaExt {
+        &self.meta
+    }
+
+    /// Path of the referenced resource.
+    pub fn path(&self) -> &str {
+        &self.meta.path
+    }
+
+    /// Whether the resource was already loaded by the handle.
+    pub fn is_loaded(&self) -> bool {
+        matches!(*self.state.borrow(), ExtRefState::Loaded(_))
+    }
+
+    /// Get the resource, loading it on first access. Returns `None` if it can't be loaded or isn't of class `T`.
+    pub fn get(&self) -> Option<Gd<T>> {
+        // State isn't borrowed during load, as it can load other resources.
+        let loaded = match self.state.replace(ExtRefState::Unloaded) {
+            ExtRefState::Loaded(resource) => resource,
+            ExtRefState::Requested(path) => ResourceLoader::singleton()
+                .load_threaded_get(path)
+                .or_else(|| self.meta.try_load())?
+                .try_cast::<T>()
+                .ok()?,
+            ExtRefState::Unloaded => self.meta.try_load()?.try_cast::<T>().ok()?,
+        };
+        *self.state.borrow_mut() = ExtRefState::Loaded(loaded.clone());
+        Some(loaded)
+    }
+
+    /// Request the resource to be loaded on other thread with `ResourceLoader.load_threaded_request`, so it is ready
+    /// when accessed with [`GdExtRef::get`]. Does nothing if it was already loaded or requested.
+    pub fn preload(&self) {
+        if !matches!(*self.state.borrow(), ExtRefState::Unloaded) {
+            return;
+        }
+        let path = self.meta.load_path();
+        let error = ResourceLoader::singleton()
+            .load_threaded_request_ex(path.clone())
+            .type_hint(GString::from(&self.meta.gd_class))
+            .done();
+        if error == Error::OK {
+            *self.state.borrow_mut() = ExtRefState::Requested(path);
+        }
+    }
+
+    /// Release the resource kept by the handle. It is loaded again on next access, from Godot's cache if it is still
+    /// referenced elsewhere.
+    pub fn unload(&self) {
+        if let ExtRefState::Requested(path) = self.state.replace(ExtRefState::Unloaded) {
+            // Threaded request needs to be retrieved to be released.
+            ResourceLoader::singleton().load_threaded_get(path);
+        }
+    }
+}
+
+impl<T> Clone for GdExtRef<T>
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn clone(&self) -> Self {
+        let state = match &*self.state.borrow() {
+            ExtRefState::Loaded(resource) => ExtRefState::Loaded(resource.clone()),
+            // Threaded request is retrieved only by the original handle.
+            ExtRefState::Unloaded | ExtRefState::Requested(_) => ExtRefState::Unloaded,
+        };
+        Self {
+            meta: self.meta.clone(),
+            state: RefCell::new(state),
+        }
+    }
+}
+
+impl<T> fmt::Debug for GdExtRef<T>
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GdExtRef")
+            .field("meta", &self.meta)
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
+
+impl<T> Serialize for GdExtRef<T>
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        GdExtResource::ExtResource(self.meta.clone()).serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for GdExtRef<T>
+where
+    T: GodotClass + Inherits<Resource>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match GdExtResource::deserialize(deserializer)? {
+            GdExtResource::ExtResource(meta) => Ok(Self {
+                meta,
+                state: RefCell::new(ExtRefState::Unloaded),
+            }),
+            GdExtResource::None => Err(de::Error::custom("no meta found")),
+        }
+    }
+}
@@ -1,126 +1,453 @@
-use std::io::BufRead;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::ops::Range;
 
 use godot::builtin::GString;
 use godot::classes::file_access::ModeFlags;
-use godot::classes::{FileAccess, Resource, ResourceLoader, ResourceUid};
+use godot::classes::{Resource, ResourceLoader, ResourceUid};
 use godot::obj::Gd;
 
 use godot::tools::GFile;
+#[cfg(any(feature = "json", feature = "cbor"))]
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::atomic::write_atomic;
+#[cfg(feature = "bincode")]
+use crate::codec::BincodeCodec;
+#[cfg(feature = "cbor")]
+use crate::codec::CborCodec;
+#[cfg(feature = "json")]
+use crate::codec::JsonCodec;
+#[cfg(feature = "postcard")]
+use crate::codec::PostcardCodec;
+use crate::codec::{BinCodec, GdPropCodec, GdPropFormat, RonCodec};
+use crate::compression::GdPropCompression;
 use crate::errors::GdPropError;
+use crate::integrity::GdPropChecksum;
+use crate::loading::GdPropLoadContext;
+use crate::security::{GdPropKey, GdPropSeal};
 
-#[derive(Serialize, Deserialize)]
-pub(crate) struct GdMetaHeader {
+/// Header of every `gd-props` file, containing the identifier of the saved struct, its UID and schema version.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GdMetaHeader {
     pub gd_class: String,
     pub uid: String,
+    /// Schema version of the saved resource. Files saved before versioning was introduced are treated as version `0`.
+    #[serde(default)]
+    pub version: u32,
+    /// Compression of the payload.
+    #[serde(default, skip_serializing_if = "GdPropCompression::is_none")]
+    pub compression: GdPropCompression,
+    /// Seal of the payload, present in files saved with [`GdPropKey`](crate::codec::GdPropKey).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seal: Option<GdPropSeal>,
+    /// Length and checksum of the stored payload, present in binary files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<GdPropChecksum>,
 }
 
 impl GdMetaHeader {
-    pub fn read_from_gdron_header(path: GString) -> Result<Self, GdPropError> {
-        let fa = FileAccess::open(path.clone(), ModeFlags::READ);
-        if fa.is_none() {
-            return Err(GdPropError::OpenFileRead);
-        }
-        let mut fa = fa.unwrap();
-        let line = fa.get_line().to_string();
-        fa.close();
-        let meta = ron::from_str::<GdMetaHeader>(&line);
+    pub(crate) fn read_from_file<C: GdPropCodec>(path: GString) -> Result<Self, GdPropError> {
+        let mut gfile = GFile::open(path, ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        C::read_header(&mut gfile)
+    }
+
+    /// Replace the header of the file. Header length can change, so the payload is rewritten after it.
+    pub(crate) fn write_to_file<C: GdPropCodec>(&self, path: GString) -> Result<(), GdPropError> {
+        let mut gfile =
+            GFile::open(path.clone(), ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let mut content = Vec::new();
+        gfile
+            .read_to_end(&mut content)
+            .map_err(GdPropError::FileRead)?;
+        drop(gfile);
 
-        if let Err(error) = meta {
-            return Err(GdPropError::HeaderDeserialize(error));
+        let mut payload = content.as_slice();
+        C::read_header(&mut payload)?;
+
+        write_atomic(&path, 0, |writer| {
+            C::write_header(self, writer)?;
+            writer.write_all(payload).map_err(GdPropError::FileWrite)
+        })
+    }
+
+    /// Read the header of file in given `format`.
+    pub(crate) fn read_from_file_in(
+        format: GdPropFormat,
+        path: GString,
+    ) -> Result<Self, GdPropError> {
+        match format {
+            GdPropFormat::GdRon => Self::read_from_file::<RonCodec>(path),
+            GdPropFormat::GdBin => Self::read_from_file::<BinCodec>(path),
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => Self::read_from_file::<JsonCodec>(path),
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => Self::read_from_file::<CborCodec>(path),
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => Self::read_from_file::<BincodeCodec>(path),
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => Self::read_from_file::<PostcardCodec>(path),
+            GdPropFormat::None => Err(GdPropError::UnrecognizedFormat(path.to_string())),
         }
-        Ok(meta.unwrap())
     }
 
-    pub fn write_to_gdron_header(&self, path: GString) -> Result<(), GdPropError> {
-        let ser_res = ron::to_string(&self);
-        if ser_res.is_err() {
-            return Err(GdPropError::HeaderSerialize);
+    /// Replace the header of file in given `format`.
+    pub(crate) fn write_to_file_in(
+        &self,
+        format: GdPropFormat,
+        path: GString,
+    ) -> Result<(), GdPropError> {
+        match format {
+            GdPropFormat::GdRon => self.write_to_file::<RonCodec>(path),
+            GdPropFormat::GdBin => self.write_to_file::<BinCodec>(path),
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => self.write_to_file::<JsonCodec>(path),
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => self.write_to_file::<CborCodec>(path),
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => self.write_to_file::<BincodeCodec>(path),
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => self.write_to_file::<PostcardCodec>(path),
+            GdPropFormat::None => Err(GdPropError::UnrecognizedFormat(path.to_string())),
         }
-        let ser = ser_res.unwrap();
+    }
 
-        let fa = FileAccess::open(path, ModeFlags::READ_WRITE);
-        if fa.is_none() {
-            return Err(GdPropError::OpenFileWrite);
+    /// Verify the `payload` stored after the header, if the header contains its checksum.
+    pub fn verify_checksum(&self, payload: &[u8]) -> Result<(), GdPropError> {
+        match &self.checksum {
+            Some(checksum) => checksum.verify(payload),
+            None => Ok(()),
         }
-        let mut fa = fa.unwrap();
+    }
 
-        fa.store_line(GString::from(ser));
+    /// Verify that the header contains `expected` struct identifier.
+    pub fn check_class(&self, expected: &str) -> Result<(), GdPropError> {
+        if self.gd_class != expected {
+            return Err(GdPropError::ClassMismatch {
+                expected: expected.to_string(),
+                found: self.gd_class.clone(),
+            });
+        }
         Ok(())
     }
 
-    pub fn write_to_gdbin_header(&self, path: GString) -> Result<(), GdPropError> {
-        let mut fa =
-            FileAccess::open(path, ModeFlags::READ_WRITE).ok_or(GdPropError::OpenFileWrite)?;
+    /// Point the UID from header to the `path` of loaded file.
+    pub(crate) fn register_uid(&self, path: GString) {
+        let mut resource_uid = ResourceUid::singleton();
+        let uid = resource_uid.text_to_id(GString::from(&self.uid));
+        let uid_exists = resource_uid.has_id(uid);
+        if !uid_exists {
+            resource_uid.add_id(uid, path);
+        } else {
+            resource_uid.set_id(uid, path);
+        }
+    }
+}
+
+/// Reference to External Resource, saved in place of the resource by [`serde_gd`](crate::serde_gd) `ext` modules.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GdMetaExt {
+    pub gd_class: String,
+    pub uid: String,
+    pub path: String,
+}
+
+impl GdMetaExt {
+    /// Identifier of the enum variant wrapping the reference in the payload.
+    const EXT_IDENT: &'static str = "ExtResource";
 
-        self.write_to_gdbin_fa(&mut fa);
+    /// Name of the field keeping the path in serialized reference.
+    const PATH_FIELD: &'static str = "path";
 
-        Ok(())
+    /// Reference to the `resource` saved in a file.
+    pub(crate) fn from_resource(resource: &Gd<Resource>) -> Self {
+        if let Some(reference) = Self::from_placeholder(resource) {
+            return reference;
+        }
+        let path = resource.get_path();
+        let uid = ResourceLoader::singleton().get_resource_uid(path.clone());
+        Self {
+            gd_class: resource.get_class().to_string(),
+            uid: ResourceUid::singleton().id_to_text(uid).to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    /// Find references in the file located at `path`, opening it with the `key` if it is sealed.
+    pub(crate) fn read_from_file<C: GdPropCodec>(
+        path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Result<Vec<Self>, GdPropError> {
+        let mut gfile = GFile::open(path, ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let (_, payload) = C::read_opened(&mut gfile, key)?;
+        C::read_payload(&mut payload.as_slice())?.ext_references()
     }
 
-    pub fn read_from_gdbin_header(path: GString) -> Result<Self, GdPropError> {
-        let mut fa = FileAccess::open(path, ModeFlags::READ).ok_or(GdPropError::OpenFileRead)?;
+    /// Point references in the file located at `path` to new paths, as given in `renames` keyed with old paths. The
+    /// file is rewritten, and sealed again with the `key`, only if any reference was changed.
+    pub(crate) fn rename_in_file<C: GdPropCodec>(
+        path: GString,
+        renames: &HashMap<String, String>,
+        key: Option<&GdPropKey>,
+    ) -> Result<usize, GdPropError> {
+        let mut gfile =
+            GFile::open(path.clone(), ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let (header, payload) = C::read_opened(&mut gfile, key)?;
+        drop(gfile);
 
-        Ok(Self::read_from_gdbin_fa(&mut fa))
+        let (renamed, count) =
+            C::read_payload(&mut payload.as_slice())?.rename_ext_references(renames)?;
+        if count > 0 {
+            let renamed = renamed.into_bytes();
+            write_atomic(&path, 0, |writer| {
+                C::write_sealed(&header, &renamed, key, writer)
+            })?;
+        }
+        Ok(count)
     }
 
-    pub fn write_to_gdbin_fa(&self, fa: &mut Gd<FileAccess>) {
-        fa.store_pascal_string(GString::from(&self.gd_class));
-        fa.store_pascal_string(GString::from(&self.uid));
+    /// Find references in the file in given `format`.
+    pub(crate) fn read_from_file_in(
+        format: GdPropFormat,
+        path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Result<Vec<Self>, GdPropError> {
+        match format {
+            GdPropFormat::GdRon => Self::read_from_file::<RonCodec>(path, key),
+            GdPropFormat::GdBin => Self::read_from_file::<BinCodec>(path, key),
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => Self::read_from_file::<JsonCodec>(path, key),
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => Self::read_from_file::<CborCodec>(path, key),
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => Self::read_from_file::<BincodeCodec>(path, key),
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => Self::read_from_file::<PostcardCodec>(path, key),
+            GdPropFormat::None => Err(GdPropError::UnrecognizedFormat(path.to_string())),
+        }
     }
 
-    pub fn read_from_gdbin_fa(fa: &mut Gd<FileAccess>) -> Self {
-        let gd_class = fa.get_pascal_string().to_string();
-        let uid = fa.get_pascal_string().to_string();
+    /// Point references in the file in given `format` to new paths.
+    pub(crate) fn rename_in_file_in(
+        format: GdPropFormat,
+        path: GString,
+        renames: &HashMap<String, String>,
+        key: Option<&GdPropKey>,
+    ) -> Result<usize, GdPropError> {
+        match format {
+            GdPropFormat::GdRon => Self::rename_in_file::<RonCodec>(path, renames, key),
+            GdPropFormat::GdBin => Self::rename_in_file::<BinCodec>(path, renames, key),
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => Self::rename_in_file::<JsonCodec>(path, renames, key),
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => Self::rename_in_file::<CborCodec>(path, renames, key),
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => Self::rename_in_file::<BincodeCodec>(path, renames, key),
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => Self::rename_in_file::<PostcardCodec>(path, renames, key),
+            GdPropFormat::None => Err(GdPropError::UnrecognizedFormat(path.to_string())),
+        }
+    }
 
-        Self { gd_class, uid }
+    /// Find references in `.gdron` payload, without knowing its schema.
+    pub(crate) fn find_in_ron(payload: &str) -> Vec<Self> {
+        Self::spans_in_ron(payload)
+            .into_iter()
+            .map(|(_, meta)| meta)
+            .collect()
     }
 
-    pub fn from_gfile_ron(gfile: &mut GFile) -> Result<Self, GdPropError> {
-        let mut serialized = String::new();
-        let res = gfile.read_line(&mut serialized);
-        if let Err(error) = res {
-            return Err(GdPropError::FileRead(error));
+    /// Find references in `.gdron` payload, together with the byte ranges of their serialized content.
+    fn spans_in_ron(payload: &str) -> Vec<(Range<usize>, Self)> {
+        let mut found = Vec::new();
+        for (index, _) in payload.match_indices(Self::EXT_IDENT) {
+            let rest = payload[index + Self::EXT_IDENT.len()..].trim_start();
+            let Some(rest) = rest.strip_prefix('(') else {
+                continue;
+            };
+            if let Ok(mut deserializer) = ron::Deserializer::from_str(rest) {
+                if let Ok(meta) = Self::deserialize(&mut deserializer) {
+                    let start = payload.len() - rest.len();
+                    let end = payload.len() - deserializer.remainder().len();
+                    found.push((start..end, meta));
+                }
+            }
         }
-        match ron::from_str::<Self>(&serialized) {
-            Ok(header) => Ok(header),
-            Err(error) => Err(GdPropError::HeaderDeserialize(error)),
+        found
+    }
+
+    /// Point references in `.gdron` payload to new paths, as given in `renames` keyed with old paths. Only the path
+    /// string is replaced, so formatting of the hand-edited payload is kept.
+    pub(crate) fn rename_in_ron(
+        payload: &str,
+        renames: &HashMap<String, String>,
+    ) -> Result<(String, usize), GdPropError> {
+        let mut renamed = String::with_capacity(payload.len());
+        let mut copied = 0;
+        let mut count = 0;
+
+        for (span, meta) in Self::spans_in_ron(payload) {
+            let Some(new_path) = renames.get(&meta.path) else {
+                continue;
+            };
+            let old_literal = ron::to_string(&meta.path).map_err(GdPropError::RonSerialize)?;
+            let new_literal = ron::to_string(new_path).map_err(GdPropError::RonSerialize)?;
+
+            let content = &payload[span.clone()];
+            // Class or UID could be serialized as the same string, so the field name is checked.
+            let Some((position, _)) = content.match_indices(&old_literal).find(|(position, _)| {
+                content[..*position]
+                    .trim_end()
+                    .strip_suffix(':')
+                    .is_some_and(|field| field.trim_end().ends_with(Self::PATH_FIELD))
+            }) else {
+                continue;
+            };
+
+            renamed.push_str(&payload[copied..span.start + position]);
+            renamed.push_str(&new_literal);
+            copied = span.start + position + old_literal.len();
+            count += 1;
         }
+        renamed.push_str(&payload[copied..]);
+        Ok((renamed, count))
+    }
+
+    /// Find references in `.gdbin` payload, without knowing its schema. Enum variants are saved in MessagePack as
+    /// single-entry maps keyed with variant name.
+    pub(crate) fn find_in_bin(payload: &[u8]) -> Result<Vec<Self>, GdPropError> {
+        let value =
+            rmp_serde::from_slice::<rmpv::Value>(payload).map_err(GdPropError::BinDeserialize)?;
+        let mut found = Vec::new();
+        Self::find_in_value(&value, &mut found);
+        Ok(found)
+    }
+
+    /// Point references in `.gdbin` payload to new paths, as given in `renames` keyed with old paths.
+    pub(crate) fn rename_in_bin(
+        payload: &[u8],
+        renames: &HashMap<String, String>,
+    ) -> Result<(Vec<u8>, usize), GdPropError> {
+        let mut value =
+            rmp_serde::from_slice::<rmpv::Value>(payload).map_err(GdPropError::BinDeserialize)?;
+        let count = Self::rename_in_value(&mut value, renames);
+        let renamed = rmp_serde::to_vec(&value).map_err(GdPropError::BinSerialize)?;
+        Ok((renamed, count))
+    }
+
+    /// Find references in self-describing `value` of other format, by transcoding it into MessagePack value.
+    #[cfg(any(feature = "json", feature = "cbor"))]
+    pub(crate) fn find_in_serializable<V: Serialize>(value: &V) -> Result<Vec<Self>, GdPropError> {
+        let value = rmpv::ext::to_value(value)
+            .map_err(|error| GdPropError::FormatDeserialize("rmpv", error.into()))?;
+        let mut found = Vec::new();
+        Self::find_in_value(&value, &mut found);
+        Ok(found)
+    }
+
+    /// Point references in self-describing `value` of other format to new paths, by transcoding it into MessagePack
+    /// value and back.
+    #[cfg(any(feature = "json", feature = "cbor"))]
+    pub(crate) fn rename_in_serializable<V: Serialize + DeserializeOwned>(
+        value: &V,
+        renames: &HashMap<String, String>,
+    ) -> Result<(V, usize), GdPropError> {
+        let mut value = rmpv::ext::to_value(value)
+            .map_err(|error| GdPropError::FormatDeserialize("rmpv", error.into()))?;
+        let count = Self::rename_in_value(&mut value, renames);
+        let renamed = rmpv::ext::from_value(value)
+            .map_err(|error| GdPropError::FormatSerialize("rmpv", error.into()))?;
+        Ok((renamed, count))
     }
 
-    pub fn to_gfile_ron(&self, gfile: &mut GFile) -> Result<(), GdPropError> {
-        match ron::to_string(self) {
-            Ok(serialized) => {
-                let res = gfile.write_gstring_line(&serialized);
-                if let Err(error) = res {
-                    return Err(GdPropError::FileWrite(error));
+    fn rename_in_value(value: &mut rmpv::Value, renames: &HashMap<String, String>) -> usize {
+        match value {
+            rmpv::Value::Map(entries) => {
+                if let [(rmpv::Value::String(key), inner)] = entries.as_mut_slice() {
+                    if key.as_str() == Some(Self::EXT_IDENT) {
+                        if let Ok(meta) = rmpv::ext::from_value::<Self>(inner.clone()) {
+                            return match renames.get(&meta.path) {
+                                Some(new_path) => Self::set_path_in_value(inner, new_path),
+                                None => 0,
+                            };
+                        }
+                    }
                 }
-                Ok(())
+                entries
+                    .iter_mut()
+                    .map(|(key, inner)| {
+                        Self::rename_in_value(key, renames) + Self::rename_in_value(inner, renames)
+                    })
+                    .sum()
             }
-            Err(_) => Err(GdPropError::HeaderSerialize),
+            rmpv::Value::Array(items) => items
+                .iter_mut()
+                .map(|item| Self::rename_in_value(item, renames))
+                .sum(),
+            _ => 0,
         }
     }
 
-    pub fn to_gfile_bin(&self, gfile: &mut GFile) {
-        gfile
-            .write_pascal_string(GString::from(&self.gd_class))
-            .expect("cannot write class");
-        gfile
-            .write_pascal_string(GString::from(&self.uid))
-            .expect("cannot write uid");
+    /// Replace the path of reference serialized either as sequence or as map, returning number of changed paths.
+    fn set_path_in_value(meta: &mut rmpv::Value, new_path: &str) -> usize {
+        let path = match meta {
+            rmpv::Value::Array(fields) => fields.get_mut(2),
+            rmpv::Value::Map(entries) => entries
+                .iter_mut()
+                .find(|(field, _)| field.as_str() == Some(Self::PATH_FIELD))
+                .map(|(_, path)| path),
+            _ => None,
+        };
+        match path {
+            Some(path) => {
+                *path = rmpv::Value::from(new_path);
+                1
+            }
+            None => 0,
+        }
     }
-}
 
-#[derive(Serialize, Deserialize)]
-pub(crate) struct GdMetaExt {
-    pub gd_class: String,
-    pub uid: String,
-    pub path: String,
-}
+    fn find_in_value(value: &rmpv::Value, found: &mut Vec<Self>) {
+        match value {
+            rmpv::Value::Map(entries) => {
+                if let [(rmpv::Value::String(key), inner)] = entries.as_slice() {
+                    if key.as_str() == Some(Self::EXT_IDENT) {
+                        if let Ok(meta) = rmpv::ext::from_value::<Self>(inner.clone()) {
+                            found.push(meta);
+                            return;
+                        }
+                    }
+                }
+                for (key, inner) in entries {
+                    Self::find_in_value(key, found);
+                    Self::find_in_value(inner, found);
+                }
+            }
+            rmpv::Value::Array(items) => {
+                for item in items {
+                    Self::find_in_value(item, found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Path used to load the resource: its UID if registered in Godot, or its path otherwise.
+    pub(crate) fn load_path(&self) -> GString {
+        let resource_uid = ResourceUid::singleton();
+        let uid = GString::from(&self.uid);
+        if resource_uid.has_id(resource_uid.text_to_id(uid.clone())) {
+            uid
+        } else {
+            GString::from(&self.path)
+        }
+    }
 
-impl GdMetaExt {
     pub(crate) fn try_load(&self) -> Option<Gd<Resource>> {
+        if let Some(resource) = GdPropLoadContext::retrieve(&self.load_path()) {
+            return Some(resource);
+        }
         let mut resource_loader = ResourceLoader::singleton();
         if let Some(resource) = self.try_load_from_uid(&mut resource_loader) {
             return Some(resource);
@@ -137,6 +464,7 @@ impl GdMetaExt {
             resource_loader
                 .load_ex(GString::from(&self.uid))
                 .type_hint(GString::from(&self.gd_class))
+                .cache_mode(GdPropLoadContext::cache_mode())
                 .done()
         } else {
             None
@@ -146,6 +474,7 @@ impl GdMetaExt {
         resource_loader
             .load_ex(GString::from(&self.path))
             .type_hint(GString::from(&self.gd_class))
+            .cache_mode(GdPropLoadContext::cache_mode())
             .done()
     }
 }
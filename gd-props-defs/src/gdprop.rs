@@ -1,23 +1,30 @@
-use std::io::{BufReader, BufWriter};
+use std::io::Read;
 
 use godot::builtin::meta::ToGodot;
 use godot::builtin::{GString, PackedByteArray, Variant};
 use godot::classes::file_access::ModeFlags;
-use godot::classes::{DirAccess, FileAccess, Resource, ResourceUid};
-use godot::global::{randf, Error};
+use godot::classes::{Resource, ResourceUid};
+use godot::global::Error;
 use godot::log::godot_error;
 use godot::obj::{Gd, GodotClass, Inherits, UserClass};
 use godot::tools::GFile;
-use rmp_serde::Serializer;
 use serde::{Deserialize, Serialize};
 
+use crate::atomic::write_atomic;
+use crate::codec::{BinCodec, GdPropCodec, GdPropCompression, GdPropKey, RonCodec};
+use crate::errors::GdPropError;
+use crate::ext_missing::GdExtMissingPolicy;
 use crate::gd_meta::GdMetaHeader;
+use crate::loading::GdPropLoadContext;
+use crate::migration::GdPropPayload;
 
 /// GdProp saveable resource
 ///
 /// Trait which provides methods to serialize and deserialize rust-defined [Resource](godot::classes::Resource) to:
 /// - `.gdbin` files, based on [MessagePack](rmp_serde)
 /// - `.gdron` files, based on [ron]
+/// - formats of other codecs enabled with cargo features, with generic [`GdProp::save_as`] and [`GdProp::load_as`]
+///   methods. See [`GdPropFormat`](crate::codec::GdPropFormat) for details.
 pub trait GdProp
 where
     Self: Serialize + for<'de> Deserialize<'de> + GodotClass + UserClass + Inherits<Resource>,
@@ -25,244 +32,433 @@ where
     /// Struct identifier included in `gdron` file.
     const HEAD_IDENT: &'static str;
 
-    /// Save object to a file located at `path` in `.gdbin` format.
-    fn save_bin(&self, path: GString) -> Error {
-        let mut uid = -1;
-        let mut resource_uid = ResourceUid::singleton();
+    /// Schema version of the struct, saved in the file header. Set with `#[gdprop(version = N)]` attribute.
+    ///
+    /// Files saved with older version are upgraded during load, see [`GdPropMigrate`](crate::traits::GdPropMigrate).
+    const VERSION: u32 = 0;
 
-        // Check if resource already exists and have UID assigned
-        if let Ok(meta) = GdMetaHeader::read_from_gdbin_header(path.clone()) {
-            uid = resource_uid.text_to_id(GString::from(meta.uid));
+    /// Compression of payloads saved in `.gdbin` files. Set with `#[gdprop(compression = Zstd)]` attribute.
+    const COMPRESSION: GdPropCompression = GdPropCompression::None;
+
+    /// Number of rotating backups of previous file content kept while saving, as `<path>.1.bak` being the most
+    /// recent one. Set with `#[gdprop(backups = N)]` attribute.
+    const BACKUPS: u32 = 0;
+
+    /// Handling of External Resources which can't be loaded, listed afterwards by
+    /// [`broken_references`](crate::serde_gd::broken_references). Set with `#[gdprop(missing_ext = Skip)]` attribute.
+    const MISSING_EXT: GdExtMissingPolicy = GdExtMissingPolicy::Error;
+
+    #[doc(hidden)]
+    /// Hash of the struct declaration, together with its fields, their types and attributes. Generated by
+    /// `#[derive(GdProp)]`, and combined into the fingerprint of the extension build by `#[gd_props_plugin]` macro.
+    const SCHEMA_FINGERPRINT: u64 = 0;
+
+    #[doc(hidden)]
+    /// Internal method. Upgrades `payload` saved with schema `version` by a single step.
+    fn _int_migrate(_version: u32, payload: GdPropPayload) -> Result<GdPropPayload, GdPropError> {
+        Ok(payload)
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Upgrades `payload` saved with schema `version` up to current [`GdProp::VERSION`] and
+    /// deserializes it.
+    fn _int_from_payload(version: u32, mut payload: GdPropPayload) -> Result<Self, GdPropError> {
+        if version > Self::VERSION {
+            return Err(GdPropError::VersionUnsupported {
+                found: version,
+                current: Self::VERSION,
+            });
         }
-        // If UID couldn't be retrieved, create new id
-        if uid == -1 {
-            uid = resource_uid.create_id();
-            // If UID points to another path, remove the old UID
-        } else if resource_uid.has_id(uid) && resource_uid.get_id_path(uid).ne(&path) {
-            resource_uid.remove_id(uid);
+        for step in version..Self::VERSION {
+            payload = Self::_int_migrate(step, payload)?;
         }
+        payload.deserialize::<Self>()
+    }
 
-        let meta = GdMetaHeader {
-            gd_class: Self::HEAD_IDENT.to_string(),
-            uid: resource_uid.id_to_text(uid).to_string(),
-        };
+    /// Save object to a file located at `path` in `.gdbin` format.
+    fn save_bin(&self, path: GString) -> Error {
+        match self.try_save_bin(path.clone()) {
+            Ok(()) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while saving to: {}; {}", path, error);
+                error.to_godot_error()
+            }
+        }
+    }
 
-        if let Some(mut access) = FileAccess::open(path.clone(), ModeFlags::WRITE) {
-            meta.write_to_gdbin_fa(&mut access);
-            if let Ok(file) = GFile::try_from_unique(access) {
-                let bufwriter = BufWriter::new(file);
-                let mut serializer = Serializer::new(bufwriter);
-                let res = self.serialize(&mut serializer);
-
-                if let Err(error) = res {
-                    godot_error!("Error while serializing: {}", error);
-                    return Error::ERR_CANT_CREATE;
-                } else {
-                    // Add new UID only after everything else went OK
-                    let uid_exists = resource_uid.has_id(uid);
-                    if uid_exists {
-                        resource_uid.set_id(uid, path)
-                    } else if !uid_exists {
-                        resource_uid.add_id(uid, path);
-                    }
-
-                    return Error::OK;
-                }
+    /// Save object to a file located at `path` in `.gdbin` format, returning the cause of failure.
+    fn try_save_bin(&self, path: GString) -> Result<(), GdPropError> {
+        self._int_try_save_bin(path, None)
+    }
+
+    /// Save object to a file located at `path` in `.gdbin` format, sealed with the `key`.
+    fn save_bin_with_key(&self, path: GString, key: &GdPropKey) -> Error {
+        match self.try_save_bin_with_key(path.clone(), key) {
+            Ok(()) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while saving to: {}; {}", path, error);
+                error.to_godot_error()
             }
         }
-        Error::ERR_FILE_CANT_WRITE
+    }
+
+    /// Save object to a file located at `path` in `.gdbin` format, sealed with the `key`, returning the cause of
+    /// failure.
+    fn try_save_bin_with_key(&self, path: GString, key: &GdPropKey) -> Result<(), GdPropError> {
+        self._int_try_save_bin(path, Some(key))
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Saves object to `.gdbin` file, sealed with the `key` if provided.
+    fn _int_try_save_bin(&self, path: GString, key: Option<&GdPropKey>) -> Result<(), GdPropError> {
+        self._int_try_save_as::<BinCodec>(path, key)
     }
 
     /// Load object from a file located at `path` in `.gdbin` format.
     fn load_bin(path: GString) -> Variant {
-        if let Some(mut access) = FileAccess::open(path.clone(), ModeFlags::READ) {
-            let meta = GdMetaHeader::read_from_gdbin_fa(&mut access);
-            if meta.gd_class != Self::HEAD_IDENT {
-                godot_error!(
-                    "File {} contains class {}, while expected: {}",
-                    &path,
-                    &meta.gd_class,
-                    Self::HEAD_IDENT
-                );
-                return Error::ERR_FILE_CORRUPT.to_variant();
+        match Self::try_load_bin(path.clone()) {
+            Ok(loaded) => loaded.to_variant(),
+            Err(error) => {
+                godot_error!("Error while loading: {}; {}", path, error);
+                error.to_godot_error().to_variant()
             }
+        }
+    }
 
-            if let Ok(file) = GFile::try_from_unique(access) {
-                let bufread = BufReader::new(file);
-                let res = rmp_serde::from_read::<BufReader<GFile>, Self>(bufread);
-                match res {
-                    Ok(loaded) => {
-                        let mut resource_uid = ResourceUid::singleton();
-                        let uid = resource_uid.text_to_id(GString::from(meta.uid));
-                        let uid_exists = resource_uid.has_id(uid);
-                        if !uid_exists {
-                            resource_uid.add_id(uid, path);
-                        } else {
-                            resource_uid.set_id(uid, path);
-                        }
-                        return Gd::from_object(loaded).to_variant();
-                    }
-                    Err(error) => {
-                        godot_error!("{}", error);
-                        return Error::ERR_FILE_CANT_READ.to_variant();
-                    }
-                }
+    /// Load object from a file located at `path` in `.gdbin` format, returning the cause of failure.
+    fn try_load_bin(path: GString) -> Result<Gd<Self>, GdPropError> {
+        Self::try_load_as::<BinCodec>(path)
+    }
+
+    /// Load object from a file located at `path` in `.gdbin` format, sealed with the `key`.
+    fn load_bin_with_key(path: GString, key: &GdPropKey) -> Variant {
+        match Self::try_load_bin_with_key(path.clone(), key) {
+            Ok(loaded) => loaded.to_variant(),
+            Err(error) => {
+                godot_error!("Error while loading: {}; {}", path, error);
+                error.to_godot_error().to_variant()
             }
         }
-        Error::ERR_FILE_CANT_OPEN.to_variant()
+    }
+
+    /// Load object from a file located at `path` in `.gdbin` format, sealed with the `key`, returning the cause of
+    /// failure. Files which weren't sealed with the `key` or were modified after saving are rejected with
+    /// [`GdPropError::Tampered`].
+    fn try_load_bin_with_key(path: GString, key: &GdPropKey) -> Result<Gd<Self>, GdPropError> {
+        Self::_int_try_load_as::<BinCodec>(path, Some(key))
     }
 
     /// Save object to a file located at `path` in [ron] format.
     fn save_ron(&self, path: GString) -> Error {
-        let mut uid = -1;
-        let mut resource_uid = ResourceUid::singleton();
+        match self.try_save_ron(path.clone()) {
+            Ok(()) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while saving to: {}; {}", path, error);
+                error.to_godot_error()
+            }
+        }
+    }
 
-        // Check if resource already exists and have UID assigned
-        if let Ok(meta) = GdMetaHeader::read_from_gdron_header(path.clone()) {
-            uid = resource_uid.text_to_id(GString::from(meta.uid));
+    /// Save object to a file located at `path` in [ron] format, returning the cause of failure.
+    fn try_save_ron(&self, path: GString) -> Result<(), GdPropError> {
+        self.try_save_as::<RonCodec>(path)
+    }
+
+    /// Translate `.gdron` file located at `path` into bytes of `.gdbin` file.
+    fn translate_ron_to_bin(path: GString) -> PackedByteArray {
+        match Self::try_translate_ron_to_bin(path.clone()) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                godot_error!("Error while translating: {}; {}", path, error);
+                PackedByteArray::new()
+            }
         }
-        // If UID couldn't be retrieved, or retrieved UID points to other path
-        // create new UID
-        if uid == -1 || (resource_uid.has_id(uid) && !resource_uid.get_id_path(uid).eq(&path)) {
-            uid = resource_uid.create_id();
+    }
+
+    /// Translate `.gdron` file located at `path` into bytes of `.gdbin` file, returning the cause of failure.
+    fn try_translate_ron_to_bin(path: GString) -> Result<PackedByteArray, GdPropError> {
+        let mut gfile =
+            GFile::open(path.clone(), ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let mut content = Vec::new();
+        gfile
+            .read_to_end(&mut content)
+            .map_err(GdPropError::FileRead)?;
+        let bytes = Self::_int_translate_ron_to_bin(&path, &content, Self::COMPRESSION, None)?;
+
+        Ok(PackedByteArray::from(bytes.as_slice()))
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Translate `content` of `.gdron` file located at `path` into bytes of `.gdbin` file, compressed
+    /// with `compression` and sealed with the `key`.
+    fn _int_translate_ron_to_bin(
+        path: &GString,
+        content: &[u8],
+        compression: GdPropCompression,
+        key: Option<&GdPropKey>,
+    ) -> Result<Vec<u8>, GdPropError> {
+        let (mut meta, obj) =
+            Self::MISSING_EXT.enter(path, || RonCodec::decode::<Self, _>(&mut &content[..]))?;
+        // Payload is translated with current schema.
+        meta.version = Self::VERSION;
+        meta.compression = compression;
+
+        let mut bytes = Vec::new();
+        BinCodec::encode_with_key(&meta, &obj, key, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Translate `.gdbin` file located at `path` into content of `.gdron` file, keeping its header.
+    fn translate_bin_to_ron(path: GString) -> GString {
+        match Self::try_translate_bin_to_ron(path.clone()) {
+            Ok(content) => content,
+            Err(error) => {
+                godot_error!("Error while translating: {}; {}", path, error);
+                GString::new()
+            }
         }
+    }
 
-        let meta = GdMetaHeader {
-            gd_class: Self::HEAD_IDENT.to_string(),
-            uid: resource_uid.id_to_text(uid).to_string(),
-        };
+    /// Translate `.gdbin` file located at `path` into content of `.gdron` file, keeping its header and returning the
+    /// cause of failure.
+    fn try_translate_bin_to_ron(path: GString) -> Result<GString, GdPropError> {
+        Self::try_translate_bin_to_ron_with_key(path, None)
+    }
+
+    /// Translate `.gdbin` file located at `path`, opened with the `key` if it is sealed, into content of unsealed
+    /// `.gdron` file, keeping the rest of its header and returning the cause of failure.
+    fn try_translate_bin_to_ron_with_key(
+        path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Result<GString, GdPropError> {
+        let mut gfile = GFile::open(path, ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let (mut meta, obj) = BinCodec::decode_with_key::<Self, _>(&mut gfile, key)?;
+        // Payload is translated with current schema, and kept readable.
+        meta.version = Self::VERSION;
+        meta.compression = GdPropCompression::None;
+
+        let mut bytes = Vec::new();
+        RonCodec::encode(&meta, &obj, &mut bytes)?;
+
+        // Ron serializer produces valid UTF-8.
+        Ok(GString::from(String::from_utf8_lossy(&bytes).as_ref()))
+    }
 
-        match GFile::open(path.clone(), ModeFlags::WRITE) {
-            Ok(mut gfile) => {
-                let res = meta.to_gfile_ron(&mut gfile);
-                if let Err(error) = res {
-                    godot_error!("Error while reading header: {}; {}", path, error);
-                    return Error::ERR_FILE_CANT_WRITE;
-                }
-                let mut bufwriter = BufWriter::new(gfile);
-                let res = ron::ser::to_writer_pretty(
-                    &mut bufwriter,
-                    self,
-                    ron::ser::PrettyConfig::default(),
-                );
-
-                match res {
-                    Ok(_) => {
-                        // Add new UID only after everything else went OK
-                        let uid_exists = resource_uid.has_id(uid);
-                        if uid_exists {
-                            resource_uid.set_id(uid, path)
-                        } else {
-                            resource_uid.add_id(uid, path);
-                        }
-
-                        Error::OK
-                    }
-                    Err(error) => {
-                        godot_error!("Error while saving to: {}; {}", path, error);
-                        Error::ERR_CANT_CREATE
-                    }
-                }
+    /// Load object from a file located at `path` in [ron] format.
+    fn load_ron(path: GString) -> Variant {
+        match Self::try_load_ron(path.clone()) {
+            Ok(loaded) => loaded.to_variant(),
+            Err(error) => {
+                godot_error!("Error while loading: {}; {}", path, error);
+                error.to_godot_error().to_variant()
             }
+        }
+    }
+
+    /// Load object from a file located at `path` in [ron] format, returning the cause of failure.
+    fn try_load_ron(path: GString) -> Result<Gd<Self>, GdPropError> {
+        Self::try_load_as::<RonCodec>(path)
+    }
+
+    /// Serialize object into content of `.gdbin` file, eg. to send it over the network or keep it in a database.
+    fn to_bin_bytes(&self) -> PackedByteArray {
+        match self.try_to_bin_bytes() {
+            Ok(bytes) => bytes,
             Err(error) => {
-                godot_error!("Error while saving to: {}; {}", path, error);
-                Error::ERR_FILE_CANT_WRITE
+                godot_error!("Error while serializing {}; {}", Self::HEAD_IDENT, error);
+                PackedByteArray::new()
             }
         }
     }
 
-    fn translate_ron_to_bin(path: GString) -> PackedByteArray {
-        let mut file = GFile::open(path.clone(), ModeFlags::READ).expect("Can't open file");
+    /// Serialize object into content of `.gdbin` file, returning the cause of failure.
+    fn try_to_bin_bytes(&self) -> Result<PackedByteArray, GdPropError> {
+        let bytes = self._int_try_to_bytes::<BinCodec>()?;
+        Ok(PackedByteArray::from(bytes.as_slice()))
+    }
 
-        let meta = GdMetaHeader::from_gfile_ron(&mut file).expect("Can't read meta header");
-        let bufreader = BufReader::new(file);
-        let obj =
-            ron::de::from_reader::<BufReader<GFile>, Self>(bufreader).expect("Can't read ron file");
+    /// Deserialize object from content of `.gdbin` file, created with [`GdProp::to_bin_bytes`].
+    fn from_bin_bytes(bytes: PackedByteArray) -> Variant {
+        match Self::try_from_bin_bytes(bytes) {
+            Ok(loaded) => loaded.to_variant(),
+            Err(error) => {
+                godot_error!("Error while deserializing {}; {}", Self::HEAD_IDENT, error);
+                error.to_godot_error().to_variant()
+            }
+        }
+    }
 
-        let temp_file = TempFile::new();
-        let mut temp_gfile = temp_file.open_write_read();
-        meta.to_gfile_bin(&mut temp_gfile);
+    /// Deserialize object from content of `.gdbin` file, returning the cause of failure.
+    fn try_from_bin_bytes(bytes: PackedByteArray) -> Result<Gd<Self>, GdPropError> {
+        Self::_int_try_from_bytes::<BinCodec>(bytes.as_slice())
+    }
 
-        let bufwriter = BufWriter::new(temp_gfile);
-        let mut serializer = Serializer::new(bufwriter);
-        let res = obj.serialize(&mut serializer);
-        res.expect("Can't write the file as bin");
+    /// Serialize object into content of `.gdron` file.
+    fn to_ron_string(&self) -> GString {
+        match self.try_to_ron_string() {
+            Ok(content) => content,
+            Err(error) => {
+                godot_error!("Error while serializing {}; {}", Self::HEAD_IDENT, error);
+                GString::new()
+            }
+        }
+    }
 
-        temp_file.get_file_as_bytes()
+    /// Serialize object into content of `.gdron` file, returning the cause of failure.
+    fn try_to_ron_string(&self) -> Result<GString, GdPropError> {
+        let bytes = self._int_try_to_bytes::<RonCodec>()?;
+        // Ron serializer produces valid UTF-8.
+        Ok(GString::from(String::from_utf8_lossy(&bytes).as_ref()))
     }
 
-    /// Load object from a file located at `path` in [ron] format.
-    fn load_ron(path: GString) -> Variant {
-        if let Ok(mut gfile) = GFile::open(path.clone(), ModeFlags::READ) {
-            let meta = if let Ok(meta) = GdMetaHeader::from_gfile_ron(&mut gfile) {
-                if meta.gd_class != Self::HEAD_IDENT {
-                    godot_error!(
-                        "File {} contains class {}, while expected: {}",
-                        &path,
-                        &meta.gd_class,
-                        Self::HEAD_IDENT
-                    );
-                    return Error::ERR_FILE_CORRUPT.to_variant();
-                }
-                meta
+    /// Deserialize object from content of `.gdron` file, created with [`GdProp::to_ron_string`] or read from a file.
+    fn from_ron_string(content: GString) -> Variant {
+        match Self::try_from_ron_string(content) {
+            Ok(loaded) => loaded.to_variant(),
+            Err(error) => {
+                godot_error!("Error while deserializing {}; {}", Self::HEAD_IDENT, error);
+                error.to_godot_error().to_variant()
+            }
+        }
+    }
+
+    /// Deserialize object from content of `.gdron` file, returning the cause of failure.
+    fn try_from_ron_string(content: GString) -> Result<Gd<Self>, GdPropError> {
+        Self::_int_try_from_bytes::<RonCodec>(content.to_string().as_bytes())
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Serializes object into content of a file in format of codec `C`. Content isn't tied to any
+    /// file, so its header has no UID.
+    fn _int_try_to_bytes<C: GdPropCodec>(&self) -> Result<Vec<u8>, GdPropError> {
+        let meta = GdMetaHeader {
+            gd_class: Self::HEAD_IDENT.to_string(),
+            uid: String::new(),
+            version: Self::VERSION,
+            // Payloads are compressed in the same way as when saved to files.
+            compression: if C::COMPRESSED {
+                Self::COMPRESSION
             } else {
-                return Error::ERR_FILE_CORRUPT.to_variant();
-            };
-
-            let bufread = BufReader::new(gfile);
-            let res = ron::de::from_reader::<BufReader<GFile>, Self>(bufread);
-            match res {
-                Ok(loaded) => {
-                    let mut resource_uid = ResourceUid::singleton();
-                    let uid = resource_uid.text_to_id(GString::from(meta.uid));
-                    let uid_exists = resource_uid.has_id(uid);
-                    if !uid_exists {
-                        resource_uid.add_id(uid, path);
-                    } else {
-                        resource_uid.set_id(uid, path);
-                    }
-                    return Gd::from_object(loaded).to_variant();
-                }
-                Err(error) => {
-                    godot_error!("{}", error);
-                    return Error::ERR_FILE_CANT_READ.to_variant();
-                }
+                GdPropCompression::None
+            },
+            seal: None,
+            checksum: None,
+        };
+        let mut bytes = Vec::new();
+        C::encode(&meta, self, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Deserializes object from content of a file in format of codec `C`. References which can't be
+    /// loaded are handled with [`GdProp::MISSING_EXT`], but aren't listed by
+    /// [`broken_references`](crate::serde_gd::broken_references), as the content isn't loaded from any file.
+    fn _int_try_from_bytes<C: GdPropCodec>(bytes: &[u8]) -> Result<Gd<Self>, GdPropError> {
+        let (_, loaded) =
+            Self::MISSING_EXT.enter_pathless(|| C::decode::<Self, _>(&mut &bytes[..]))?;
+        Ok(Gd::from_object(loaded))
+    }
+
+    /// Save object to a file located at `path` in format of codec `C`.
+    fn save_as<C: GdPropCodec>(&self, path: GString) -> Error {
+        match self.try_save_as::<C>(path.clone()) {
+            Ok(()) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while saving to: {}; {}", path, error);
+                error.to_godot_error()
             }
         }
-        Error::ERR_FILE_CANT_OPEN.to_variant()
     }
-}
 
-struct TempFile {
-    dir: String,
-    file: String,
-}
+    /// Save object to a file located at `path` in format of codec `C`, returning the cause of failure.
+    fn try_save_as<C: GdPropCodec>(&self, path: GString) -> Result<(), GdPropError> {
+        self._int_try_save_as::<C>(path, None)
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Saves object to a file in format of codec `C`, sealed with the `key` if provided. UID of the
+    /// file already saved at `path` is kept, and the file is replaced atomically.
+    fn _int_try_save_as<C: GdPropCodec>(
+        &self,
+        path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Result<(), GdPropError> {
+        let mut uid = -1;
+        let mut resource_uid = ResourceUid::singleton();
+
+        // Check if resource already exists and have UID assigned
+        if let Ok(meta) = GdMetaHeader::read_from_file::<C>(path.clone()) {
+            uid = resource_uid.text_to_id(GString::from(meta.uid));
+        }
+        // If UID couldn't be retrieved, create new id
+        if uid == -1 {
+            uid = resource_uid.create_id();
+        }
 
-impl TempFile {
-    fn new() -> Self {
-        let mut file = "temp_".to_owned();
-        file.push_str(&randf().abs().to_string());
+        let meta = GdMetaHeader {
+            gd_class: Self::HEAD_IDENT.to_string(),
+            uid: resource_uid.id_to_text(uid).to_string(),
+            version: Self::VERSION,
+            compression: if C::COMPRESSED {
+                Self::COMPRESSION
+            } else {
+                GdPropCompression::None
+            },
+            seal: None,
+            checksum: None,
+        };
 
-        Self {
-            dir: "user://".into(),
-            file,
+        write_atomic(&path, Self::BACKUPS, |writer| {
+            C::encode_with_key(&meta, self, key, writer)
+        })?;
+
+        // Change UID mapping only after the file was replaced. If UID points to another path, remove the old UID
+        if resource_uid.has_id(uid) && resource_uid.get_id_path(uid).ne(&path) {
+            resource_uid.remove_id(uid);
         }
+        let uid_exists = resource_uid.has_id(uid);
+        if uid_exists {
+            resource_uid.set_id(uid, path)
+        } else {
+            resource_uid.add_id(uid, path);
+        }
+
+        Ok(())
     }
 
-    fn open_write_read(&self) -> GFile {
-        GFile::open(format!("{}{}", self.dir, self.file), ModeFlags::WRITE_READ)
-            .expect("Cannot open temporary file write")
+    /// Load object from a file located at `path` in format of codec `C`.
+    fn load_as<C: GdPropCodec>(path: GString) -> Variant {
+        match Self::try_load_as::<C>(path.clone()) {
+            Ok(loaded) => loaded.to_variant(),
+            Err(error) => {
+                godot_error!("Error while loading: {}; {}", path, error);
+                error.to_godot_error().to_variant()
+            }
+        }
     }
 
-    fn get_file_as_bytes(&self) -> PackedByteArray {
-        FileAccess::get_file_as_bytes(GString::from(format!("{}{}", self.dir, self.file)))
+    /// Load object from a file located at `path` in format of codec `C`, returning the cause of failure.
+    fn try_load_as<C: GdPropCodec>(path: GString) -> Result<Gd<Self>, GdPropError> {
+        Self::_int_try_load_as::<C>(path, None)
     }
-}
 
-impl Drop for TempFile {
-    fn drop(&mut self) {
-        let mut da =
-            DirAccess::open(GString::from(&self.dir)).expect("Cannot open user directory!");
-        da.remove(GString::from(&self.file));
+    #[doc(hidden)]
+    /// Internal method. Loads object from a file in format of codec `C`, verifying its seal with the `key`.
+    fn _int_try_load_as<C: GdPropCodec>(
+        path: GString,
+        key: Option<&GdPropKey>,
+    ) -> Result<Gd<Self>, GdPropError> {
+        let mut gfile =
+            GFile::open(path.clone(), ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let (meta, loaded) = Self::MISSING_EXT.enter(&path, || {
+            if GdPropLoadContext::use_sub_threads() {
+                GdPropLoadContext::decode_threaded::<Self, C, _>(&mut gfile, key)
+            } else {
+                C::decode_with_key::<Self, _>(&mut gfile, key)
+            }
+        })?;
+
+        meta.register_uid(path);
+        Ok(Gd::from_object(loaded))
     }
 }
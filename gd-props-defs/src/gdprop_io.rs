@@ -1,63 +1,32 @@
+use std::collections::HashMap;
+
 use godot::builtin::meta::ToGodot;
-use godot::builtin::{GString, PackedStringArray, Variant};
+use godot::builtin::{Dictionary, GString, PackedStringArray, Variant};
+use godot::classes::file_access::ModeFlags;
 use godot::classes::{
-    IResourceFormatLoader, IResourceFormatSaver, ResourceFormatLoader, ResourceFormatSaver,
-    ResourceUid,
+    DirAccess, IResourceFormatLoader, IResourceFormatSaver, ResourceFormatLoader,
+    ResourceFormatSaver, ResourceUid,
 };
 use godot::global::Error;
 use godot::log::{godot_error, godot_warn};
 use godot::obj::{Gd, GodotClass, Inherits, UserClass};
+use godot::tools::GFile;
 
+#[cfg(feature = "bincode")]
+use crate::codec::BincodeCodec;
+#[cfg(feature = "cbor")]
+use crate::codec::CborCodec;
+#[cfg(feature = "json")]
+use crate::codec::JsonCodec;
+#[cfg(feature = "postcard")]
+use crate::codec::PostcardCodec;
+use crate::codec::{BinCodec, GdPropCodec, GdPropFormat, GdPropKey, RonCodec};
 use crate::errors::GdPropError;
-use crate::gd_meta::GdMetaHeader;
+use crate::gd_meta::{GdMetaExt, GdMetaHeader};
 use crate::gdprop::GdProp;
+use crate::loading::GdPropLoadContext;
 use crate::utils::RefCountedSingleton;
 
-#[derive(PartialEq, Eq, Copy, Clone)]
-pub(crate) enum GdPropFormat {
-    GdRon,
-    GdBin,
-    None,
-}
-
-impl GdPropFormat {
-    const SUPPORTED_EXTENSIONS: [&'static str; 2] = ["gdbin", "gdron"];
-
-    // pub(crate) fn verify_supported_extensions(extensions: &PackedStringArray) -> bool {
-    //     for extension in Self::get_supported_extensions().as_slice() {
-    //         if !extensions.as_slice().contains(extension) {
-    //             return false;
-    //         }
-    //     }
-    //     true
-    // }
-
-    pub(crate) fn get_supported_extensions() -> PackedStringArray {
-        PackedStringArray::from(&[
-            GString::from(Self::SUPPORTED_EXTENSIONS[0]),
-            GString::from(Self::SUPPORTED_EXTENSIONS[1]),
-        ])
-    }
-
-    pub(crate) fn recognize_format(path: &str) -> Self {
-        if path.ends_with(GdPropFormat::GdBin.get_recognized_extension()) {
-            return GdPropFormat::GdBin;
-        }
-        if path.ends_with(GdPropFormat::GdRon.get_recognized_extension()) {
-            return GdPropFormat::GdRon;
-        }
-        GdPropFormat::None
-    }
-
-    fn get_recognized_extension(&self) -> &str {
-        match self {
-            GdPropFormat::GdRon => "gdron",
-            GdPropFormat::GdBin => "gdbin",
-            GdPropFormat::None => "",
-        }
-    }
-}
-
 pub trait GdPropLoader
 where
     Self: GodotClass
@@ -66,6 +35,9 @@ where
         + IResourceFormatLoader
         + RefCountedSingleton,
 {
+    /// Formats handled by the loader. Set with `#[formats]` attribute of `#[gd_props_plugin]` macro.
+    const FORMATS: &'static [GdPropFormat] = GdPropFormat::DEFAULT;
+
     /// Associated function to register the created [ResourceFormatLoader] in Godot's [ResourceLoader](godot::classes::ResourceLoader).
     /// To be used in [ExtensionLibrary](godot::prelude::ExtensionLibrary) implementation in `on_level_init()` function, as shown
     /// in example below. Unregistering function [`GdPropLoader::unregister_loader`] should be used in conjuction, in `on_level_deinit()` function
@@ -120,52 +92,143 @@ where
         Self::free_singleton();
     }
 
+    /// Register the loader like [`GdPropLoader::register_loader`], with the `key` used to open sealed `.gdbin` files.
+    /// Files which weren't sealed with the `key` or were modified after saving are rejected. Use with
+    /// [`GdPropSaver::register_saver_with_key`] providing the same key.
+    fn register_loader_with_key(key: GdPropKey) {
+        let mut instance = Self::singleton_refcount();
+        *instance.bind_mut()._int_key_mut() = Some(key);
+        let loader = &mut godot::classes::ResourceLoader::singleton();
+        loader.add_resource_format_loader(instance.upcast());
+    }
+
+    #[doc(hidden)]
+    /// Internal method to get the key used to open `.gdbin` files
+    fn _int_key(&self) -> Option<&GdPropKey>;
+
+    #[doc(hidden)]
+    /// Internal method to set the key used to open `.gdbin` files
+    fn _int_key_mut(&mut self) -> &mut Option<GdPropKey>;
+
     #[doc(hidden)]
     /// Internal method to get resource UID from file
     fn _int_get_uid(&self, path: GString) -> Result<i64, GdPropError> {
-        let str_path = &path.to_string();
-        match GdPropFormat::recognize_format(str_path) {
-            GdPropFormat::GdRon => {
-                let meta = GdMetaHeader::read_from_gdron_header(path)?;
-                let resource_uid = ResourceUid::singleton();
-                Ok(resource_uid.text_to_id(GString::from(meta.uid)))
-            }
-            GdPropFormat::GdBin => {
-                let meta = GdMetaHeader::read_from_gdbin_header(path)?;
-                let resource_uid = ResourceUid::singleton();
-                Ok(resource_uid.text_to_id(GString::from(meta.uid)))
-            }
-            GdPropFormat::None => Err(GdPropError::OpenFileRead),
-        }
+        let format = GdPropFormat::recognize_among(&path.to_string(), Self::FORMATS);
+        let meta = GdMetaHeader::read_from_file_in(format, path)?;
+        let resource_uid = ResourceUid::singleton();
+        Ok(resource_uid.text_to_id(GString::from(meta.uid)))
     }
 
     #[doc(hidden)]
     /// Internal method to get resource type from file
     fn _int_get_type(&self, path: GString) -> Result<String, GdPropError> {
-        let str_path = &path.to_string();
-        match GdPropFormat::recognize_format(str_path) {
-            GdPropFormat::GdRon => {
-                let meta = GdMetaHeader::read_from_gdron_header(path)?;
-                Ok(meta.gd_class)
+        let format = GdPropFormat::recognize_among(&path.to_string(), Self::FORMATS);
+        let meta = GdMetaHeader::read_from_file_in(format, path)?;
+        Ok(meta.gd_class)
+    }
+
+    #[doc(hidden)]
+    /// Internal method to get External Resources referenced in file, as paths optionally followed by `::` and their
+    /// class
+    fn _int_get_dependencies(&self, path: GString, add_types: bool) -> PackedStringArray {
+        let references = match self._int_try_get_dependencies(path.clone()) {
+            Ok(references) => references,
+            // References can't be found without knowing the schema.
+            Err(GdPropError::NotSelfDescribing(_)) => return PackedStringArray::new(),
+            Err(error) => {
+                godot_error!("Error while getting dependencies of: {}; {}", path, error);
+                return PackedStringArray::new();
             }
-            GdPropFormat::GdBin => {
-                let meta = GdMetaHeader::read_from_gdbin_header(path)?;
-                Ok(meta.gd_class)
+        };
+
+        let mut dependencies = Vec::<String>::new();
+        for reference in references {
+            let dependency = if add_types {
+                format!("{}::{}", reference.path, reference.gd_class)
+            } else {
+                reference.path
+            };
+            if !dependencies.contains(&dependency) {
+                dependencies.push(dependency);
             }
-            GdPropFormat::None => Err(GdPropError::OpenFileRead),
         }
+        dependencies.iter().map(GString::from).collect()
+    }
+
+    #[doc(hidden)]
+    /// Internal method to get External Resources referenced in file, returning the cause of failure
+    fn _int_try_get_dependencies(&self, path: GString) -> Result<Vec<GdMetaExt>, GdPropError> {
+        let format = GdPropFormat::recognize_among(&path.to_string(), Self::FORMATS);
+        GdMetaExt::read_from_file_in(format, path, self._int_key())
+    }
+
+    #[doc(hidden)]
+    /// Internal method to point External Resources referenced in file to their new paths, after they were moved
+    fn _int_rename_dependencies(&self, path: GString, renames: Dictionary) -> Error {
+        let renames = renames
+            .iter_shared()
+            .filter_map(|(old, new)| {
+                Some((
+                    old.try_to::<GString>().ok()?.to_string(),
+                    new.try_to::<GString>().ok()?.to_string(),
+                ))
+            })
+            .collect::<HashMap<_, _>>();
+
+        match self._int_try_rename_dependencies(path.clone(), &renames) {
+            Ok(_) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while renaming dependencies of: {}; {}", path, error);
+                error.to_godot_error()
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    /// Internal method to point External Resources referenced in file to their new paths, returning number of changed
+    /// references or the cause of failure
+    fn _int_try_rename_dependencies(
+        &self,
+        path: GString,
+        renames: &HashMap<String, String>,
+    ) -> Result<usize, GdPropError> {
+        let format = GdPropFormat::recognize_among(&path.to_string(), Self::FORMATS);
+        GdMetaExt::rename_in_file_in(format, path, renames, self._int_key())
+    }
+
+    #[doc(hidden)]
+    /// Internal method to load file from file, with options received by `ResourceFormatLoader::load`. External
+    /// Resources of the file are loaded with matching cache mode, and on other threads if `use_sub_threads` is set.
+    fn _int_load_file<T>(&self, path: GString, use_sub_threads: bool, cache_mode: i32) -> Variant
+    where
+        T: GdProp,
+    {
+        GdPropLoadContext::enter(use_sub_threads, cache_mode, || {
+            self._int_load_in_format::<T>(path)
+        })
     }
 
     #[doc(hidden)]
-    /// Internal method to load file from file
-    fn _int_load_file<T>(&self, path: GString) -> Variant
+    /// Internal method to load file in format recognized by its extension
+    fn _int_load_in_format<T>(&self, path: GString) -> Variant
     where
         T: GdProp,
     {
         let str_path = path.to_string();
-        match GdPropFormat::recognize_format(&str_path) {
+        match GdPropFormat::recognize_among(&str_path, Self::FORMATS) {
             GdPropFormat::GdRon => T::load_ron(path),
-            GdPropFormat::GdBin => T::load_bin(path),
+            GdPropFormat::GdBin => match self._int_key() {
+                Some(key) => T::load_bin_with_key(path, key),
+                None => T::load_bin(path),
+            },
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => T::load_as::<JsonCodec>(path),
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => T::load_as::<CborCodec>(path),
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => T::load_as::<BincodeCodec>(path),
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => T::load_as::<PostcardCodec>(path),
             GdPropFormat::None => {
                 godot_warn!("unrecognized format for: {}", &path);
                 Error::ERR_FILE_UNRECOGNIZED.to_variant()
@@ -176,7 +239,58 @@ where
     #[doc(hidden)]
     /// Internal method to get the supported extensions
     fn _int_get_recognized_extensions(&self) -> PackedStringArray {
-        GdPropFormat::get_supported_extensions()
+        get_extensions(Self::FORMATS)
+    }
+
+    #[doc(hidden)]
+    /// Internal method to decompile `.gdbin` file into `.gdron` file saved at `ron_path`
+    fn _int_decompile_bin<T>(&self, path: GString, ron_path: GString) -> Error
+    where
+        T: GdProp,
+    {
+        match self._int_try_decompile_bin::<T>(path.clone(), ron_path) {
+            Ok(()) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while decompiling: {}; {}", path, error);
+                error.to_godot_error()
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    /// Internal method to decompile `.gdbin` file, opened with the key of the loader, into `.gdron` file saved at
+    /// `ron_path`, returning the cause of failure. The `.gdron` file is a copy, so it gets its own UID.
+    fn _int_try_decompile_bin<T>(&self, path: GString, ron_path: GString) -> Result<(), GdPropError>
+    where
+        T: GdProp,
+    {
+        let str_path = path.to_string();
+        if GdPropFormat::recognize_among(&str_path, Self::FORMATS) != GdPropFormat::GdBin {
+            return Err(GdPropError::UnrecognizedFormat(str_path));
+        }
+        let mut gfile = GFile::open(path, ModeFlags::READ).map_err(GdPropError::FileRead)?;
+        let (header, resource) = BinCodec::decode_with_key::<T, _>(&mut gfile, self._int_key())?;
+        drop(gfile);
+
+        // Copy left by the previous decompilation could keep UID of the `.gdbin` file, which would be reused.
+        if GdMetaHeader::read_from_file::<RonCodec>(ron_path.clone())
+            .is_ok_and(|existing| existing.uid == header.uid)
+        {
+            DirAccess::remove_absolute(ron_path.clone());
+        }
+        resource.try_save_as::<RonCodec>(ron_path)
+    }
+
+    #[doc(hidden)]
+    /// Internal method to get the path of `.gdron` file decompiled from `.gdbin` file
+    fn _int_bin_to_ron_change_path(path: GString) -> GString {
+        let stringified = path.to_string();
+        let replace_index = stringified.len() - ".gdbin".len();
+        GString::from(&format!(
+            "{}{}",
+            &stringified[..replace_index],
+            "_from_bin.gdron"
+        ))
     }
 }
 
@@ -188,6 +302,9 @@ where
         + IResourceFormatSaver
         + RefCountedSingleton,
 {
+    /// Formats handled by the saver. Set with `#[formats]` attribute of `#[gd_props_plugin]` macro.
+    const FORMATS: &'static [GdPropFormat] = GdPropFormat::DEFAULT;
+
     /// Associated function to register the created [ResourceFormatSaver] in Godot's [ResourceSaver](godot::classes::ResourceSaver).
     /// Recommended to use in [ExtensionLibrary](godot::prelude::ExtensionLibrary) implementation, in `on_level_init()` function, as shown
     /// in example below. Unregistering function [`GdPropSaver::unregister_saver`] should be used in conjuction, in `on_level_deinit()` function
@@ -241,63 +358,68 @@ where
         Self::free_singleton();
     }
 
+    /// Register the saver like [`GdPropSaver::register_saver`], with the `key` used to seal saved `.gdbin` files. Use
+    /// with [`GdPropLoader::register_loader_with_key`] providing the same key.
+    fn register_saver_with_key(key: GdPropKey) {
+        let mut instance = Self::singleton_refcount();
+        *instance.bind_mut()._int_key_mut() = Some(key);
+        let saver = &mut godot::classes::ResourceSaver::singleton();
+        saver.add_resource_format_saver(instance.upcast::<ResourceFormatSaver>());
+    }
+
+    #[doc(hidden)]
+    /// Internal function. Gets the key used to seal `.gdbin` files
+    fn _int_key(&self) -> Option<&GdPropKey>;
+
+    #[doc(hidden)]
+    /// Internal function. Sets the key used to seal `.gdbin` files
+    fn _int_key_mut(&mut self) -> &mut Option<GdPropKey>;
+
     #[doc(hidden)]
     /// Internal function. Sets UID in file
     fn _int_set_uid(&mut self, path: GString, uid: i64) -> Error {
-        let str_path = path.to_string();
-        let format = GdPropFormat::recognize_format(&str_path);
+        match self._int_try_set_uid(path.clone(), uid) {
+            Ok(()) => Error::OK,
+            Err(error) => {
+                godot_error!("Error while setting UID of: {}; {}", path, error);
+                error.to_godot_error()
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    /// Internal function. Sets UID in file, returning the cause of failure.
+    fn _int_try_set_uid(&mut self, path: GString, uid: i64) -> Result<(), GdPropError> {
+        let format = GdPropFormat::recognize_among(&path.to_string(), Self::FORMATS);
+        let mut meta = GdMetaHeader::read_from_file_in(format, path.clone())?;
+
+        let mut resource_uid = ResourceUid::singleton();
+        let old_uid = resource_uid.text_to_id(GString::from(&meta.uid));
+
+        let uid_exists = resource_uid.has_id(uid);
+        let old_uid_exists = resource_uid.has_id(old_uid);
 
-        if format == GdPropFormat::None {
-            return Error::ERR_FILE_UNRECOGNIZED;
+        if uid_exists && !resource_uid.get_id_path(uid).eq(&path) {
+            return Err(GdPropError::UidConflict {
+                uid: resource_uid.id_to_text(uid).to_string(),
+                path: resource_uid.get_id_path(uid).to_string(),
+            });
         }
 
-        let meta_res = match format {
-            GdPropFormat::GdRon => GdMetaHeader::read_from_gdron_header(path.clone()),
-            GdPropFormat::GdBin => GdMetaHeader::read_from_gdbin_header(path.clone()),
-            GdPropFormat::None => unreachable!(),
-        };
+        meta.uid = resource_uid.id_to_text(uid).to_string();
+        meta.write_to_file_in(format, path.clone())?;
 
-        match meta_res {
-            Ok(mut meta) => {
-                let mut resource_uid = ResourceUid::singleton();
-                let old_uid = resource_uid.text_to_id(GString::from(&meta.uid));
-
-                let uid_exists = resource_uid.has_id(uid);
-                let old_uid_exists = resource_uid.has_id(old_uid);
-
-                if uid_exists && !resource_uid.get_id_path(uid).eq(&path) {
-                    godot_error!("Other resource of this UID already exists! {}", uid);
-                    return Error::ERR_ALREADY_EXISTS;
-                }
-
-                meta.uid = resource_uid.id_to_text(uid).to_string();
-                let write_res = match format {
-                    GdPropFormat::GdRon => meta.write_to_gdron_header(path.clone()),
-                    GdPropFormat::GdBin => meta.write_to_gdbin_header(path.clone()),
-                    GdPropFormat::None => unreachable!(),
-                };
-
-                if write_res.is_err() {
-                    return Error::ERR_FILE_CANT_WRITE;
-                }
-
-                if old_uid_exists {
-                    resource_uid.remove_id(old_uid);
-                }
-
-                if uid_exists {
-                    resource_uid.set_id(uid, path);
-                } else {
-                    resource_uid.add_id(uid, path);
-                }
-
-                Error::OK
-            }
-            Err(error) => {
-                godot_error!("{}", error);
-                Error::ERR_FILE_CANT_READ
-            }
+        if old_uid_exists {
+            resource_uid.remove_id(old_uid);
+        }
+
+        if uid_exists {
+            resource_uid.set_id(uid, path);
+        } else {
+            resource_uid.add_id(uid, path);
         }
+
+        Ok(())
     }
 
     #[doc(hidden)]
@@ -308,14 +430,32 @@ where
     {
         let str_path = path.to_string();
 
-        match GdPropFormat::recognize_format(&str_path) {
+        match GdPropFormat::recognize_among(&str_path, Self::FORMATS) {
             GdPropFormat::GdRon => obj.bind().save_ron(path),
-            GdPropFormat::GdBin => obj.bind().save_bin(path),
+            GdPropFormat::GdBin => match self._int_key() {
+                Some(key) => obj.bind().save_bin_with_key(path, key),
+                None => obj.bind().save_bin(path),
+            },
+            #[cfg(feature = "json")]
+            GdPropFormat::GdJson => obj.bind().save_as::<JsonCodec>(path),
+            #[cfg(feature = "cbor")]
+            GdPropFormat::GdCbor => obj.bind().save_as::<CborCodec>(path),
+            #[cfg(feature = "bincode")]
+            GdPropFormat::GdBincode => obj.bind().save_as::<BincodeCodec>(path),
+            #[cfg(feature = "postcard")]
+            GdPropFormat::GdPostcard => obj.bind().save_as::<PostcardCodec>(path),
             GdPropFormat::None => Error::ERR_UNCONFIGURED,
         }
     }
 
     fn _int_get_recognized_extensions(&self) -> PackedStringArray {
-        GdPropFormat::get_supported_extensions()
+        get_extensions(Self::FORMATS)
     }
 }
+
+fn get_extensions(formats: &[GdPropFormat]) -> PackedStringArray {
+    formats
+        .iter()
+        .map(|format| GString::from(format.extension()))
+        .collect()
+}
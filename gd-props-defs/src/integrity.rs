@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::GdPropError;
+
+/// Length and CRC32 checksum of the payload as stored in the file: after compression and sealing.
+///
+/// Saved in the header of binary files, so files truncated by a crashed write or otherwise damaged are detected before
+/// deserialization. `.gdron` and `.gdjson` files are meant to be edited by hand, so they aren't checksummed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GdPropChecksum {
+    pub len: u64,
+    pub crc32: u32,
+}
+
+impl GdPropChecksum {
+    /// Checksum of the stored `payload`.
+    pub fn of(payload: &[u8]) -> Self {
+        Self {
+            len: payload.len() as u64,
+            crc32: crc32fast::hash(payload),
+        }
+    }
+
+    /// Verify the stored `payload`, returning [`GdPropError::LengthMismatch`] or
+    /// [`GdPropError::ChecksumMismatch`] if it is damaged.
+    pub fn verify(&self, payload: &[u8]) -> Result<(), GdPropError> {
+        let found = Self::of(payload);
+        if found.len != self.len {
+            return Err(GdPropError::LengthMismatch {
+                expected: self.len,
+                found: found.len,
+            });
+        }
+        if found.crc32 != self.crc32 {
+            return Err(GdPropError::ChecksumMismatch {
+                expected: self.crc32,
+                found: found.crc32,
+            });
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,31 @@
+pub(crate) mod adapters;
+pub(crate) mod atomic;
+pub(crate) mod builtin;
+/// Engine-independent encoding and decoding of `gd-props` files.
+pub mod codec;
+pub(crate) mod compression;
+pub mod editor_menu;
 pub mod errors;
+pub(crate) mod export_cache;
 pub mod export_plugin;
+pub(crate) mod ext_missing;
+pub(crate) mod ext_ref;
 pub(crate) mod gd_meta;
 pub(crate) mod gdprop;
 pub(crate) mod gdprop_io;
+pub(crate) mod integrity;
+pub(crate) mod loading;
+pub(crate) mod native;
+/// Replication of resources between peers over Godot multiplayer.
+pub mod network;
+pub(crate) mod registry;
+pub(crate) mod security;
+pub(crate) mod subresource;
 pub(crate) mod utils;
 
+/// Module containing types used to migrate resources saved with older schema versions.
+pub mod migration;
+
 /// Module containing serialization and deserialization modules for pointers to Godot [Resource](godot::classes::Resource)
 /// and their collections.
 pub mod serde_gd;
@@ -14,5 +35,7 @@ pub mod traits {
     pub use super::export_plugin::GdPropExporter;
     pub use super::gdprop::GdProp;
     pub use super::gdprop_io::{GdPropLoader, GdPropSaver};
+    pub use super::migration::GdPropMigrate;
+    pub use super::registry::GdPropRegistry;
     pub use super::utils::RefCountedSingleton;
 }
@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::io::BufRead;
+
+use godot::builtin::GString;
+use godot::classes::resource_loader::CacheMode;
+use godot::classes::{Resource, ResourceLoader};
+use godot::global::Error;
+use godot::obj::{EngineEnum, Gd};
+
+use crate::codec::{GdMetaExt, GdMetaHeader, GdPropCodec, GdPropKey};
+use crate::errors::GdPropError;
+use crate::gdprop::GdProp;
+
+/// `ResourceFormatLoader.CACHE_MODE_IGNORE_DEEP`, available since Godot 4.3.
+const CACHE_MODE_IGNORE_DEEP: i32 = 3;
+/// `ResourceFormatLoader.CACHE_MODE_REPLACE_DEEP`, available since Godot 4.3.
+const CACHE_MODE_REPLACE_DEEP: i32 = 4;
+
+thread_local! {
+    /// Loads in progress on the current thread. Loading External Resources in `gd-props` formats nests them.
+    static CONTEXTS: RefCell<Vec<GdPropLoadContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Options of the load requested by Godot's [`ResourceLoader`], applied to External Resources loaded while
+/// deserializing the file.
+///
+/// Every thread keeps its own contexts, so loads requested with `ResourceLoader.load_threaded_request` don't interfere
+/// with each other.
+pub(crate) struct GdPropLoadContext {
+    use_sub_threads: bool,
+    cache_mode: i32,
+    /// External Resources requested to be loaded on other threads, not retrieved yet.
+    requested: Vec<GString>,
+}
+
+impl GdPropLoadContext {
+    /// Run the `load` with options received by `ResourceFormatLoader::load`.
+    pub(crate) fn enter<F, R>(use_sub_threads: bool, cache_mode: i32, load: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        CONTEXTS.with_borrow_mut(|contexts| {
+            contexts.push(Self {
+                use_sub_threads,
+                cache_mode,
+                requested: Vec::new(),
+            })
+        });
+        let loaded = load();
+        let context = CONTEXTS.with_borrow_mut(|contexts| contexts.pop());
+
+        // Every threaded request needs to be retrieved, even if deserialization failed before reaching it.
+        if let Some(context) = context {
+            let mut loader = ResourceLoader::singleton();
+            for path in context.requested {
+                loader.load_threaded_get(path);
+            }
+        }
+        loaded
+    }
+
+    /// Whether External Resources of currently loaded file can be loaded on other threads.
+    pub(crate) fn use_sub_threads() -> bool {
+        CONTEXTS.with_borrow(|contexts| {
+            contexts
+                .last()
+                .is_some_and(|context| context.use_sub_threads)
+        })
+    }
+
+    /// Cache mode for External Resources of currently loaded file. Like in Godot's own loaders, External Resources
+    /// are reused from cache, unless one of the `_DEEP` cache modes was requested.
+    pub(crate) fn cache_mode() -> CacheMode {
+        let cache_mode = CONTEXTS.with_borrow(|contexts| {
+            contexts
+                .last()
+                .map_or(CacheMode::REUSE.ord(), |context| context.cache_mode)
+        });
+        match cache_mode {
+            CACHE_MODE_IGNORE_DEEP | CACHE_MODE_REPLACE_DEEP => {
+                CacheMode::try_from_ord(cache_mode).unwrap_or(CacheMode::REUSE)
+            }
+            _ => CacheMode::REUSE,
+        }
+    }
+
+    /// Request the External Resource to be loaded on other thread, to be retrieved with [`Self::retrieve`] during
+    /// deserialization.
+    fn request(reference: &GdMetaExt) {
+        let path = reference.load_path();
+        let already_requested = CONTEXTS.with_borrow(|contexts| {
+            contexts
+                .last()
+                .is_some_and(|context| context.requested.contains(&path))
+        });
+        if already_requested {
+            return;
+        }
+
+        let error = ResourceLoader::singleton()
+            .load_threaded_request_ex(path.clone())
+            .type_hint(GString::from(&reference.gd_class))
+            .use_sub_threads(true)
+            .cache_mode(Self::cache_mode())
+            .done();
+
+        if error == Error::OK {
+            CONTEXTS.with_borrow_mut(|contexts| {
+                if let Some(context) = contexts.last_mut() {
+                    context.requested.push(path);
+                }
+            });
+        }
+    }
+
+    /// Retrieve the External Resource requested with [`Self::request`], waiting for its load to finish. Returns `None`
+    /// if it wasn't requested or failed to load.
+    pub(crate) fn retrieve(path: &GString) -> Option<Gd<Resource>> {
+        let requested = CONTEXTS.with_borrow_mut(|contexts| {
+            let context = contexts.last_mut()?;
+            let index = context
+                .requested
+                .iter()
+                .position(|requested| requested == path)?;
+            Some(context.requested.swap_remove(index))
+        })?;
+        ResourceLoader::singleton().load_threaded_get(requested)
+    }
+
+    /// Read whole file like [`GdPropCodec::decode_with_key`], requesting all its External Resources to be loaded on
+    /// other threads before deserializing the payload. Their progress is reported by Godot as the progress of the
+    /// loaded file.
+    pub(crate) fn decode_threaded<T: GdProp, C: GdPropCodec, R: BufRead>(
+        reader: &mut R,
+        key: Option<&GdPropKey>,
+    ) -> Result<(GdMetaHeader, T), GdPropError> {
+        let (header, payload) = C::read_opened(reader, key)?;
+        header.check_class(T::HEAD_IDENT)?;
+
+        // Payloads of formats which aren't self-describing have their External Resources loaded one by one.
+        if let Ok(references) =
+            C::read_payload(&mut payload.as_slice()).and_then(|payload| payload.ext_references())
+        {
+            for reference in &references {
+                Self::request(reference);
+            }
+        }
+
+        let value = C::decode_body(&header, &mut payload.as_slice())?;
+        Ok((header, value))
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[cfg(feature = "bincode")]
+use crate::codec::BincodeCodec;
+#[cfg(feature = "cbor")]
+use crate::codec::CborCodec;
+#[cfg(any(
+    feature = "json",
+    feature = "cbor",
+    feature = "bincode",
+    feature = "postcard"
+))]
+use crate::codec::GdPropCodec;
+#[cfg(feature = "json")]
+use crate::codec::JsonCodec;
+#[cfg(feature = "postcard")]
+use crate::codec::PostcardCodec;
+use crate::errors::GdPropError;
+use crate::gd_meta::GdMetaExt;
+use crate::gdprop::GdProp;
+
+/// Payload of the `gd-props` file, stripped of its header.
+///
+/// Handed over to [`GdPropMigrate::migrate`] when the file was saved with older schema version than the current
+/// [`GdProp::VERSION`]. It is kept in the format it was read from, so the migration steps don't need to be aware whether
+/// they upgrade `.gdron` or `.gdbin` file.
+pub enum GdPropPayload {
+    /// Payload read from `.gdron` file.
+    Ron(String),
+    /// Payload read from `.gdbin` file.
+    Bin(Vec<u8>),
+    /// Payload read from `.gdjson` file.
+    #[cfg(feature = "json")]
+    Json(String),
+    /// Payload read from `.gdcbor` file.
+    #[cfg(feature = "cbor")]
+    Cbor(Vec<u8>),
+    /// Payload read from `.gdbc` file.
+    #[cfg(feature = "bincode")]
+    Bincode(Vec<u8>),
+    /// Payload read from `.gdpc` file.
+    #[cfg(feature = "postcard")]
+    Postcard(Vec<u8>),
+}
+
+impl GdPropPayload {
+    /// Deserialize the payload into `T`. Most often `T` is the struct reflecting the schema of older version.
+    pub fn deserialize<T>(&self) -> Result<T, GdPropError>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            GdPropPayload::Ron(serialized) => {
+                ron::from_str(serialized).map_err(GdPropError::RonDeserialize)
+            }
+            GdPropPayload::Bin(serialized) => {
+                rmp_serde::from_slice(serialized).map_err(GdPropError::BinDeserialize)
+            }
+            #[cfg(feature = "json")]
+            GdPropPayload::Json(serialized) => serde_json::from_str(serialized).map_err(|error| {
+                GdPropError::FormatDeserialize(JsonCodec::EXTENSION, error.into())
+            }),
+            #[cfg(feature = "cbor")]
+            GdPropPayload::Cbor(serialized) => ciborium::from_reader(serialized.as_slice())
+                .map_err(|error| {
+                    GdPropError::FormatDeserialize(CborCodec::EXTENSION, error.into())
+                }),
+            #[cfg(feature = "bincode")]
+            GdPropPayload::Bincode(serialized) => bincode::deserialize(serialized)
+                .map_err(|error| GdPropError::FormatDeserialize(BincodeCodec::EXTENSION, error)),
+            #[cfg(feature = "postcard")]
+            GdPropPayload::Postcard(serialized) => {
+                postcard::from_bytes(serialized).map_err(|error| {
+                    GdPropError::FormatDeserialize(PostcardCodec::EXTENSION, error.into())
+                })
+            }
+        }
+    }
+
+    /// Replace the payload with serialized `value`, keeping the format of the payload.
+    pub fn serialize<T>(self, value: &T) -> Result<Self, GdPropError>
+    where
+        T: Serialize,
+    {
+        match self {
+            GdPropPayload::Ron(_) => {
+                ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                    .map(GdPropPayload::Ron)
+                    .map_err(GdPropError::RonSerialize)
+            }
+            GdPropPayload::Bin(_) => rmp_serde::to_vec(value)
+                .map(GdPropPayload::Bin)
+                .map_err(GdPropError::BinSerialize),
+            #[cfg(feature = "json")]
+            GdPropPayload::Json(_) => serde_json::to_string_pretty(value)
+                .map(GdPropPayload::Json)
+                .map_err(|error| GdPropError::FormatSerialize(JsonCodec::EXTENSION, error.into())),
+            #[cfg(feature = "cbor")]
+            GdPropPayload::Cbor(_) => {
+                let mut serialized = Vec::new();
+                ciborium::into_writer(value, &mut serialized).map_err(|error| {
+                    GdPropError::FormatSerialize(CborCodec::EXTENSION, error.into())
+                })?;
+                Ok(GdPropPayload::Cbor(serialized))
+            }
+            #[cfg(feature = "bincode")]
+            GdPropPayload::Bincode(_) => bincode::serialize(value)
+                .map(GdPropPayload::Bincode)
+                .map_err(|error| GdPropError::FormatSerialize(BincodeCodec::EXTENSION, error)),
+            #[cfg(feature = "postcard")]
+            GdPropPayload::Postcard(_) => postcard::to_stdvec(value)
+                .map(GdPropPayload::Postcard)
+                .map_err(|error| {
+                    GdPropError::FormatSerialize(PostcardCodec::EXTENSION, error.into())
+                }),
+        }
+    }
+
+    /// Deserialize the payload into `Old`, transform it into `New` and serialize it back.
+    ///
+    /// Convenient way to write a single migration step between two structs reflecting consecutive schema versions.
+    pub fn map<Old, New, F>(self, fun: F) -> Result<Self, GdPropError>
+    where
+        Old: DeserializeOwned,
+        New: Serialize,
+        F: FnOnce(Old) -> New,
+    {
+        let old = self.deserialize::<Old>()?;
+        let new = fun(old);
+        self.serialize(&new)
+    }
+
+    /// Find all External Resources referenced in the payload, without knowing its schema.
+    ///
+    /// Payloads of formats which aren't self-describing return [`GdPropError::NotSelfDescribing`].
+    pub fn ext_references(&self) -> Result<Vec<GdMetaExt>, GdPropError> {
+        match self {
+            GdPropPayload::Ron(serialized) => Ok(GdMetaExt::find_in_ron(serialized)),
+            GdPropPayload::Bin(serialized) => GdMetaExt::find_in_bin(serialized),
+            #[cfg(feature = "json")]
+            GdPropPayload::Json(serialized) => {
+                let value =
+                    serde_json::from_str::<serde_json::Value>(serialized).map_err(|error| {
+                        GdPropError::FormatDeserialize(JsonCodec::EXTENSION, error.into())
+                    })?;
+                GdMetaExt::find_in_serializable(&value)
+            }
+            #[cfg(feature = "cbor")]
+            GdPropPayload::Cbor(serialized) => {
+                let value = ciborium::from_reader::<ciborium::Value, _>(serialized.as_slice())
+                    .map_err(|error| {
+                        GdPropError::FormatDeserialize(CborCodec::EXTENSION, error.into())
+                    })?;
+                GdMetaExt::find_in_serializable(&value)
+            }
+            #[cfg(feature = "bincode")]
+            GdPropPayload::Bincode(_) => {
+                Err(GdPropError::NotSelfDescribing(BincodeCodec::EXTENSION))
+            }
+            #[cfg(feature = "postcard")]
+            GdPropPayload::Postcard(_) => {
+                Err(GdPropError::NotSelfDescribing(PostcardCodec::EXTENSION))
+            }
+        }
+    }
+
+    /// Point External Resources referenced in the payload to new paths, as given in `renames` keyed with old paths.
+    /// Returns the payload with number of changed references.
+    ///
+    /// Payloads of formats which aren't self-describing return [`GdPropError::NotSelfDescribing`].
+    pub fn rename_ext_references(
+        self,
+        renames: &HashMap<String, String>,
+    ) -> Result<(Self, usize), GdPropError> {
+        match self {
+            GdPropPayload::Ron(serialized) => {
+                let (renamed, count) = GdMetaExt::rename_in_ron(&serialized, renames)?;
+                Ok((GdPropPayload::Ron(renamed), count))
+            }
+            GdPropPayload::Bin(serialized) => {
+                let (renamed, count) = GdMetaExt::rename_in_bin(&serialized, renames)?;
+                Ok((GdPropPayload::Bin(renamed), count))
+            }
+            #[cfg(feature = "json")]
+            GdPropPayload::Json(serialized) => {
+                let value =
+                    serde_json::from_str::<serde_json::Value>(&serialized).map_err(|error| {
+                        GdPropError::FormatDeserialize(JsonCodec::EXTENSION, error.into())
+                    })?;
+                let (value, count) = GdMetaExt::rename_in_serializable(&value, renames)?;
+                let renamed = serde_json::to_string_pretty(&value).map_err(|error| {
+                    GdPropError::FormatSerialize(JsonCodec::EXTENSION, error.into())
+                })?;
+                Ok((GdPropPayload::Json(renamed), count))
+            }
+            #[cfg(feature = "cbor")]
+            GdPropPayload::Cbor(serialized) => {
+                let value = ciborium::from_reader::<ciborium::Value, _>(serialized.as_slice())
+                    .map_err(|error| {
+                        GdPropError::FormatDeserialize(CborCodec::EXTENSION, error.into())
+                    })?;
+                let (value, count) = GdMetaExt::rename_in_serializable(&value, renames)?;
+                let mut renamed = Vec::new();
+                ciborium::into_writer(&value, &mut renamed).map_err(|error| {
+                    GdPropError::FormatSerialize(CborCodec::EXTENSION, error.into())
+                })?;
+                Ok((GdPropPayload::Cbor(renamed), count))
+            }
+            #[cfg(feature = "bincode")]
+            GdPropPayload::Bincode(_) => {
+                Err(GdPropError::NotSelfDescribing(BincodeCodec::EXTENSION))
+            }
+            #[cfg(feature = "postcard")]
+            GdPropPayload::Postcard(_) => {
+                Err(GdPropError::NotSelfDescribing(PostcardCodec::EXTENSION))
+            }
+        }
+    }
+
+    /// Serialized payload, as saved in the file after the header.
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            GdPropPayload::Ron(serialized) => serialized.into_bytes(),
+            GdPropPayload::Bin(serialized) => serialized,
+            #[cfg(feature = "json")]
+            GdPropPayload::Json(serialized) => serialized.into_bytes(),
+            #[cfg(feature = "cbor")]
+            GdPropPayload::Cbor(serialized) => serialized,
+            #[cfg(feature = "bincode")]
+            GdPropPayload::Bincode(serialized) => serialized,
+            #[cfg(feature = "postcard")]
+            GdPropPayload::Postcard(serialized) => serialized,
+        }
+    }
+}
+
+/// Migration of payloads saved with older schema versions of [`GdProp`] resource.
+///
+/// To be implemented by the user for resources annotated with `#[gdprop(version = N, migrate)]`. While loading a file
+/// saved with version lower than current [`GdProp::VERSION`], the loader calls [`GdPropMigrate::migrate`] for every
+/// version step before deserializing the payload into current type.
+///
+/// Without the `migrate` flag, payloads of older versions are deserialized directly, which is enough for changes
+/// compatible with `serde`, eg. adding a field annotated with `#[serde(default)]`.
+///
+/// ## Example
+/// ```no_run
+/// use godot::prelude::*;
+/// use gd_props::GdProp;
+/// use gd_props::errors::GdPropError;
+/// use gd_props::migration::GdPropPayload;
+/// use gd_props::traits::GdPropMigrate;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct PlayerV0 {
+///     name: String,
+///     hp: u32,
+/// }
+///
+/// #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+/// #[class(init, base=Resource)]
+/// #[gdprop(version = 1, migrate)]
+/// struct Player {
+///     name: String,
+///     health: u32,
+/// }
+///
+/// impl GdPropMigrate for Player {
+///     fn migrate(version: u32, payload: GdPropPayload) -> Result<GdPropPayload, GdPropError> {
+///         match version {
+///             0 => payload.map(|old: PlayerV0| Player { name: old.name, health: old.hp }),
+///             _ => Ok(payload),
+///         }
+///     }
+/// }
+/// ```
+pub trait GdPropMigrate
+where
+    Self: GdProp,
+{
+    /// Upgrade `payload` saved with schema `version` to `version + 1`.
+    fn migrate(version: u32, payload: GdPropPayload) -> Result<GdPropPayload, GdPropError>;
+}
@@ -0,0 +1,138 @@
+use godot::builtin::meta::ToGodot;
+use godot::builtin::{GString, StringName, Variant, VariantType};
+use godot::classes::{ClassDb, Resource};
+use godot::global::PropertyUsageFlags;
+use godot::obj::{Gd, GodotClass, Inherits};
+use serde::{de, ser, Deserialize, Serialize};
+
+use crate::builtin::repr;
+use crate::gd_meta::GdMetaExt;
+
+/// Native Godot resource, eg. [`Curve`](godot::classes::Curve) or [`Gradient`](godot::classes::Gradient), serialized
+/// with its class and values of its stored properties, in the order they are listed by the class.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GdNativeResource {
+    class: String,
+    properties: Vec<(String, GdNativeValue)>,
+}
+
+/// Value of stored property of native resource.
+#[derive(Serialize, Deserialize)]
+enum GdNativeValue {
+    /// Resource without its own file, embedded in the same way as the one holding it.
+    Resource(Box<GdNativeResource>),
+    /// Resource saved in its own file, referenced in the same way as with `ext` modules.
+    ExtResource(GdMetaExt),
+    /// Any other value, serialized like [`Variant`] with [`serde_gd::builtin`](crate::serde_gd::builtin).
+    Value(repr::Variant),
+}
+
+impl GdNativeResource {
+    /// Stored properties of the `resource`, which fails if any of them holds unsupported value.
+    pub(crate) fn from_resource(resource: &Gd<Resource>) -> Result<Self, String> {
+        let mut properties = Vec::new();
+        for property in resource.get_property_list().iter_shared() {
+            let usage = property
+                .get("usage")
+                .and_then(|usage| usage.try_to::<u64>().ok())
+                .unwrap_or_default();
+            if usage & PropertyUsageFlags::STORAGE.ord() == 0 {
+                continue;
+            }
+            let Some(name) = property
+                .get("name")
+                .and_then(|name| name.try_to::<GString>().ok())
+            else {
+                continue;
+            };
+            let value = resource.get(StringName::from(&name));
+            let value = GdNativeValue::from_variant(&value).map_err(|error| {
+                format!("property `{name}` of {}: {error}", resource.get_class())
+            })?;
+            properties.push((name.to_string(), value));
+        }
+        Ok(Self {
+            class: resource.get_class().to_string(),
+            properties,
+        })
+    }
+
+    /// New instance of the resource class, with stored properties set.
+    pub(crate) fn into_resource(self) -> Result<Gd<Resource>, String> {
+        let mut class_db = ClassDb::singleton();
+        let class = StringName::from(&self.class);
+        if !class_db.class_exists(class.clone())
+            || !class_db.is_parent_class(class.clone(), StringName::from("Resource"))
+        {
+            return Err(format!("{} is not a Resource class", self.class));
+        }
+        let mut resource = class_db
+            .instantiate(class)
+            .try_to::<Gd<Resource>>()
+            .map_err(|_| format!("cannot instantiate {}", self.class))?;
+        for (name, value) in self.properties {
+            resource.set(StringName::from(&name), value.into_variant()?);
+        }
+        Ok(resource)
+    }
+
+    pub(crate) fn serialize<S, T>(pointer: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + Inherits<Resource>,
+    {
+        Self::from_resource(&pointer.clone().upcast())
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + Inherits<Resource>,
+    {
+        let native = <Self as Deserialize>::deserialize(deserializer)?;
+        let class = native.class.clone();
+        native
+            .into_resource()
+            .map_err(de::Error::custom)?
+            .try_cast::<T>()
+            .map_err(|_| de::Error::custom(format!("{class} is not of class {}", T::class_name())))
+    }
+}
+
+impl GdNativeValue {
+    fn from_variant(value: &Variant) -> Result<Self, String> {
+        if value.get_type() != VariantType::OBJECT {
+            return repr::Variant::try_from(value).map(Self::Value);
+        }
+        match value.try_to::<Option<Gd<Resource>>>() {
+            Ok(None) => Ok(Self::Value(repr::Variant::Nil)),
+            Ok(Some(resource)) if Self::has_own_file(&resource) => {
+                Ok(Self::ExtResource(GdMetaExt::from_resource(&resource)))
+            }
+            Ok(Some(resource)) => GdNativeResource::from_resource(&resource)
+                .map(|native| Self::Resource(Box::new(native))),
+            Err(_) => Err("only objects inheriting Resource can be serialized".to_string()),
+        }
+    }
+
+    fn into_variant(self) -> Result<Variant, String> {
+        match self {
+            Self::Resource(native) => native.into_resource().map(|resource| resource.to_variant()),
+            Self::ExtResource(meta) => meta
+                .resolve::<Resource, de::value::Error>(true)
+                .map(|resource| {
+                    resource.map_or_else(Variant::nil, |resource| resource.to_variant())
+                })
+                .map_err(|error| error.to_string()),
+            Self::Value(value) => Ok(Variant::from(value)),
+        }
+    }
+
+    /// Whether the resource is saved in its own file, rather than being built-in subresource of other one.
+    fn has_own_file(resource: &Gd<Resource>) -> bool {
+        let path = resource.get_path().to_string();
+        !path.is_empty() && !path.contains("::")
+    }
+}
@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+
+use godot::builtin::PackedByteArray;
+use godot::obj::Gd;
+use serde::{Deserialize, Serialize};
+
+use crate::codec::{BinCodec, GdPropCodec};
+use crate::errors::GdPropError;
+use crate::gdprop::GdProp;
+use crate::subresource::GdSubResources;
+
+/// Packet sent between peers, holding [`GdProp`] resource in MessagePack format of `.gdbin` payload.
+#[derive(Serialize, Deserialize)]
+struct GdPropPacket {
+    gd_class: String,
+    version: u32,
+    body: GdPropPacketBody,
+}
+
+#[derive(Serialize, Deserialize)]
+enum GdPropPacketBody {
+    /// Whole payload of the resource.
+    Full(rmpv::Value),
+    /// Fields of the resource which changed since the one with payload of `base` checksum, by their index.
+    Delta {
+        base: u32,
+        fields: Vec<(u32, rmpv::Value)>,
+    },
+}
+
+/// Replication of [`GdProp`] resource between peers, eg. as argument of Godot multiplayer RPC.
+///
+/// Packets created with [`GdPropSync::pack`] hold the whole resource, while ones created with
+/// [`GdPropSync::pack_delta`] hold only its top-level fields which changed since the previously packed resource. Both
+/// are reconstructed with [`GdPropSync::unpack`], which rejects packets of other class or schema version.
+///
+/// Each side keeps the last resource it packed or unpacked, so the instance should be kept for a single sender and
+/// receiver pair. Delta packets need to arrive in order, so they should be sent with reliable transfer mode - delta which
+/// doesn't apply to the last unpacked resource fails with [`GdPropError::DeltaBaseMismatch`], after which full packet
+/// needs to be sent.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gd_props::traits::GdProp;
+/// # use gd_props::network::GdPropSync;
+/// # fn example<T: GdProp>(resource: Gd<T>, mut sender: GdPropSync<T>, mut receiver: GdPropSync<T>) {
+/// let packet = sender.pack_delta(&*resource.bind()).unwrap();
+/// // send `packet` as RPC argument...
+/// let received: Gd<T> = receiver.unpack(&packet).unwrap();
+/// # }
+/// ```
+pub struct GdPropSync<T: GdProp> {
+    last: Option<(u32, rmpv::Value)>,
+    _resource: PhantomData<fn() -> T>,
+}
+
+impl<T: GdProp> Default for GdPropSync<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: GdProp> GdPropSync<T> {
+    pub fn new() -> Self {
+        Self {
+            last: None,
+            _resource: PhantomData,
+        }
+    }
+
+    /// Pack the whole `resource`, which becomes the base of following delta packets.
+    pub fn pack(&mut self, resource: &T) -> Result<PackedByteArray, GdPropError> {
+        let value = Self::to_value(resource)?;
+        let packet = Self::packet(GdPropPacketBody::Full(value.clone()))?;
+        self.last = Some((Self::checksum(&value)?, value));
+        Ok(packet)
+    }
+
+    /// Pack fields of the `resource` which changed since the previously packed one. Whole resource is packed if there
+    /// is no previous one, or its payload can't be compared by fields.
+    pub fn pack_delta(&mut self, resource: &T) -> Result<PackedByteArray, GdPropError> {
+        let Some((base, last)) = &self.last else {
+            return self.pack(resource);
+        };
+        let value = Self::to_value(resource)?;
+        let fields = match (last, &value) {
+            (rmpv::Value::Array(previous), rmpv::Value::Array(current))
+                if previous.len() == current.len() =>
+            {
+                previous
+                    .iter()
+                    .zip(current)
+                    .enumerate()
+                    .filter(|(_, (previous, current))| previous != current)
+                    .map(|(index, (_, current))| (index as u32, current.clone()))
+                    .collect::<Vec<_>>()
+            }
+            _ => return self.pack(resource),
+        };
+        let packet = Self::packet(GdPropPacketBody::Delta {
+            base: *base,
+            fields,
+        })?;
+        self.last = Some((Self::checksum(&value)?, value));
+        Ok(packet)
+    }
+
+    /// Reconstruct the resource from packet created with [`GdPropSync::pack`] or [`GdPropSync::pack_delta`].
+    pub fn unpack(&mut self, packet: &PackedByteArray) -> Result<Gd<T>, GdPropError> {
+        let packet: GdPropPacket =
+            rmp_serde::from_slice(packet.as_slice()).map_err(GdPropError::BinDeserialize)?;
+        if packet.gd_class != T::HEAD_IDENT {
+            return Err(GdPropError::ClassMismatch {
+                expected: T::HEAD_IDENT.to_string(),
+                found: packet.gd_class,
+            });
+        }
+        if packet.version != T::VERSION {
+            return Err(GdPropError::VersionMismatch {
+                found: packet.version,
+                current: T::VERSION,
+            });
+        }
+
+        let value = match packet.body {
+            GdPropPacketBody::Full(value) => value,
+            GdPropPacketBody::Delta { base, fields } => {
+                let mut value = match &self.last {
+                    Some((checksum, last)) if *checksum == base => last.clone(),
+                    _ => return Err(GdPropError::DeltaBaseMismatch { base }),
+                };
+                let rmpv::Value::Array(items) = &mut value else {
+                    return Err(GdPropError::DeltaBaseMismatch { base });
+                };
+                for (index, field) in fields {
+                    let item = items
+                        .get_mut(index as usize)
+                        .ok_or(GdPropError::DeltaBaseMismatch { base })?;
+                    *item = field;
+                }
+                value
+            }
+        };
+
+        let payload = rmp_serde::to_vec(&value).map_err(GdPropError::BinSerialize)?;
+        let resource = T::MISSING_EXT.enter_pathless(|| {
+            GdSubResources::scope(|| BinCodec::read_body::<T, _>(&mut payload.as_slice()))
+        })?;
+        self.last = Some((crc32fast::hash(&payload), value));
+        Ok(Gd::from_object(resource))
+    }
+
+    /// Forget the last packed or unpacked resource, eg. after the peer reconnected. Next delta will hold the whole
+    /// resource.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn to_value(resource: &T) -> Result<rmpv::Value, GdPropError> {
+        let mut payload = Vec::new();
+        GdSubResources::scope(|| BinCodec::write_body(resource, &mut payload))?;
+        rmp_serde::from_slice(&payload).map_err(GdPropError::BinDeserialize)
+    }
+
+    fn checksum(value: &rmpv::Value) -> Result<u32, GdPropError> {
+        rmp_serde::to_vec(value)
+            .map(|payload| crc32fast::hash(&payload))
+            .map_err(GdPropError::BinSerialize)
+    }
+
+    fn packet(body: GdPropPacketBody) -> Result<PackedByteArray, GdPropError> {
+        let packet = GdPropPacket {
+            gd_class: T::HEAD_IDENT.to_string(),
+            version: T::VERSION,
+            body,
+        };
+        rmp_serde::to_vec(&packet)
+            .map(|bytes| PackedByteArray::from(bytes.as_slice()))
+            .map_err(GdPropError::BinSerialize)
+    }
+}
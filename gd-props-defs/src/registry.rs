@@ -0,0 +1,72 @@
+use godot::classes::Resource;
+use godot::obj::Gd;
+use serde::{de, ser};
+
+use crate::codec::GdPropCodec;
+use crate::errors::GdPropError;
+use crate::gdprop::GdProp;
+
+/// Classes registered in `gd_props_plugin` with `#[register(...)]` attribute, implemented on the plugin struct by the
+/// macro.
+///
+/// Allows fields holding pointers to any of registered classes, eg. `Gd<Resource>` or `Vec<Gd<Resource>>`, to be
+/// bundled with [`GdPoly`](crate::serde_gd::GdPoly) adapter. Every such pointer is serialized as an enum variant named
+/// after [`GdProp::HEAD_IDENT`] of its class, eg. `FireEffect((damage: 5))` in `.gdron` file, and deserialized into
+/// the class named by the variant.
+pub trait GdPropRegistry {
+    /// [`GdProp::HEAD_IDENT`]s of registered classes, in the order of registration.
+    const CLASSES: &'static [&'static str];
+
+    #[doc(hidden)]
+    /// Internal method. Serialize the `resource` as the registered class it is instance of.
+    fn _int_serialize_poly<S>(resource: &Gd<Resource>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer;
+
+    #[doc(hidden)]
+    /// Internal method. Deserialize the registered class identified by `class` from the enum `variant`.
+    fn _int_deserialize_poly<'de, A>(class: &str, variant: A) -> Result<Gd<Resource>, A::Error>
+    where
+        A: de::VariantAccess<'de>;
+
+    #[doc(hidden)]
+    /// Internal method. Serialize the `resource` into content of a file in format of codec `C`, as the registered
+    /// class it is instance of.
+    fn _int_encode_poly<C: GdPropCodec>(resource: &Gd<Resource>) -> Result<Vec<u8>, GdPropError>;
+
+    #[doc(hidden)]
+    /// Internal method. Deserialize the registered class named in the header of content of a file in format of codec
+    /// `C`.
+    fn _int_decode_poly<C: GdPropCodec>(bytes: &[u8]) -> Result<Gd<Resource>, GdPropError>;
+
+    #[doc(hidden)]
+    /// Internal method. Serialize the `resource` of class `T` as the enum variant.
+    fn _int_serialize_as<T, S>(resource: &Gd<Resource>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: GdProp,
+        S: ser::Serializer,
+    {
+        let index = Self::CLASSES
+            .iter()
+            .position(|class| *class == T::HEAD_IDENT)
+            .unwrap_or_default();
+        let resource = resource.clone().cast::<T>();
+        let bound = resource.bind();
+        serializer.serialize_newtype_variant(POLY_NAME, index as u32, T::HEAD_IDENT, &*bound)
+    }
+
+    #[doc(hidden)]
+    /// Internal method. Deserialize the enum `variant` as the class `T`.
+    fn _int_deserialize_as<'de, T, A>(variant: A) -> Result<Gd<Resource>, A::Error>
+    where
+        T: GdProp,
+        A: de::VariantAccess<'de>,
+    {
+        variant
+            .newtype_variant::<T>()
+            .map(|object| Gd::from_object(object).upcast())
+    }
+}
+
+/// Name of the enum wrapping registered classes, passed to serializers.
+pub(crate) const POLY_NAME: &str = "GdPoly";
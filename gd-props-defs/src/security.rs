@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "aes-gcm")]
+use aes_gcm::aead;
+#[cfg(all(feature = "chacha20", not(feature = "aes-gcm")))]
+use chacha20poly1305::aead;
+
+use crate::errors::GdPropError;
+use crate::gd_meta::GdMetaHeader;
+
+/// Secret key protecting `.gdbin` files from being read or edited outside of the game.
+///
+/// Key is provided to the plugin with [`GdPropSaver::register_saver_with_key`](crate::traits::GdPropSaver::register_saver_with_key)
+/// and [`GdPropLoader::register_loader_with_key`](crate::traits::GdPropLoader::register_loader_with_key). Payload of
+/// sealed files is bound to the `gd_class`, `version` and `compression` of their header, so modifying any of them
+/// is detected during load and rejected with [`GdPropError::Tampered`].
+///
+/// Every algorithm needs its cargo feature to be enabled: `hmac`, `aes-gcm` or `chacha20`.
+#[derive(Clone)]
+pub enum GdPropKey {
+    /// Payload is signed with HMAC-SHA256, behind `hmac` feature. File stays readable, but can't be edited.
+    #[cfg(feature = "hmac")]
+    HmacSha256([u8; 32]),
+    /// Payload is encrypted with AES-256-GCM, behind `aes-gcm` feature.
+    #[cfg(feature = "aes-gcm")]
+    Aes256Gcm([u8; 32]),
+    /// Payload is encrypted with ChaCha20-Poly1305, behind `chacha20` feature.
+    #[cfg(feature = "chacha20")]
+    ChaCha20Poly1305([u8; 32]),
+}
+
+/// Seal of the file payload, saved in the file header.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum GdPropSeal {
+    /// Payload is signed with HMAC-SHA256 `mac`.
+    HmacSha256 { mac: Vec<u8> },
+    /// Payload is encrypted with AES-256-GCM using the `nonce`.
+    Aes256Gcm { nonce: Vec<u8> },
+    /// Payload is encrypted with ChaCha20-Poly1305 using the `nonce`.
+    ChaCha20Poly1305 { nonce: Vec<u8> },
+}
+
+impl GdPropKey {
+    /// Seal the already compressed `payload`, saving the seal in the `header`.
+    #[cfg_attr(
+        not(any(feature = "hmac", feature = "aes-gcm", feature = "chacha20")),
+        allow(unused_variables)
+    )]
+    pub fn seal(
+        &self,
+        header: &mut GdMetaHeader,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, GdPropError> {
+        match *self {
+            #[cfg(feature = "hmac")]
+            GdPropKey::HmacSha256(ref key) => {
+                use hmac::Mac;
+
+                let mac = hmac_sha256(key, &authenticated_data(header)?, &payload);
+                header.seal = Some(GdPropSeal::HmacSha256 {
+                    mac: mac.finalize().into_bytes().to_vec(),
+                });
+                Ok(payload)
+            }
+            #[cfg(feature = "aes-gcm")]
+            GdPropKey::Aes256Gcm(ref key) => {
+                let (nonce, encrypted) =
+                    encrypt::<aes_gcm::Aes256Gcm>(key, &authenticated_data(header)?, &payload)?;
+                header.seal = Some(GdPropSeal::Aes256Gcm { nonce });
+                Ok(encrypted)
+            }
+            #[cfg(feature = "chacha20")]
+            GdPropKey::ChaCha20Poly1305(ref key) => {
+                let (nonce, encrypted) = encrypt::<chacha20poly1305::ChaCha20Poly1305>(
+                    key,
+                    &authenticated_data(header)?,
+                    &payload,
+                )?;
+                header.seal = Some(GdPropSeal::ChaCha20Poly1305 { nonce });
+                Ok(encrypted)
+            }
+        }
+    }
+
+    /// Verify the `payload` sealed with `seal` and decrypt it if needed.
+    #[cfg_attr(
+        not(any(feature = "hmac", feature = "aes-gcm", feature = "chacha20")),
+        allow(unused_variables)
+    )]
+    fn open(
+        &self,
+        seal: &GdPropSeal,
+        header: &GdMetaHeader,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, GdPropError> {
+        match (self, seal) {
+            #[cfg(feature = "hmac")]
+            (GdPropKey::HmacSha256(key), GdPropSeal::HmacSha256 { mac }) => {
+                use hmac::Mac;
+
+                hmac_sha256(key, &authenticated_data(header)?, &payload)
+                    .verify_slice(mac)
+                    .map_err(|_| GdPropError::Tampered)?;
+                Ok(payload)
+            }
+            #[cfg(feature = "aes-gcm")]
+            (GdPropKey::Aes256Gcm(key), GdPropSeal::Aes256Gcm { nonce }) => {
+                decrypt::<aes_gcm::Aes256Gcm>(key, nonce, &authenticated_data(header)?, &payload)
+            }
+            #[cfg(feature = "chacha20")]
+            (GdPropKey::ChaCha20Poly1305(key), GdPropSeal::ChaCha20Poly1305 { nonce }) => {
+                decrypt::<chacha20poly1305::ChaCha20Poly1305>(
+                    key,
+                    nonce,
+                    &authenticated_data(header)?,
+                    &payload,
+                )
+            }
+            // File was sealed with other algorithm than the key.
+            _ => Err(GdPropError::Tampered),
+        }
+    }
+}
+
+impl GdPropSeal {
+    /// Open the `payload` of file with given `header`, verifying it with the `key` used by the loader.
+    ///
+    /// Sealed files can't be opened without a key and return [`GdPropError::KeyMissing`]. If the `key` is provided,
+    /// unsealed files and files which payload or header were modified are rejected with [`GdPropError::Tampered`].
+    pub fn open(
+        header: &GdMetaHeader,
+        payload: Vec<u8>,
+        key: Option<&GdPropKey>,
+    ) -> Result<Vec<u8>, GdPropError> {
+        match (&header.seal, key) {
+            (None, None) => Ok(payload),
+            (Some(_), None) => Err(GdPropError::KeyMissing),
+            (None, Some(_)) => Err(GdPropError::Tampered),
+            (Some(seal), Some(key)) => key.open(seal, header, payload),
+        }
+    }
+}
+
+/// Header fields bound to the sealed payload. UID isn't included, as it can be changed by Godot editor.
+#[cfg(any(feature = "hmac", feature = "aes-gcm", feature = "chacha20"))]
+fn authenticated_data(header: &GdMetaHeader) -> Result<Vec<u8>, GdPropError> {
+    rmp_serde::to_vec(&(&header.gd_class, header.version, header.compression))
+        .map_err(GdPropError::BinSerialize)
+}
+
+#[cfg(feature = "hmac")]
+fn hmac_sha256(key: &[u8; 32], authenticated: &[u8], payload: &[u8]) -> hmac::Hmac<sha2::Sha256> {
+    use hmac::Mac;
+
+    let mut mac = <hmac::Hmac<sha2::Sha256> as Mac>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    mac.update(authenticated);
+    mac.update(payload);
+    mac
+}
+
+#[cfg(any(feature = "aes-gcm", feature = "chacha20"))]
+fn encrypt<C: aead::Aead + aead::AeadCore + aead::KeyInit>(
+    key: &[u8; 32],
+    authenticated: &[u8],
+    payload: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), GdPropError> {
+    let cipher = C::new_from_slice(key).map_err(|_| GdPropError::SealFailed)?;
+    let nonce = C::generate_nonce(&mut aead::OsRng);
+    let encrypted = cipher
+        .encrypt(
+            &nonce,
+            aead::Payload {
+                msg: payload,
+                aad: authenticated,
+            },
+        )
+        .map_err(|_| GdPropError::SealFailed)?;
+    Ok((nonce.to_vec(), encrypted))
+}
+
+#[cfg(any(feature = "aes-gcm", feature = "chacha20"))]
+fn decrypt<C: aead::Aead + aead::AeadCore + aead::KeyInit>(
+    key: &[u8; 32],
+    nonce: &[u8],
+    authenticated: &[u8],
+    payload: &[u8],
+) -> Result<Vec<u8>, GdPropError> {
+    let cipher = C::new_from_slice(key).map_err(|_| GdPropError::Tampered)?;
+    let nonce =
+        aead::Nonce::<C>::from_exact_iter(nonce.iter().copied()).ok_or(GdPropError::Tampered)?;
+    cipher
+        .decrypt(
+            &nonce,
+            aead::Payload {
+                msg: payload,
+                aad: authenticated,
+            },
+        )
+        .map_err(|_| GdPropError::Tampered)
+}
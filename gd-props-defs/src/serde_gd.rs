@@ -2,6 +2,14 @@ use godot::obj::{Gd, GodotClass, UserClass};
 
 use serde::{Serialize, Serializer};
 
+pub use crate::adapters::{
+    GdArray, GdAs, GdBuiltin, GdBundled, GdDeserializeAs, GdDictionary, GdExt, GdNative, GdPoly,
+    GdSerializeAs, GdShared,
+};
+pub use crate::builtin::GdSerdeBuiltin;
+pub use crate::ext_missing::{broken_references, GdExtMissingPolicy};
+pub use crate::ext_ref::GdExtRef;
+
 pub(crate) struct GodotPointerSerWrapper<T: GodotClass + UserClass + Serialize>(Gd<T>);
 
 impl<T> Serialize for GodotPointerSerWrapper<T>
@@ -15,6 +23,52 @@ where
         self.0.bind().serialize(serializer)
     }
 }
+/// Module that can be used to serialize and deserialize Godot builtin types, like [`Vector2`](godot::builtin::Vector2),
+/// [`Color`](godot::builtin::Color), [`StringName`](godot::builtin::StringName) or [`Variant`](godot::builtin::Variant),
+/// kept within your custom [`Resource`](godot::classes::Resource).
+///
+/// Every type implementing [`GdSerdeBuiltin`] is supported - see it for their representation. Containers of builtin
+/// types can be handled with [`GdAs`] adapter, eg. `#[serde(with = "GdAs::<Vec<GdBuiltin>>")]`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(init, base=Resource)]
+/// struct SpawnPoint {
+///     #[serde(with="gd_props::serde_gd::builtin")]
+///     #[export]
+///     position: Vector2,
+///     #[serde(with="gd_props::serde_gd::builtin")]
+///     #[export]
+///     tint: Color,
+/// }
+/// ```
+pub mod builtin {
+    use serde::{de, ser};
+
+    use crate::builtin::GdSerdeBuiltin;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GdSerdeBuiltin,
+    {
+        T::deserialize_builtin(deserializer)
+    }
+
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GdSerdeBuiltin,
+    {
+        value.serialize_builtin(serializer)
+    }
+}
+
 /// Module that can be used to serialize and deserialize rust-defined [`GodotClass`]es on basis of their [`Gd`].
 ///
 /// Its main use is to derive [`serde::Serialize`] and [`serde::Deserialize`] on resources containing pointers to other
@@ -209,6 +263,153 @@ pub mod gd_hashmap {
     }
 }
 
+/// Module that can be used to serialize and deserialize rust-defined [`GodotClass`]es on basis of their [`Gd`], keeping
+/// pointers shared between them.
+///
+/// Unlike [`gd`], the pointer referenced from multiple places within the saved payload is written once, with local id
+/// referenced by every next occurrence, and all of them are deserialized back as the same instance. Resources can
+/// reference each other in cycles. Pointers annotated with any of `gd_shared` modules in the same payload share the
+/// ids: [`gd_shared_option`], [`gd_shared_array`] and [`gd_shared_hashmap`].
+///
+/// Resources which are referenced before they are deserialized, as in cycles, need to be default-constructible.
+///
+/// ## Example
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use godot::classes::Resource;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(base=Resource, init)]
+/// struct DialogueNode {
+///     line: String,
+///     #[serde(with="gd_props::serde_gd::gd_shared_option")]
+///     next: Option<Gd<DialogueNode>>,
+/// }
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(base=Resource, init)]
+/// struct Dialogue {
+///     #[serde(with="gd_props::serde_gd::gd_shared_array")]
+///     nodes: Array<Gd<DialogueNode>>,
+/// }
+/// ```
+pub mod gd_shared {
+    use godot::obj::{Gd, GodotClass, NewGd, UserClass};
+    use serde::{de, ser, Deserialize, Serialize};
+
+    use crate::subresource::GdSubResources;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+    {
+        GdSubResources::deserialize(deserializer)
+    }
+
+    pub fn serialize<S, T>(pointer: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + UserClass + Serialize,
+    {
+        GdSubResources::serialize(pointer, serializer)
+    }
+}
+
+/// Module that can be used to serialize and deserialize rust-defined [`GodotClass`]es on basis of their [`Option`]<[`Gd`]>,
+/// keeping pointers shared between them. See [`gd_shared`] for details.
+pub mod gd_shared_option {
+    use godot::obj::{Gd, GodotClass, NewGd, UserClass};
+    use serde::{de, ser, Deserialize, Serialize};
+
+    use crate::subresource::GdSharedWrapper;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Gd<T>>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+    {
+        let wrapper = Option::<GdSharedWrapper<T>>::deserialize(deserializer)?;
+        Ok(wrapper.map(|wrapper| wrapper.0))
+    }
+
+    pub fn serialize<S, T>(pointer: &Option<Gd<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + UserClass + Serialize,
+    {
+        match pointer {
+            Some(ptr) => serializer.serialize_some(&GdSharedWrapper(ptr.clone())),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Module that can be used to serialize and deserialize rust-defined [`GodotClass`]es on basis of their pointers contained
+/// within [`Array`](godot::builtin::Array), keeping pointers shared between them. See [`gd_shared`] for details.
+pub mod gd_shared_array {
+    use godot::builtin::Array;
+    use godot::obj::{Gd, GodotClass, NewGd, UserClass};
+    use serde::{de, ser, Deserialize, Serialize};
+
+    use crate::subresource::GdSharedWrapper;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Array<Gd<T>>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+    {
+        let vec = Vec::<GdSharedWrapper<T>>::deserialize(deserializer)?;
+        Ok(vec.into_iter().map(|wrapper| wrapper.0).collect())
+    }
+
+    pub fn serialize<S, T>(array: &Array<Gd<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + UserClass + Serialize,
+    {
+        let vec: Vec<GdSharedWrapper<T>> = array.iter_shared().map(GdSharedWrapper).collect();
+        vec.serialize(serializer)
+    }
+}
+
+/// Module that can be used to serialize and deserialize rust-defined [`GodotClass`]es on basis of their pointers contained
+/// within [`HashMap`](std::collections::HashMap), keeping pointers shared between them. See [`gd_shared`] for details.
+pub mod gd_shared_hashmap {
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    use godot::obj::{Gd, GodotClass, NewGd, UserClass};
+    use serde::{de, ser, Deserialize, Serialize};
+
+    use crate::subresource::GdSharedWrapper;
+
+    pub fn deserialize<'de, D, T, K>(deserializer: D) -> Result<HashMap<K, Gd<T>>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+        K: Hash + Eq + PartialEq + Deserialize<'de> + Clone,
+    {
+        let map = HashMap::<K, GdSharedWrapper<T>>::deserialize(deserializer)?;
+        Ok(map.into_iter().map(|(k, wrapper)| (k, wrapper.0)).collect())
+    }
+
+    pub fn serialize<S, T, K>(map: &HashMap<K, Gd<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + UserClass + Serialize,
+        K: Hash + Eq + PartialEq + Serialize + Clone,
+    {
+        let wrapper_map: HashMap<&K, GdSharedWrapper<T>> = map
+            .iter()
+            .map(|(k, gd)| (k, GdSharedWrapper(gd.clone())))
+            .collect();
+        wrapper_map.serialize(serializer)
+    }
+}
+
 /// Module that can be used to serialize and deserialize objects castable to [`Resource`](godot::classes::Resource) on basis
 /// of their pointers contained within [`Array`](godot::builtin::Array) collection.
 ///
@@ -279,6 +480,62 @@ pub mod gd_array {
     }
 }
 
+/// Module that can be used to serialize and deserialize native Godot resources, like [`Curve`](godot::classes::Curve),
+/// [`Gradient`](godot::classes::Gradient) or [`StyleBoxFlat`](godot::classes::StyleBoxFlat), embedding them within
+/// the file of your custom [`Resource`](godot::classes::Resource).
+///
+/// The resource is saved with its class and values of all its stored properties, which are serialized like [`Variant`]
+/// with [`builtin`] module, eg. `(class: "Gradient", properties: [("offsets", Value(PackedFloat32Array([0.0, 1.0]))), ...])`
+/// in `.gdron` file. Upon deserialization, new instance of the class is created and the properties are set in the same
+/// order. Properties holding other resources are embedded in the same way, unless the resource is saved in its own file
+/// - then it is referenced like with [`ext`] module. Properties holding other objects, or resources kept in arrays and
+/// dictionaries, can't be serialized.
+///
+/// Optional pointers and containers of them can be handled with [`GdNative`] adapter, eg.
+/// `#[serde(with = "GdAs::<Option<GdNative>>")]`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use godot::prelude::*;
+/// use godot::classes::{Curve, Gradient};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize)]
+/// #[class(init, base=Resource)]
+/// struct WeaponStats {
+///     #[serde(with="gd_props::serde_gd::native")]
+///     #[export]
+///     damage_falloff: Gd<Curve>,
+///     #[serde(with="gd_props::serde_gd::native")]
+///     #[export]
+///     trail: Gd<Gradient>,
+/// }
+/// ```
+pub mod native {
+    use godot::classes::Resource;
+    use godot::obj::{Gd, GodotClass, Inherits};
+    use serde::{de, ser};
+
+    use crate::native::GdNativeResource;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + Inherits<Resource>,
+    {
+        GdNativeResource::deserialize(deserializer)
+    }
+
+    pub fn serialize<S, T>(pointer: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + Inherits<Resource>,
+    {
+        GdNativeResource::serialize(pointer, serializer)
+    }
+}
+
 /// Module that can be used to serialize and deserialize External Resources kept within your custom [`Resource`].  
 ///
 /// External Resource which [`Gd`] is contained within the annotated field don't need to implement [`serde::Serialize`] and
@@ -326,12 +583,8 @@ pub mod ext {
         T: GodotClass + Inherits<Resource>,
     {
         if let GdExtResource::ExtResource(meta) = GdExtResource::deserialize(deserializer)? {
-            let obj = meta
-                .try_load()
-                .ok_or::<D::Error>(de::Error::custom("cannot load resource"))
-                .unwrap();
-
-            Ok(obj.cast::<T>())
+            meta.resolve::<T, D::Error>(false)?
+                .ok_or_else(|| de::Error::custom("cannot load resource"))
         } else {
             Err(de::Error::custom("no meta found"))
         }
@@ -342,18 +595,8 @@ pub mod ext {
         S: ser::Serializer,
         T: GodotClass + Inherits<Resource>,
     {
-        let mut loader = godot::classes::ResourceLoader::singleton();
-        let res_uid = godot::classes::ResourceUid::singleton();
-        let upcasted = pointer.clone().upcast::<Resource>();
-        let path = upcasted.get_path();
-        let gd_class = upcasted.get_class().to_string();
-        let uuid = loader.get_resource_uid(path.clone());
-        GdExtResource::ExtResource(GdMetaExt {
-            gd_class,
-            uid: res_uid.id_to_text(uuid).to_string(),
-            path: path.to_string(),
-        })
-        .serialize(serializer)
+        GdExtResource::ExtResource(GdMetaExt::from_resource(&pointer.clone().upcast()))
+            .serialize(serializer)
     }
 }
 
@@ -407,12 +650,7 @@ pub mod ext_option {
         T: GodotClass + Inherits<Resource>,
     {
         if let GdExtResource::ExtResource(meta) = GdExtResource::deserialize(deserializer)? {
-            let obj = meta
-                .try_load()
-                .ok_or::<D::Error>(de::Error::custom("cannot load resource from path"))
-                .unwrap();
-
-            Ok(Some(obj.cast::<T>()))
+            meta.resolve::<T, D::Error>(true)
         } else {
             Ok(Option::<Gd<T>>::None)
         }
@@ -489,10 +727,9 @@ pub mod ext_hashmap {
 
         for (k, element) in map {
             if let GdExtResource::ExtResource(meta) = element {
-                let obj = meta
-                    .try_load()
-                    .ok_or_else(|| de::Error::custom("cannot load resource"))?;
-                result.insert(k, obj.cast::<T>());
+                if let Some(obj) = meta.resolve::<T, D::Error>(true)? {
+                    result.insert(k, obj);
+                }
             } else {
                 return Err(de::Error::custom("no meta found"));
             }
@@ -507,23 +744,11 @@ pub mod ext_hashmap {
         T: GodotClass + Inherits<Resource>,
         K: Hash + Eq + PartialEq + Serialize + Clone,
     {
-        let mut loader = godot::classes::ResourceLoader::singleton();
-        let res_uid = godot::classes::ResourceUid::singleton();
-
         let external: HashMap<K, GdExtResource> =
             HashMap::from_iter(map.iter().map(|(k, element)| {
-                let upcasted = element.clone().upcast::<Resource>();
-                let path = upcasted.get_path();
-                let gd_class = upcasted.get_class().to_string();
-                let uuid = loader.get_resource_uid(path.clone());
-
                 (
                     k.clone(),
-                    GdExtResource::ExtResource(GdMetaExt {
-                        gd_class,
-                        uid: res_uid.id_to_text(uuid).to_string(),
-                        path: path.to_string(),
-                    }),
+                    GdExtResource::ExtResource(GdMetaExt::from_resource(&element.clone().upcast())),
                 )
             }));
 
@@ -586,10 +811,9 @@ pub mod ext_array {
 
         for element in vec {
             if let GdExtResource::ExtResource(meta) = element {
-                let obj = meta
-                    .try_load()
-                    .ok_or_else(|| de::Error::custom("cannot load resource"))?;
-                result.push(obj.cast::<T>());
+                if let Some(obj) = meta.resolve::<T, D::Error>(true)? {
+                    result.push(obj);
+                }
             } else {
                 return Err(de::Error::custom("no meta found"));
             }
@@ -603,23 +827,9 @@ pub mod ext_array {
         S: ser::Serializer,
         T: GodotClass + Inherits<Resource>,
     {
-        let mut loader = godot::classes::ResourceLoader::singleton();
-        let res_uid = godot::classes::ResourceUid::singleton();
-
         let serialized: Vec<GdExtResource> = vec
             .iter_shared()
-            .map(|element| {
-                let upcasted = element.clone().upcast::<Resource>();
-                let path = upcasted.get_path();
-                let gd_class = upcasted.get_class().to_string();
-                let uuid = loader.get_resource_uid(path.clone());
-
-                GdExtResource::ExtResource(GdMetaExt {
-                    gd_class,
-                    uid: res_uid.id_to_text(uuid).to_string(),
-                    path: path.to_string(),
-                })
-            })
+            .map(|element| GdExtResource::ExtResource(GdMetaExt::from_resource(&element.upcast())))
             .collect();
 
         serialized.serialize(serializer)
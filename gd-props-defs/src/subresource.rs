@@ -0,0 +1,154 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use godot::obj::{Gd, GodotClass, InstanceId, NewGd, UserClass};
+use serde::{de, ser, Deserialize, Serialize};
+
+thread_local! {
+    /// Subresource tables of the payloads being serialized or deserialized on the current thread.
+    static TABLES: RefCell<Vec<GdSubResources>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Table of bundled resources shared within a single payload, like `[sub_resource]` sections of Godot's `.tres` files.
+///
+/// First occurrence of the pointer is serialized with its data and a local id, and every next one only with the id.
+/// During deserialization, every id is resolved to the same [`Gd`] instance. Pointer to the resource which is still
+/// being deserialized is created in advance and filled afterwards, so the resources can reference each other in cycles.
+#[derive(Default)]
+pub(crate) struct GdSubResources {
+    /// Ids of already serialized resources.
+    ids: HashMap<InstanceId, u32>,
+    /// Already deserialized or referenced resources, holding `Gd<T>` of their class.
+    objects: HashMap<u32, Box<dyn Any>>,
+}
+
+/// Serialized form of the shared pointer.
+#[derive(Serialize, Deserialize)]
+enum GdSubResource<T> {
+    SubResource(u32, T),
+    SubResourceRef(u32),
+}
+
+impl GdSubResources {
+    /// Run `f` with new subresource table, shared by all shared pointers of the payload.
+    pub(crate) fn scope<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        TABLES.with_borrow_mut(|tables| tables.push(Self::default()));
+        let result = f();
+        TABLES.with_borrow_mut(|tables| tables.pop());
+        result
+    }
+
+    /// Run `f` with current subresource table, or a new one if the value is (de)serialized outside of `gd-props` codec.
+    fn join<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        if TABLES.with_borrow(|tables| tables.is_empty()) {
+            Self::scope(f)
+        } else {
+            f()
+        }
+    }
+
+    fn with_current<F, R>(f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        TABLES.with_borrow_mut(|tables| {
+            let table = tables
+                .last_mut()
+                .expect("subresource table is entered before use");
+            f(table)
+        })
+    }
+
+    /// Pointer of the subresource with given `id`, created in advance if it wasn't deserialized yet.
+    fn object<T, E>(id: u32) -> Result<Gd<T>, E>
+    where
+        T: GodotClass + UserClass + NewGd,
+        E: de::Error,
+    {
+        Self::with_current(|table| {
+            let object = table
+                .objects
+                .entry(id)
+                .or_insert_with(|| Box::new(T::new_gd()));
+            object.downcast_ref::<Gd<T>>().cloned().ok_or_else(|| {
+                E::custom(format!(
+                    "subresource {id} is not of class {}",
+                    T::class_name()
+                ))
+            })
+        })
+    }
+
+    pub(crate) fn serialize<S, T>(pointer: &Gd<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: GodotClass + UserClass + Serialize,
+    {
+        Self::join(|| {
+            let (id, first) = Self::with_current(|table| {
+                let next = table.ids.len() as u32;
+                match table.ids.get(&pointer.instance_id()) {
+                    Some(id) => (*id, false),
+                    None => {
+                        table.ids.insert(pointer.instance_id(), next);
+                        (next, true)
+                    }
+                }
+            });
+            if first {
+                GdSubResource::SubResource(id, &*pointer.bind()).serialize(serializer)
+            } else {
+                GdSubResource::<&T>::SubResourceRef(id).serialize(serializer)
+            }
+        })
+    }
+
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<Gd<T>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+    {
+        Self::join(|| match GdSubResource::<T>::deserialize(deserializer)? {
+            GdSubResource::SubResource(id, value) => {
+                let mut object = Self::object::<T, D::Error>(id)?;
+                *object.bind_mut() = value;
+                Ok(object)
+            }
+            GdSubResource::SubResourceRef(id) => Self::object::<T, D::Error>(id),
+        })
+    }
+}
+
+/// Shared pointer serialized within collections.
+pub(crate) struct GdSharedWrapper<T: GodotClass>(pub Gd<T>);
+
+impl<T> Serialize for GdSharedWrapper<T>
+where
+    T: GodotClass + UserClass + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        GdSubResources::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for GdSharedWrapper<T>
+where
+    T: GodotClass + UserClass + NewGd + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        GdSubResources::deserialize(deserializer).map(Self)
+    }
+}
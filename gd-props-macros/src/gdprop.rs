@@ -1,17 +1,72 @@
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, ToTokens};
 use venial::Declaration;
 
+use crate::utils::GdPropAttributes;
+
 pub fn derive_resource(decl: Declaration) -> Result<TokenStream, venial::Error> {
     let item = decl
         .as_struct()
         .ok_or_else(|| venial::Error::new("Not a struct!"))?;
 
     let name = &item.name;
+    let schema_fingerprint = Literal::u64_suffixed(
+        gd_props_defs::export_plugin::schema_fingerprint(&decl.to_token_stream().to_string()),
+    );
+
+    let GdPropAttributes {
+        version,
+        migrate,
+        compression,
+        backups,
+        missing_ext,
+    } = GdPropAttributes::declare(item)?;
+
+    let version = version.map(|version| {
+        quote!(
+          const VERSION: u32 = #version;
+        )
+    });
+
+    let compression = compression.map(|compression| {
+        quote!(
+          const COMPRESSION: ::gd_props::codec::GdPropCompression = #compression;
+        )
+    });
+
+    let backups = backups.map(|backups| {
+        quote!(
+          const BACKUPS: u32 = #backups;
+        )
+    });
+
+    let missing_ext = missing_ext.map(|missing_ext| {
+        quote!(
+          const MISSING_EXT: ::gd_props::serde_gd::GdExtMissingPolicy = #missing_ext;
+        )
+    });
+
+    let migrate = migrate.then(|| {
+        quote!(
+            fn _int_migrate(
+                version: u32,
+                payload: ::gd_props::migration::GdPropPayload,
+            ) -> Result<::gd_props::migration::GdPropPayload, ::gd_props::errors::GdPropError>
+            {
+                <Self as ::gd_props::traits::GdPropMigrate>::migrate(version, payload)
+            }
+        )
+    });
 
     Ok(quote!(
       impl ::gd_props::traits::GdProp for #name {
         const HEAD_IDENT: &'static str = stringify!(#name);
+        const SCHEMA_FINGERPRINT: u64 = #schema_fingerprint;
+        #version
+        #compression
+        #backups
+        #missing_ext
+        #migrate
       }
     ))
 }
@@ -21,37 +21,110 @@ pub(crate) mod utils;
 /// #[class(init, base=Resource)]
 /// struct MyResource {}
 /// ```
-#[proc_macro_derive(GdProp)]
+///
+/// ## Schema versioning
+/// Schema version saved in the file header can be set with `#[gdprop(version = N)]` helper attribute. Files saved with older
+/// version are deserialized directly into the current struct, unless `migrate` flag is provided: then the payload is upgraded
+/// step by step with [GdPropMigrate](gd_props_defs::traits::GdPropMigrate) implementation before deserialization.
+///
+/// ```no_run
+/// use godot::prelude::GodotClass;
+/// use gd_props::GdProp;
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+/// #[class(init, base=Resource)]
+/// #[gdprop(version = 2)]
+/// struct MyResource {
+///     #[serde(default)]
+///     added_in_v2: u32,
+/// }
+/// ```
+///
+/// ## Compression
+/// `.gdbin` files of the resource can be compressed with `#[gdprop(compression = Zstd)]` helper attribute. Available
+/// algorithms are `Zstd`, `Lz4` and `Deflate`, each requiring respective `gd-props` cargo feature.
+///
+/// ## Backups
+/// Files are saved through a temporary file replacing the target only after successful write. Previous content of the
+/// file can be kept in rotating `<path>.N.bak` files with `#[gdprop(backups = N)]` helper attribute.
+///
+/// ## Missing External Resources
+/// By default, loading fails if any External Resource can't be loaded. With `#[gdprop(missing_ext = Skip)]` missing
+/// references are left out of `ext_array`, `ext_hashmap` and `ext_option` fields, and with `missing_ext = Placeholder`
+/// they are substituted with new instances of their class. References found broken during load are listed by
+/// `gd_props::serde_gd::broken_references`.
+#[proc_macro_derive(GdProp, attributes(gdprop))]
 pub fn derive_gd_resource(input: TokenStream) -> TokenStream {
     translate(input, gdprop::derive_resource)
 }
 
 /// Implement GodotClasses necessary for `.gdbin` and `.gdron` files handling within Godot
 ///
-/// This single macro is used to implement four different, intertwined [`GodotClass`](godot::obj::GodotClass)
+/// This single macro is used to implement five different, intertwined [`GodotClass`](godot::obj::GodotClass)
 /// structs with all necessary implementations:
 ///
 /// - [`ResourceFormatLoader`](godot::classes::ResourceFormatLoader) and [`ResourceFormatSaver`](godot::classes::ResourceFormatSaver): used to
 /// load and save [`GdProp`]-deriving resources to `.gdbin` and `.gdron` formats,
 /// - [`EditorPlugin`](godot::classes::EditorPlugin) and [`EditorExportPlugin`](godot::classes::EditorExportPlugin) which handle
 /// exporting `.gdbin` and `.gdron` format files. `.gdron` files are transformed into more compact and faster `.gdbin` format
-/// during export.
+/// during export,
+/// - [`RefCounted`](godot::classes::RefCounted) with static functions `to_bin_bytes`, `from_bin_bytes`, `to_ron_string`
+/// and `from_ron_string`, which let GDScript serialize resources of any registered class in memory, without saving them
+/// to files.
 ///
 /// Identifiers will be generated based on provided struct `Identifier`, with the visibility marker provided, either `pub` or `pub(crate)`:
 /// - `EditorPlugin`: `Identifier`,
 /// - `EditorExportPlugin`: `IdentifierExporter`,
 /// - `ResourceFormatSaver`: `IdentifierSaver`,
-/// - `ResourceFormatLoader`: `IdentifierLoader`.
+/// - `ResourceFormatLoader`: `IdentifierLoader`,
+/// - `RefCounted`: `IdentifierSerializer`.
 ///
 /// ## Register [`GdProp`] resources
 /// Every resource that should be saveable/loadable/exportable as `.gdbin`/`.gdron` file needs to be provided in helper
 /// `#[register]` macro attribute, as seen in example below. Multiple `#[register]` helper macros with different identifiers can be provided
 /// for code readability.
 ///
+/// ## Select formats
+/// By default Loader and Saver handle `.gdron` and `.gdbin` files. Other formats can be selected with `#[formats]` helper
+/// attribute, eg. `#[formats(Ron, Bin, Json)]`. Available formats are `Ron`, `Bin` and - with respective `gd-props` cargo
+/// features enabled - `Json`, `Cbor`, `Bincode` and `Postcard`.
+///
+/// ## Export compression
+/// `.gdbin` files created from `.gdron` files during export can be compressed with `#[export_compression]` helper
+/// attribute, eg. `#[export_compression(Zstd)]`. Available algorithms are `Zstd`, `Lz4` and `Deflate`.
+///
+/// ## Export options
+/// `EditorExportPlugin` adds options to every export preset, under `gd_props` section:
+/// - `debug_gdron_export` and `release_gdron_export` - handling of `.gdron` files in debug and release exports: `Keep`
+///   exports them as they are, `Convert` translates them into `.gdbin` and `Convert and compress` additionally compresses
+///   them. Defaults to `Convert and compress` if `#[export_compression]` is set, and to `Convert` otherwise.
+/// - `compression` - compression used with `Convert and compress`, defaulting to `#[export_compression]`.
+/// - `debug_only_paths` - paths of `.gdron` and `.gdbin` files, or of directories containing them, which are left out
+///   of release exports. Directory `res://debug` covers files inside it, but not `res://debug_items/sword.gdron`.
+/// - `report_as_errors` - whether problems with `.gdron` files are logged as errors, enabled by default. If disabled,
+///   they are logged as warnings. Export succeeds either way.
+/// - `use_export_cache` - whether translations of `.gdron` files are reused from `.godot/gd_props/export_cache`,
+///   enabled by default. Cached translations are keyed with the file content, state of files it references and the
+///   fingerprint of the extension, made from name and version of the crate defining the plugin and declarations of
+///   registered resources. Translations are invalidated only by changes of them.
+///
+/// ## Export validation
+/// Every exported `.gdron` file is loaded before translation. Files which can't be loaded or translated, or which class
+/// isn't registered in the plugin, are left out of exported project and logged as errors, with a summary listing all
+/// of them at the end of export. Godot doesn't allow export plugins to fail the export, so it still succeeds, also when
+/// run headless with `--export-release` - check the log, or `export_report()` method of `EditorExportPlugin`, to make
+/// sure no data is missing from exported project.
+///
 /// ## Setup
 /// Created plugins don't need further setup: as they are created, they will be registered and used by `Godot` automatically
 /// during export.
 ///
+/// Context menu of FileSystem dock gets *Convert to .gdbin*, *Convert to .gdron*, *Validate*, *Show references* and
+/// *Regenerate UID* actions for the selected files. `EditorPlugin` also adds *Project > Tools > Decompile selected
+/// .gdbin to .gdron* menu item, which runs *Convert to .gdron* action on selected `.gdbin` files. Decompiling to chosen
+/// path is available from code with `decompile_bin(path, ron_path)` method of `ResourceFormatLoader`.
+///
 /// Loader and Saver need registering in your [`#[gdextension]`](godot::init::gdextension) implementation. It is recommended to
 /// use provided associated functions: [`register_saver`](gd_props_defs::traits::GdPropSaver::register_saver) and
 /// [`register_loader`](gd_props_defs::traits::GdPropLoader::register_loader) - for implementation details see their documentation.
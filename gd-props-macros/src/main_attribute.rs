@@ -2,10 +2,16 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use venial::Declaration;
 
-use crate::utils::{GdPropIdents, RegisteredProps, VisMarkerHandler};
+use crate::utils::{
+    BuildFingerprint, ExportCompression, GdPropIdents, PluginFormats, RegisteredProps,
+    VisMarkerHandler,
+};
 
 pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error> {
     let RegisteredProps { registers } = RegisteredProps::declare(&decl)?;
+    let formats = PluginFormats::declare(&decl)?.to_const();
+    let export_compression = ExportCompression::declare(&decl)?.to_const();
+    let build_fingerprint = BuildFingerprint::declare(&registers).to_const();
 
     let item = decl
         .as_struct()
@@ -16,6 +22,7 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
         exporter,
         loader,
         saver,
+        serializer,
     } = GdPropIdents::from_item(item);
 
     let VisMarkerHandler { marker } = VisMarkerHandler::from_item(item)?;
@@ -47,6 +54,16 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
           .add_export_plugin(exporter.clone().upcast());
 
           self.exporter = Some(exporter);
+
+          let decompile = ::godot::builtin::Callable::from_object_method(
+            &<Self as ::godot::obj::WithBaseField>::to_gd(self),
+            "decompile_selected_bin"
+          );
+          <Self as ::godot::obj::WithBaseField>::base_mut(self)
+          .add_tool_menu_item(::godot::builtin::GString::from(Self::DECOMPILE_MENU_ITEM), decompile);
+
+          let (fill, run) = self.file_menu_callables();
+          ::gd_props::private::GdPropFileAction::connect_menus(&fill, &run);
         }
 
         fn exit_tree(&mut self) {
@@ -56,6 +73,195 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
           .remove_export_plugin(exporter.upcast());
 
           self.exporter = None;
+
+          <Self as ::godot::obj::WithBaseField>::base_mut(self)
+          .remove_tool_menu_item(::godot::builtin::GString::from(Self::DECOMPILE_MENU_ITEM));
+
+          let (fill, run) = self.file_menu_callables();
+          ::gd_props::private::GdPropFileAction::disconnect_menus(&fill, &run);
+        }
+      }
+
+      impl ::gd_props::traits::GdPropRegistry for #plugin {
+        const CLASSES: &'static [&'static str] = &[#(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT),*];
+
+        fn _int_serialize_poly<S>(
+          resource: &::godot::obj::Gd<::godot::classes::Resource>,
+          serializer: S
+        ) -> Result<S::Ok, S::Error>
+        where
+          S: ::gd_props::private::serde::ser::Serializer
+        {
+          let class = resource.get_class();
+          #(
+            if class.eq(&::godot::builtin::GString::from(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT)) {
+              return <Self as ::gd_props::traits::GdPropRegistry>::_int_serialize_as::<#registers, S>(resource, serializer);
+            }
+          )*
+          Err(<S::Error as ::gd_props::private::serde::ser::Error>::custom(format!("class {} is not registered in {}", class, stringify!(#plugin))))
+        }
+
+        fn _int_deserialize_poly<'de, A>(class: &str, variant: A) -> Result<::godot::obj::Gd<::godot::classes::Resource>, A::Error>
+        where
+          A: ::gd_props::private::serde::de::VariantAccess<'de>
+        {
+          #(
+            if class.eq(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT) {
+              return <Self as ::gd_props::traits::GdPropRegistry>::_int_deserialize_as::<#registers, A>(variant);
+            }
+          )*
+          Err(<A::Error as ::gd_props::private::serde::de::Error>::unknown_variant(class, <Self as ::gd_props::traits::GdPropRegistry>::CLASSES))
+        }
+
+        fn _int_encode_poly<C: ::gd_props::codec::GdPropCodec>(
+          resource: &::godot::obj::Gd<::godot::classes::Resource>
+        ) -> Result<Vec<u8>, ::gd_props::errors::GdPropError> {
+          let class = resource.get_class();
+          #(
+            if class.eq(&::godot::builtin::GString::from(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT)) {
+              let resource = resource.clone().cast::<#registers>();
+              let bound = resource.bind();
+              return <#registers as ::gd_props::traits::GdProp>::_int_try_to_bytes::<C>(&*bound);
+            }
+          )*
+          Err(::gd_props::errors::GdPropError::ClassMismatch {
+            expected: <Self as ::gd_props::traits::GdPropRegistry>::CLASSES.join(", "),
+            found: class.to_string(),
+          })
+        }
+
+        fn _int_decode_poly<C: ::gd_props::codec::GdPropCodec>(
+          bytes: &[u8]
+        ) -> Result<::godot::obj::Gd<::godot::classes::Resource>, ::gd_props::errors::GdPropError> {
+          let header = <C as ::gd_props::codec::GdPropCodec>::read_header(&mut &bytes[..])?;
+          #(
+            if header.gd_class.eq(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT) {
+              return <#registers as ::gd_props::traits::GdProp>::_int_try_from_bytes::<C>(bytes).map(|loaded| loaded.upcast());
+            }
+          )*
+          Err(::gd_props::errors::GdPropError::ClassMismatch {
+            expected: <Self as ::gd_props::traits::GdPropRegistry>::CLASSES.join(", "),
+            found: header.gd_class,
+          })
+        }
+      }
+
+      #[derive(::godot::register::GodotClass)]
+      #[class(base=RefCounted, init)]
+      #marker struct #serializer {}
+
+      #[::godot::register::godot_api]
+      impl #serializer {
+        /// Serialize the resource of any registered class into content of `.gdbin` file.
+        #[func]
+        fn to_bin_bytes(resource: ::godot::obj::Gd<::godot::classes::Resource>) -> ::godot::builtin::PackedByteArray {
+          match <#plugin as ::gd_props::traits::GdPropRegistry>::_int_encode_poly::<::gd_props::codec::BinCodec>(&resource) {
+            Ok(bytes) => ::godot::builtin::PackedByteArray::from(bytes.as_slice()),
+            Err(error) => {
+              ::godot::log::godot_error!("[{}]: error while serializing: {}", stringify!(#serializer), error);
+              ::godot::builtin::PackedByteArray::new()
+            }
+          }
+        }
+
+        /// Deserialize the resource of any registered class from content of `.gdbin` file. Returns `null` on failure.
+        #[func]
+        fn from_bin_bytes(bytes: ::godot::builtin::PackedByteArray) -> Option<::godot::obj::Gd<::godot::classes::Resource>> {
+          match <#plugin as ::gd_props::traits::GdPropRegistry>::_int_decode_poly::<::gd_props::codec::BinCodec>(bytes.as_slice()) {
+            Ok(resource) => Some(resource),
+            Err(error) => {
+              ::godot::log::godot_error!("[{}]: error while deserializing: {}", stringify!(#serializer), error);
+              None
+            }
+          }
+        }
+
+        /// Serialize the resource of any registered class into content of `.gdron` file.
+        #[func]
+        fn to_ron_string(resource: ::godot::obj::Gd<::godot::classes::Resource>) -> ::godot::builtin::GString {
+          match <#plugin as ::gd_props::traits::GdPropRegistry>::_int_encode_poly::<::gd_props::codec::RonCodec>(&resource) {
+            Ok(bytes) => ::godot::builtin::GString::from(String::from_utf8_lossy(&bytes).as_ref()),
+            Err(error) => {
+              ::godot::log::godot_error!("[{}]: error while serializing: {}", stringify!(#serializer), error);
+              ::godot::builtin::GString::new()
+            }
+          }
+        }
+
+        /// Deserialize the resource of any registered class from content of `.gdron` file. Returns `null` on failure.
+        #[func]
+        fn from_ron_string(content: ::godot::builtin::GString) -> Option<::godot::obj::Gd<::godot::classes::Resource>> {
+          match <#plugin as ::gd_props::traits::GdPropRegistry>::_int_decode_poly::<::gd_props::codec::RonCodec>(content.to_string().as_bytes()) {
+            Ok(resource) => Some(resource),
+            Err(error) => {
+              ::godot::log::godot_error!("[{}]: error while deserializing: {}", stringify!(#serializer), error);
+              None
+            }
+          }
+        }
+      }
+
+      impl #plugin {
+        const DECOMPILE_MENU_ITEM: &'static str = "Decompile selected .gdbin to .gdron";
+
+        /// Run file `action` on every file selected in FileSystem dock it applies to, and which path is accepted by
+        /// `filter`.
+        fn run_on_selected<F>(action: ::gd_props::private::GdPropFileAction, filter: F)
+        where
+          F: Fn(&str) -> bool,
+        {
+          let formats = <#loader as ::gd_props::traits::GdPropLoader>::FORMATS;
+          let mut editor = ::godot::classes::EditorInterface::singleton();
+
+          for path in editor.get_selected_paths().as_slice() {
+            let str_path = path.to_string();
+            if !action.applies_to(&str_path, formats) || !filter(&str_path) {
+              continue;
+            }
+            match action.run::<#loader, #saver>(path.clone()) {
+              Ok(message) => ::godot::log::godot_print!("[{}]: {} {}; {}", stringify!(#plugin), action.label(), path, message),
+              Err(error) => ::godot::log::godot_error!("[{}]: {} {} failed; {}", stringify!(#plugin), action.label(), path, error),
+            }
+          }
+
+          if let Some(mut filesystem) = editor.get_resource_filesystem() {
+            filesystem.scan();
+          }
+        }
+
+        fn file_menu_callables(&self) -> (::godot::builtin::Callable, ::godot::builtin::Callable) {
+          let plugin = <Self as ::godot::obj::WithBaseField>::to_gd(self);
+          (
+            ::godot::builtin::Callable::from_object_method(&plugin, "fill_file_menus"),
+            ::godot::builtin::Callable::from_object_method(&plugin, "run_file_action"),
+          )
+        }
+      }
+
+      #[::godot::register::godot_api]
+      impl #plugin {
+        /// Decompile `.gdbin` files selected in FileSystem dock into `.gdron` files saved alongside them, with the
+        /// same "Convert to .gdron" action as their context menu.
+        #[func]
+        fn decompile_selected_bin(&mut self) {
+          let formats = <#loader as ::gd_props::traits::GdPropLoader>::FORMATS;
+          Self::run_on_selected(::gd_props::private::GdPropFileAction::ConvertToRon, |path| {
+            ::gd_props::codec::GdPropFormat::recognize_among(path, formats) == ::gd_props::codec::GdPropFormat::GdBin
+          });
+        }
+
+        /// Add actions applicable to files selected in FileSystem dock to its context menu, right before it's shown.
+        #[func]
+        fn fill_file_menus(&mut self) {
+          ::gd_props::private::GdPropFileAction::fill_menus(<#loader as ::gd_props::traits::GdPropLoader>::FORMATS);
+        }
+
+        /// Run action chosen in FileSystem dock context menu on every selected file it applies to.
+        #[func]
+        fn run_file_action(&mut self, id: i64) {
+          if let Some(action) = ::gd_props::private::GdPropFileAction::from_id(id) {
+            Self::run_on_selected(action, |_| true);
+          }
         }
       }
 
@@ -67,6 +273,9 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
       }
 
       impl ::gd_props::traits::GdPropExporter for #exporter {
+        #export_compression
+        #build_fingerprint
+
         fn _int_state_mut(&mut self) -> &mut ::gd_props::private::ExporterState {
           &mut self.state
         }
@@ -92,27 +301,53 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
           ::godot::builtin::GString::from(stringify!(#exporter))
         }
 
+        fn get_export_options(
+          &self,
+          _platform: ::godot::obj::Gd< ::godot::classes::EditorExportPlatform>
+        ) -> ::godot::builtin::Array< ::godot::builtin::Dictionary> {
+          <Self as ::gd_props::traits::GdPropExporter>::_int_export_options()
+        }
+
         fn export_file(
           &mut self,
           path: ::godot::builtin::GString,
           type_: ::godot::builtin::GString,
           _features: ::godot::builtin::PackedStringArray
         ) {
-          // only `.gdron` files needs to be handled. `.gdbin` files are already exported correctly by built-in functionalities.
-          if <Self as ::gd_props::traits::GdPropExporter>::_int_is_gdron(path.clone()) {
+          if <Self as ::gd_props::traits::GdPropExporter>::_int_is_stripped(self, path.clone()) {
+            <Self as ::godot::obj::WithBaseField>::base_mut(self).skip();
+            return;
+          }
 
+          // only `.gdron` files needs to be handled, unless they are kept as they are. `.gdbin` files are already exported correctly by built-in functionalities.
+          if <Self as ::gd_props::traits::GdPropExporter>::_int_is_gdron(path.clone()) && <Self as ::gd_props::traits::GdPropExporter>::_int_converts_ron(self) {
+
+            let mut registered = false;
             let mut bytes: Option<::godot::builtin::PackedByteArray> = None;
             let changed_path = <Self as ::gd_props::traits::GdPropExporter>::_int_ron_to_bin_change_path(path.clone());
+            // exported `.gdbin` files are sealed in the same way as ones saved by the saver.
+            let saver = <#saver as ::gd_props::traits::RefCountedSingleton>::singleton_refcount();
+            let saver = saver.bind();
+            let key = <#saver as ::gd_props::traits::GdPropSaver>::_int_key(&saver);
 
             #(
               if type_.eq(&::godot::builtin::GString::from(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT)) {
-                bytes = Some(<Self as ::gd_props::traits::GdPropExporter>::_int_process_ron_file::<#registers>(self, path.clone(), changed_path.clone()));
+                registered = true;
+                bytes = <Self as ::gd_props::traits::GdPropExporter>::_int_process_ron_file::<#registers>(self, path.clone(), changed_path.clone(), key);
               }
             )*
 
-            if let Some(bytes) = bytes {
-              ::godot::log::godot_print!("[{}]: .gdron -> .gdbin remap: {} type, from: {}; Remapped to: {}", stringify!(#exporter), &type_, &path, &changed_path);
-              <Self as ::godot::obj::WithBaseField>::base_mut(self).add_file(changed_path, bytes, true);
+            if !registered {
+              <Self as ::gd_props::traits::GdPropExporter>::_int_report_unregistered(self, path.clone(), type_.clone());
+            }
+
+            match bytes {
+              Some(bytes) => {
+                ::godot::log::godot_print!("[{}]: .gdron -> .gdbin remap: {} type, from: {}; Remapped to: {}", stringify!(#exporter), &type_, &path, &changed_path);
+                <Self as ::godot::obj::WithBaseField>::base_mut(self).add_file(changed_path, bytes, true);
+              }
+              // Invalid file is left out, rather than exported in a form which can't be loaded.
+              None => <Self as ::godot::obj::WithBaseField>::base_mut(self).skip(),
             }
           }
         }
@@ -120,7 +355,9 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
 
       #[derive(::godot::register::GodotClass)]
       #[class(base=ResourceFormatLoader, init, tool)]
-      #marker struct #loader;
+      #marker struct #loader {
+        key: Option<::gd_props::codec::GdPropKey>
+      }
 
       #[::godot::register::godot_api]
       impl ::godot::classes::IResourceFormatLoader for #loader {
@@ -153,8 +390,8 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
           &self,
           path: ::godot::builtin::GString,
           _original_path: godot::builtin::GString,
-          _use_sub_threads: bool,
-          _cache_mode: i32
+          use_sub_threads: bool,
+          cache_mode: i32
         ) -> godot::builtin::Variant {
 
           match <Self as ::gd_props::traits::GdPropLoader>::_int_get_type(self, path.clone()) {
@@ -162,7 +399,7 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
             Ok(struct_name) => {
               #(
                 if struct_name.eq(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT) {
-                  return <Self as ::gd_props::traits::GdPropLoader>::_int_load_file::<#registers>(self, path);
+                  return <Self as ::gd_props::traits::GdPropLoader>::_int_load_file::<#registers>(self, path, use_sub_threads, cache_mode);
                 }
               )*
             }
@@ -170,6 +407,14 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
           ::godot::builtin::Variant::nil()
         }
 
+        fn get_dependencies(&self, path: ::godot::builtin::GString, add_types: bool) -> ::godot::builtin::PackedStringArray {
+          <Self as ::gd_props::traits::GdPropLoader>::_int_get_dependencies(self, path, add_types)
+        }
+
+        fn rename_dependencies(&self, path: ::godot::builtin::GString, renames: ::godot::builtin::Dictionary) -> ::godot::global::Error {
+          <Self as ::gd_props::traits::GdPropLoader>::_int_rename_dependencies(self, path, renames)
+        }
+
         fn get_resource_uid(&self, path: ::godot::builtin::GString) -> i64 {
           match <Self as ::gd_props::traits::GdPropLoader>::_int_get_uid(self, path.clone()) {
             Ok(uid) => uid,
@@ -178,7 +423,37 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
         }
       }
 
-      impl ::gd_props::traits::GdPropLoader for #loader {}
+      #[::godot::register::godot_api]
+      impl #loader {
+        /// Decompile `.gdbin` file located at `path`, opened with the key of the loader, into `.gdron` file saved at
+        /// `ron_path`. The `.gdron` file gets its own UID.
+        #[func]
+        fn decompile_bin(&self, path: ::godot::builtin::GString, ron_path: ::godot::builtin::GString) -> ::godot::global::Error {
+          match <Self as ::gd_props::traits::GdPropLoader>::_int_get_type(self, path.clone()) {
+            Err(error) => ::godot::prelude::godot_error!("error getting '{}' resource type during decompile: {}", path, error),
+            Ok(struct_name) => {
+              #(
+                if struct_name.eq(<#registers as ::gd_props::traits::GdProp>::HEAD_IDENT) {
+                  return <Self as ::gd_props::traits::GdPropLoader>::_int_decompile_bin::<#registers>(self, path, ron_path);
+                }
+              )*
+            }
+          }
+          ::godot::global::Error::ERR_FILE_UNRECOGNIZED
+        }
+      }
+
+      impl ::gd_props::traits::GdPropLoader for #loader {
+        #formats
+
+        fn _int_key(&self) -> Option<&::gd_props::codec::GdPropKey> {
+          self.key.as_ref()
+        }
+
+        fn _int_key_mut(&mut self) -> &mut Option<::gd_props::codec::GdPropKey> {
+          &mut self.key
+        }
+      }
 
       impl ::gd_props::traits::RefCountedSingleton for #loader {
         const SINGLETON_NAME: &'static str = stringify!(#loader);
@@ -186,7 +461,9 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
 
       #[derive(::godot::register::GodotClass)]
       #[class(base=ResourceFormatSaver, init, tool)]
-      #marker struct #saver;
+      #marker struct #saver {
+        key: Option<::gd_props::codec::GdPropKey>
+      }
 
       #[::godot::register::godot_api]
       impl ::godot::classes::IResourceFormatSaver for #saver {
@@ -228,7 +505,17 @@ pub fn gd_plugin_parser(decl: Declaration) -> Result<TokenStream, venial::Error>
         }
       }
 
-      impl ::gd_props::traits::GdPropSaver for #saver {}
+      impl ::gd_props::traits::GdPropSaver for #saver {
+        #formats
+
+        fn _int_key(&self) -> Option<&::gd_props::codec::GdPropKey> {
+          self.key.as_ref()
+        }
+
+        fn _int_key_mut(&mut self) -> &mut Option<::gd_props::codec::GdPropKey> {
+          &mut self.key
+        }
+      }
 
       impl ::gd_props::traits::RefCountedSingleton for #saver {
         const SINGLETON_NAME: &'static str = stringify!(#saver);
@@ -1,5 +1,5 @@
 use proc_macro2::TokenStream as TokenStream2;
-use proc_macro2::{Ident, TokenTree};
+use proc_macro2::{Ident, Literal, TokenTree};
 use quote::{format_ident, quote};
 use venial::{AttributeValue, Declaration, Struct};
 
@@ -34,6 +34,273 @@ impl RegisteredProps {
     }
 }
 
+#[derive(Debug)]
+pub(crate) struct PluginFormats {
+    pub formats: Option<Vec<TokenStream2>>,
+}
+
+impl PluginFormats {
+    const FORMATS_PATH: &'static str = "formats";
+    const KNOWN_FORMATS: [(&'static str, &'static str); 6] = [
+        ("Ron", "GdRon"),
+        ("Bin", "GdBin"),
+        ("Json", "GdJson"),
+        ("Cbor", "GdCbor"),
+        ("Bincode", "GdBincode"),
+        ("Postcard", "GdPostcard"),
+    ];
+
+    pub fn declare(declaration: &Declaration) -> Result<Self, venial::Error> {
+        let obj = declaration
+            .as_struct()
+            .ok_or_else(|| venial::Error::new("Only struct can be registered!"))?;
+
+        let mut formats: Option<Vec<TokenStream2>> = None;
+
+        for attr in obj.attributes.iter() {
+            let path = &attr.path;
+            if path.len() == 1 && path[0].to_string() == Self::FORMATS_PATH {
+                let idents = handle_register(&attr.value)?;
+                for ident in idents {
+                    let Some((_, variant)) =
+                        Self::KNOWN_FORMATS.iter().find(|(name, _)| ident == name)
+                    else {
+                        return Err(venial::Error::new_at_span(
+                            ident.span(),
+                            "unknown format, expected one of: Ron, Bin, Json, Cbor, Bincode, Postcard",
+                        ));
+                    };
+                    let variant = format_ident!("{}", variant, span = ident.span());
+                    formats
+                        .get_or_insert_with(Vec::new)
+                        .push(quote! { ::gd_props::codec::GdPropFormat::#variant });
+                }
+            }
+        }
+
+        Ok(Self { formats })
+    }
+
+    /// Declaration of `FORMATS` constant of loader and saver traits, if formats were selected.
+    pub fn to_const(&self) -> TokenStream2 {
+        match &self.formats {
+            Some(formats) => quote! {
+                const FORMATS: &'static [::gd_props::codec::GdPropFormat] = &[#(#formats),*];
+            },
+            None => quote! {},
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct GdPropAttributes {
+    pub version: Option<Literal>,
+    pub migrate: bool,
+    pub compression: Option<TokenStream2>,
+    pub backups: Option<Literal>,
+    pub missing_ext: Option<TokenStream2>,
+}
+
+impl GdPropAttributes {
+    const GDPROP_PATH: &'static str = "gdprop";
+
+    pub fn declare(item: &Struct) -> Result<Self, venial::Error> {
+        let mut attributes = Self::default();
+
+        for attr in item.attributes.iter() {
+            let path = &attr.path;
+            if path.len() == 1 && path[0].to_string() == Self::GDPROP_PATH {
+                attributes.handle_gdprop(&attr.value)?;
+            }
+        }
+
+        if attributes.migrate && attributes.version.is_none() {
+            return Err(venial::Error::new(
+                "`migrate` needs `version` to be set in `gdprop` attribute",
+            ));
+        }
+
+        Ok(attributes)
+    }
+
+    fn handle_gdprop(&mut self, value: &AttributeValue) -> Result<(), venial::Error> {
+        let AttributeValue::Group(_, tree) = &value else {
+            return Err(venial::Error::new(
+                "`gdprop` attribute expects arguments, eg. `#[gdprop(version = 1)]`",
+            ));
+        };
+
+        let mut iter = tree.iter();
+        while let Some(token) = iter.next() {
+            match token {
+                TokenTree::Ident(ident) if ident == "version" => match (iter.next(), iter.next()) {
+                    (Some(TokenTree::Punct(punct)), Some(TokenTree::Literal(literal)))
+                        if punct.as_char() == '=' =>
+                    {
+                        self.version = Some(literal.clone());
+                    }
+                    _ => {
+                        return Err(venial::Error::new_at_span(
+                            ident.span(),
+                            "expected `version = <u32>`",
+                        ))
+                    }
+                },
+                TokenTree::Ident(ident) if ident == "migrate" => {
+                    self.migrate = true;
+                }
+                TokenTree::Ident(ident) if ident == "backups" => match (iter.next(), iter.next()) {
+                    (Some(TokenTree::Punct(punct)), Some(TokenTree::Literal(literal)))
+                        if punct.as_char() == '=' =>
+                    {
+                        self.backups = Some(literal.clone());
+                    }
+                    _ => {
+                        return Err(venial::Error::new_at_span(
+                            ident.span(),
+                            "expected `backups = <u32>`",
+                        ))
+                    }
+                },
+                TokenTree::Ident(ident) if ident == "compression" => {
+                    match (iter.next(), iter.next()) {
+                        (Some(TokenTree::Punct(punct)), Some(TokenTree::Ident(variant)))
+                            if punct.as_char() == '=' =>
+                        {
+                            self.compression = Some(compression_variant(variant)?);
+                        }
+                        _ => {
+                            return Err(venial::Error::new_at_span(
+                                ident.span(),
+                                "expected `compression = <Zstd|Lz4|Deflate>`",
+                            ))
+                        }
+                    }
+                }
+                TokenTree::Ident(ident) if ident == "missing_ext" => {
+                    match (iter.next(), iter.next()) {
+                        (Some(TokenTree::Punct(punct)), Some(TokenTree::Ident(variant)))
+                            if punct.as_char() == '=' =>
+                        {
+                            self.missing_ext = Some(missing_ext_variant(variant)?);
+                        }
+                        _ => {
+                            return Err(venial::Error::new_at_span(
+                                ident.span(),
+                                "expected `missing_ext = <Error|Skip|Placeholder>`",
+                            ))
+                        }
+                    }
+                }
+                TokenTree::Punct(punct) if punct.as_char() == ',' => {}
+                other => {
+                    return Err(venial::Error::new_at_span(
+                        other.span(),
+                        "unknown argument in `gdprop` attribute",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ExportCompression {
+    pub compression: Option<TokenStream2>,
+}
+
+impl ExportCompression {
+    const EXPORT_COMPRESSION_PATH: &'static str = "export_compression";
+
+    pub fn declare(declaration: &Declaration) -> Result<Self, venial::Error> {
+        let obj = declaration
+            .as_struct()
+            .ok_or_else(|| venial::Error::new("Only struct can be registered!"))?;
+
+        let mut compression = None;
+
+        for attr in obj.attributes.iter() {
+            let path = &attr.path;
+            if path.len() == 1 && path[0].to_string() == Self::EXPORT_COMPRESSION_PATH {
+                let idents = handle_register(&attr.value)?;
+                let [variant] = idents.as_slice() else {
+                    return Err(venial::Error::new(
+                        "`export_compression` expects single algorithm, eg. `#[export_compression(Zstd)]`",
+                    ));
+                };
+                compression = Some(compression_variant(variant)?);
+            }
+        }
+
+        Ok(Self { compression })
+    }
+
+    /// Declaration of `EXPORT_COMPRESSION` constant of exporter trait, if compression was set.
+    pub fn to_const(&self) -> TokenStream2 {
+        match &self.compression {
+            Some(compression) => quote! {
+                const EXPORT_COMPRESSION: ::gd_props::codec::GdPropCompression = #compression;
+            },
+            None => quote! {},
+        }
+    }
+}
+
+/// Fingerprint of the build of the crate defining the plugin.
+#[derive(Debug)]
+pub(crate) struct BuildFingerprint {
+    pub registers: Vec<Ident>,
+}
+
+impl BuildFingerprint {
+    pub fn declare(registers: &[Ident]) -> Self {
+        Self {
+            registers: registers.to_vec(),
+        }
+    }
+
+    /// Declaration of `BUILD_FINGERPRINT` constant of exporter trait. It's computed in the crate defining the plugin,
+    /// from its package name and version and schema fingerprints of registered resources, so it changes only with
+    /// them.
+    pub fn to_const(&self) -> TokenStream2 {
+        let registers = &self.registers;
+        quote! {
+            const BUILD_FINGERPRINT: u64 = ::gd_props::private::build_fingerprint(
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+                &[#(<#registers as ::gd_props::traits::GdProp>::SCHEMA_FINGERPRINT),*],
+            );
+        }
+    }
+}
+
+fn compression_variant(variant: &Ident) -> Result<TokenStream2, venial::Error> {
+    if !["None", "Zstd", "Lz4", "Deflate"]
+        .iter()
+        .any(|name| variant == name)
+    {
+        return Err(venial::Error::new_at_span(
+            variant.span(),
+            "unknown compression, expected one of: None, Zstd, Lz4, Deflate",
+        ));
+    }
+    Ok(quote! { ::gd_props::codec::GdPropCompression::#variant })
+}
+
+fn missing_ext_variant(variant: &Ident) -> Result<TokenStream2, venial::Error> {
+    if !["Error", "Skip", "Placeholder"]
+        .iter()
+        .any(|name| variant == name)
+    {
+        return Err(venial::Error::new_at_span(
+            variant.span(),
+            "unknown missing External Resource policy, expected one of: Error, Skip, Placeholder",
+        ));
+    }
+    Ok(quote! { ::gd_props::serde_gd::GdExtMissingPolicy::#variant })
+}
+
 pub(crate) struct VisMarkerHandler {
     pub marker: TokenStream2,
 }
@@ -66,6 +333,7 @@ pub(crate) struct GdPropIdents {
     pub exporter: Ident,
     pub loader: Ident,
     pub saver: Ident,
+    pub serializer: Ident,
 }
 
 impl GdPropIdents {
@@ -74,12 +342,14 @@ impl GdPropIdents {
         let exporter = format_ident!("{}{}", &plugin, "Exporter");
         let loader = format_ident!("{}{}", &plugin, "Loader");
         let saver = format_ident!("{}{}", &plugin, "Saver");
+        let serializer = format_ident!("{}{}", &plugin, "Serializer");
 
         Self {
             plugin,
             exporter,
             loader,
             saver,
+            serializer,
         }
     }
 }
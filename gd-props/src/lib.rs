@@ -27,15 +27,24 @@ pub mod traits {
     pub use gd_props_defs::traits::GdProp;
     pub use gd_props_defs::traits::GdPropExporter;
     pub use gd_props_defs::traits::GdPropLoader;
+    pub use gd_props_defs::traits::GdPropMigrate;
+    pub use gd_props_defs::traits::GdPropRegistry;
     pub use gd_props_defs::traits::GdPropSaver;
     pub use gd_props_defs::traits::RefCountedSingleton;
 }
 
-/// Module containing types necessary for export plugin. Symbols not needed outside of internal usage.
+/// Module containing types necessary for plugin generated by the macros. Symbols not needed outside of internal usage.
 pub mod private {
+    pub use gd_props_defs::editor_menu::GdPropFileAction;
+    pub use gd_props_defs::export_plugin::build_fingerprint;
     pub use gd_props_defs::export_plugin::ExporterState;
+    pub use gd_props_defs::export_plugin::GdRonExport;
     pub use gd_props_defs::export_plugin::RemapData;
+    pub use serde;
 }
 
+pub use gd_props_defs::codec;
 pub use gd_props_defs::errors;
+pub use gd_props_defs::migration;
+pub use gd_props_defs::network;
 pub use gd_props_defs::serde_gd;
@@ -0,0 +1,44 @@
+use gd_props::codec::{BinCodec, GdPropCodec};
+use gd_rehearse::itest::gditest;
+use godot::classes::FileAccess;
+use godot::obj::NewGd;
+use godot::tools::save;
+
+use crate::remove_file;
+use crate::structs::resource::BackedUpResource;
+
+const PATH: &str = "res://";
+
+fn saved_value(file_path: &str) -> u32 {
+    let bytes = FileAccess::get_file_as_bytes(file_path.into());
+    let (_, saved) = BinCodec::decode::<BackedUpResource, _>(&mut bytes.as_slice()).unwrap();
+    saved.value
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn save_keeps_rotating_backups() {
+    let file = "test_backups.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    for value in 1..=4 {
+        let mut resource = BackedUpResource::new_gd();
+        resource.bind_mut().value = value;
+        save(resource, &file_path);
+    }
+
+    assert_eq!(saved_value(&file_path), 4);
+    assert_eq!(saved_value(&format!("{file_path}.1.bak")), 3);
+    assert_eq!(saved_value(&format!("{file_path}.2.bak")), 2);
+    assert!(!FileAccess::file_exists(
+        format!("{file_path}.3.bak").into()
+    ));
+    assert!(!FileAccess::file_exists(format!("{file_path}.tmp").into()));
+
+    for file in [
+        file.to_string(),
+        format!("{file}.1.bak"),
+        format!("{file}.2.bak"),
+    ] {
+        remove_file(PATH, file);
+    }
+}
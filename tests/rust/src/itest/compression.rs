@@ -0,0 +1,74 @@
+use gd_rehearse::itest::gditest;
+use godot::classes::FileAccess;
+use godot::obj::NewGd;
+use godot::tools::{load, save};
+
+use gd_props::codec::{BinCodec, GdPropCodec, GdPropCompression, RonCodec};
+use gd_props::traits::GdProp;
+
+use crate::remove_file;
+use crate::structs::resource::CompressedResource;
+
+const PATH: &str = "res://";
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn compressed_bin_saved_and_loaded() {
+    let file = "test_compressed.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    let mut saved = CompressedResource::new_gd();
+    saved.bind_mut().values = (0..256).collect();
+    save(saved, &file_path);
+
+    let bytes = FileAccess::get_file_as_bytes(file_path.clone().into());
+    let header = BinCodec::read_header(&mut bytes.as_slice()).unwrap();
+    assert_eq!(header.compression, GdPropCompression::Zstd);
+
+    let loaded = load::<CompressedResource>(&file_path);
+    assert_eq!(loaded.bind().values, (0..256).collect::<Vec<u32>>());
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn compressed_bin_translated_to_plain_ron() {
+    let file = "test_compressed.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    let mut saved = CompressedResource::new_gd();
+    saved.bind_mut().values = vec![1, 2, 3];
+    save(saved, &file_path);
+
+    let ron = CompressedResource::translate_bin_to_ron(file_path.into()).to_string();
+    let (header, loaded) = RonCodec::decode::<CompressedResource, _>(&mut ron.as_bytes()).unwrap();
+    assert_eq!(header.compression, GdPropCompression::None);
+    assert_eq!(loaded.values, vec![1, 2, 3]);
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn save_as_uses_compression_and_keeps_uid() {
+    let file = "test_compressed_save_as.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    let mut saved = CompressedResource::new_gd();
+    saved.bind_mut().values = (0..64).collect();
+    saved
+        .bind()
+        .try_save_as::<BinCodec>(file_path.clone().into())
+        .unwrap();
+    let bytes = FileAccess::get_file_as_bytes(file_path.clone().into());
+    let first = BinCodec::read_header(&mut bytes.as_slice()).unwrap();
+    assert_eq!(first.compression, GdPropCompression::Zstd);
+
+    saved
+        .bind()
+        .try_save_as::<BinCodec>(file_path.clone().into())
+        .unwrap();
+    let bytes = FileAccess::get_file_as_bytes(file_path.clone().into());
+    let second = BinCodec::read_header(&mut bytes.as_slice()).unwrap();
+    assert_eq!(first.uid, second.uid);
+
+    remove_file(PATH, file);
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use gd_props::traits::{GdPropLoader, RefCountedSingleton};
+use gd_rehearse::itest::gditest;
+use godot::classes::ResourceLoader;
+use godot::tools::{load, save};
+
+use crate::remove_file;
+use crate::structs::prop_handlers::PropPluginLoader;
+use crate::structs::resource::WithExtGd;
+
+const EXT_PATH: &str = "res://ext_test/test_ext.gdron";
+const RESOURCE_PATH: &str = "res://ext_test/test_resource.gdron";
+const GODOT_RES_PATH: &str = "res://ext_test/test_godot_res.tres";
+
+#[gditest]
+fn loader_reports_dependencies() {
+    let dependencies = ResourceLoader::singleton().get_dependencies(EXT_PATH.into());
+    let dependencies: Vec<String> = dependencies
+        .as_slice()
+        .iter()
+        .map(|dependency| dependency.to_string())
+        .collect();
+
+    assert_eq!(dependencies.len(), 2);
+    for path in [RESOURCE_PATH, GODOT_RES_PATH] {
+        assert!(
+            dependencies
+                .iter()
+                .any(|dependency| dependency.contains(path)),
+            "missing dependency: {path}"
+        );
+    }
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn loader_renames_dependencies() {
+    let file = "test_rename_dependencies.gdron";
+    let file_path = format!("res://{file}");
+    save(load::<WithExtGd>(EXT_PATH), &file_path);
+
+    let moved = "res://ext_test/moved/test_godot_res.tres";
+    let renames = HashMap::from([(GODOT_RES_PATH.to_string(), moved.to_string())]);
+
+    let loader = PropPluginLoader::singleton_refcount();
+    let renamed = loader
+        .bind()
+        ._int_try_rename_dependencies(file_path.clone().into(), &renames)
+        .unwrap();
+    assert_eq!(renamed, 1);
+
+    let mut paths: Vec<String> = loader
+        .bind()
+        ._int_try_get_dependencies(file_path.into())
+        .unwrap()
+        .into_iter()
+        .map(|reference| reference.path)
+        .collect();
+    paths.sort();
+    assert_eq!(paths, [moved, RESOURCE_PATH]);
+
+    remove_file("res://", file);
+}
@@ -0,0 +1,256 @@
+use std::io::Write;
+
+use gd_props::codec::{BinCodec, GdPropCodec, GdPropCompression, RonCodec};
+use gd_props::private::{ExporterState, GdRonExport};
+use gd_props::traits::GdProp;
+use gd_rehearse::itest::gditest;
+use godot::builtin::meta::ToGodot;
+use godot::builtin::{GString, PackedStringArray, Variant};
+use godot::classes::file_access::ModeFlags;
+use godot::classes::FileAccess;
+use godot::obj::NewGd;
+use godot::tools::GFile;
+
+use crate::remove_file;
+use crate::structs::resource::{CompressedResource, TestResource};
+
+const PATH: &str = "res://";
+
+fn write_ron_file(file: &str, class: &str, payload: &str) {
+    let mut gfile = GFile::open(format!("{PATH}{file}"), ModeFlags::WRITE).unwrap();
+    writeln!(gfile, "(gd_class:\"{class}\",uid:\"\")").unwrap();
+    gfile.write_all(payload.as_bytes()).unwrap();
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn malformed_ron_reported_and_skipped() {
+    let file = "test_malformed_export.gdron";
+    let file_path = format!("{PATH}{file}");
+    write_ron_file(file, "TestResource", "(set: [(int: 1, character: ");
+
+    let mut state = ExporterState::default();
+    let bytes = state.process_ron_file::<TestResource>(
+        GString::from(&file_path),
+        GString::from("res://test_malformed_export_from_ron.gdbin"),
+        None,
+    );
+    assert!(bytes.is_none(), "malformed file shouldn't be exported");
+
+    let report = state.report();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].path, file_path);
+    assert_eq!(report[0].class, "TestResource");
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn missing_ron_reported() {
+    let mut state = ExporterState::default();
+    let bytes = state.process_ron_file::<TestResource>(
+        GString::from("res://test_never_saved.gdron"),
+        GString::from("res://test_never_saved_from_ron.gdbin"),
+        None,
+    );
+    assert!(bytes.is_none());
+    assert_eq!(state.report().len(), 1);
+    assert_eq!(state.report()[0].message, "can't read the file");
+}
+
+fn preset_option(name: &str) -> Variant {
+    match name {
+        // `Keep` for debug, `Convert and compress` for release exports.
+        "gd_props/debug_gdron_export" => 0.to_variant(),
+        "gd_props/release_gdron_export" => 2.to_variant(),
+        // `Lz4`
+        "gd_props/compression" => 1.to_variant(),
+        "gd_props/debug_only_paths" => PackedStringArray::from(
+            &[
+                GString::from("res://debug/"),
+                GString::from("res://debug_tools"),
+                GString::from("res://extra/bonus.gdron"),
+            ][..],
+        )
+        .to_variant(),
+        _ => Variant::nil(),
+    }
+}
+
+#[gditest]
+fn debug_and_release_options_selected() {
+    let mut state = ExporterState::default();
+    state.configure(true, GdPropCompression::None, 0, preset_option);
+    assert_eq!(state.ron_export(), GdRonExport::Keep);
+    assert_eq!(state.compression(), GdPropCompression::None);
+
+    state.configure(false, GdPropCompression::None, 0, preset_option);
+    assert_eq!(state.ron_export(), GdRonExport::ConvertCompressed);
+    assert_eq!(state.compression(), GdPropCompression::Lz4);
+}
+
+#[gditest]
+fn missing_options_use_plugin_defaults() {
+    let mut state = ExporterState::default();
+    state.configure(false, GdPropCompression::Zstd, 0, |_| Variant::nil());
+    assert_eq!(state.ron_export(), GdRonExport::ConvertCompressed);
+    assert_eq!(state.compression(), GdPropCompression::Zstd);
+
+    state.configure(false, GdPropCompression::None, 0, |_| Variant::nil());
+    assert_eq!(state.ron_export(), GdRonExport::Convert);
+    assert_eq!(state.compression(), GdPropCompression::None);
+}
+
+#[gditest]
+fn debug_only_paths_stripped_from_release() {
+    let mut state = ExporterState::default();
+    state.configure(false, GdPropCompression::None, 0, preset_option);
+    assert!(state.is_stripped("res://debug/cheats.gdron"));
+    assert!(state.is_stripped("res://debug/nested/cheats.gdbin"));
+    assert!(!state.is_stripped("res://debug/cheats.tres"));
+    assert!(!state.is_stripped("res://debug/icon.png"));
+    assert!(!state.is_stripped("res://levels/first.gdron"));
+    // Directories don't match siblings sharing their prefix, with or without trailing slash.
+    assert!(!state.is_stripped("res://debug_items/sword.gdron"));
+    assert!(state.is_stripped("res://debug_tools/console.gdron"));
+    assert!(!state.is_stripped("res://debug_tools_old/console.gdron"));
+    // Files match only themselves.
+    assert!(state.is_stripped("res://extra/bonus.gdron"));
+    assert!(!state.is_stripped("res://extra/bonus.gdron_backup.gdron"));
+    assert!(!state.is_stripped("res://extra/bonus_level.gdron"));
+
+    state.configure(true, GdPropCompression::None, 0, preset_option);
+    assert!(!state.is_stripped("res://debug/cheats.gdron"));
+}
+
+/// Configure `state` with a `fingerprint` of build reserved for a single test, removing its entries possibly left
+/// behind by an interrupted run.
+fn configure_cached(state: &mut ExporterState, fingerprint: u64) {
+    state.configure(false, GdPropCompression::None, fingerprint, |_| {
+        Variant::nil()
+    });
+    state.clear_cache();
+}
+
+fn write_resource_ron(file: &str, values: Vec<u32>) {
+    let mut resource = CompressedResource::new_gd();
+    resource.bind_mut().values = values;
+    let content = resource.bind()._int_try_to_bytes::<RonCodec>().unwrap();
+    let mut gfile = GFile::open(format!("{PATH}{file}"), ModeFlags::WRITE).unwrap();
+    gfile.write_all(&content).unwrap();
+}
+
+fn process_resource_ron(state: &mut ExporterState, file: &str) -> Vec<u8> {
+    state
+        .process_ron_file::<CompressedResource>(
+            GString::from(format!("{PATH}{file}")),
+            GString::from("res://test_export_cache_from_ron.gdbin"),
+            None,
+        )
+        .expect("file should be exported")
+        .to_vec()
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn unchanged_ron_reused_from_cache() {
+    let file = "test_export_cache_hit.gdron";
+    write_resource_ron(file, (0..32).collect());
+
+    let fingerprint = 0x00ca_c4e0_0001;
+    let other_fingerprint = 0x00ca_c4e0_0002;
+    let mut state = ExporterState::default();
+    configure_cached(&mut state, fingerprint);
+    let translated = process_resource_ron(&mut state, file);
+    assert_eq!(state.cache_hits(), 0);
+    let cached = process_resource_ron(&mut state, file);
+    assert_eq!(state.cache_hits(), 1);
+    assert_eq!(translated, cached);
+
+    // Other build of the extension doesn't reuse translations, and prunes the ones of previous build.
+    configure_cached(&mut state, other_fingerprint);
+    process_resource_ron(&mut state, file);
+    assert_eq!(state.cache_hits(), 0);
+    state.prune_cache();
+
+    state.configure(false, GdPropCompression::None, fingerprint, |_| {
+        Variant::nil()
+    });
+    process_resource_ron(&mut state, file);
+    assert_eq!(state.cache_hits(), 0);
+
+    // Entries of the other build are pruned, and the ones of this build cleared.
+    state.prune_cache();
+    state.clear_cache();
+    state.undo_remaps();
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn changed_ron_translated_again() {
+    let file = "test_export_cache_miss.gdron";
+    write_resource_ron(file, vec![1, 2, 3]);
+
+    let mut state = ExporterState::default();
+    configure_cached(&mut state, 0x00ca_c4e0_0003);
+    let first = process_resource_ron(&mut state, file);
+
+    write_resource_ron(file, vec![3, 2, 1]);
+    let second = process_resource_ron(&mut state, file);
+    assert_eq!(state.cache_hits(), 0);
+    assert_ne!(first, second);
+
+    let (_, translated) =
+        BinCodec::decode::<CompressedResource, _>(&mut second.as_slice()).unwrap();
+    assert_eq!(translated.values, vec![3, 2, 1]);
+
+    state.clear_cache();
+    state.undo_remaps();
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn cache_disabled_by_option() {
+    let file = "test_export_cache_disabled.gdron";
+    write_resource_ron(file, vec![1, 2, 3]);
+
+    let mut state = ExporterState::default();
+    state.configure(
+        false,
+        GdPropCompression::None,
+        0x00ca_c4e0_0004,
+        |name| match name {
+            "gd_props/use_export_cache" => false.to_variant(),
+            _ => Variant::nil(),
+        },
+    );
+    process_resource_ron(&mut state, file);
+    process_resource_ron(&mut state, file);
+    assert_eq!(state.cache_hits(), 0);
+
+    state.clear_cache();
+    state.undo_remaps();
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn ron_translated_in_memory() {
+    let mut resource = CompressedResource::new_gd();
+    resource.bind_mut().values = (0..16).collect();
+    let content = resource.bind()._int_try_to_bytes::<RonCodec>().unwrap();
+
+    // No file is read or written during translation.
+    let path = GString::from("res://test_never_saved.gdron");
+    let bytes = CompressedResource::_int_translate_ron_to_bin(
+        &path,
+        &content,
+        GdPropCompression::Lz4,
+        None,
+    )
+    .unwrap();
+    assert!(!FileAccess::file_exists(path));
+
+    let (header, translated) =
+        BinCodec::decode::<CompressedResource, _>(&mut bytes.as_slice()).unwrap();
+    assert_eq!(header.gd_class, "CompressedResource");
+    assert_eq!(header.compression, GdPropCompression::Lz4);
+    assert_eq!(translated.values, (0..16).collect::<Vec<u32>>());
+}
@@ -0,0 +1,98 @@
+use std::io::Write;
+
+use gd_props::codec::RonCodec;
+use gd_props::serde_gd::broken_references;
+use gd_props::traits::GdProp;
+use gd_rehearse::itest::gditest;
+use godot::classes::file_access::ModeFlags;
+use godot::tools::GFile;
+
+use crate::remove_file;
+use crate::structs::resource::{WithExtGd, WithMissingExt};
+
+const PATH: &str = "res://";
+const EXISTING_PATH: &str = "res://ext_test/test_godot_res.tres";
+const MISSING_PATH: &str = "res://ext_test/removed.tres";
+
+fn ext_resource(path: &str) -> String {
+    format!("ExtResource((gd_class:\"TestGodotResource\",uid:\"\",path:\"{path}\"))")
+}
+
+fn write_ron_file(file: &str, class: &str, payload: &str) {
+    let mut gfile = GFile::open(format!("{PATH}{file}"), ModeFlags::WRITE).unwrap();
+    writeln!(gfile, "(gd_class:\"{class}\",uid:\"\")").unwrap();
+    gfile.write_all(payload.as_bytes()).unwrap();
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn missing_ext_skipped() {
+    let file = "test_missing_ext.gdron";
+    let file_path = format!("{PATH}{file}");
+    write_ron_file(
+        file,
+        "WithMissingExt",
+        &format!(
+            "(vec: [{}, {}], optional: {})",
+            ext_resource(EXISTING_PATH),
+            ext_resource(MISSING_PATH),
+            ext_resource(MISSING_PATH)
+        ),
+    );
+
+    let loaded = WithMissingExt::try_load_ron(file_path.clone().into());
+    assert!(loaded.is_ok(), "can't load resource with missing reference");
+
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.bind().vec.len(), 1);
+    assert!(loaded.bind().optional.is_none());
+
+    let broken = broken_references(&file_path);
+    assert_eq!(broken.len(), 2);
+    assert!(broken
+        .iter()
+        .all(|reference| reference.path == MISSING_PATH));
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn missing_ext_fails_by_default() {
+    let file = "test_missing_ext_error.gdron";
+    let file_path = format!("{PATH}{file}");
+    write_ron_file(
+        file,
+        "WithExtGd",
+        &format!(
+            "(first: ExtResource((gd_class:\"TestResource\",uid:\"\",path:\"{MISSING_PATH}\")), second: None)"
+        ),
+    );
+
+    assert!(WithExtGd::try_load_ron(file_path.clone().into()).is_err());
+    assert_eq!(broken_references(&file_path).len(), 1);
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn missing_ext_from_bytes_not_listed() {
+    let file = "test_missing_ext_listed.gdron";
+    let file_path = format!("{PATH}{file}");
+    let payload = format!("(vec: [{}], optional: None)", ext_resource(MISSING_PATH));
+    write_ron_file(file, "WithMissingExt", &payload);
+    assert!(WithMissingExt::try_load_ron(file_path.clone().into()).is_ok());
+    assert_eq!(broken_references(&file_path).len(), 1);
+
+    let content = format!("(gd_class:\"WithMissingExt\",uid:\"\")\n{payload}");
+    let loaded = WithMissingExt::_int_try_from_bytes::<RonCodec>(content.as_bytes());
+    assert!(
+        loaded.is_ok(),
+        "can't decode resource with missing reference"
+    );
+    assert!(loaded.unwrap().bind().vec.is_empty());
+
+    // Decoding without a file neither records references under empty path, nor replaces ones of the loaded file.
+    assert!(broken_references("").is_empty());
+    assert_eq!(broken_references(&file_path).len(), 1);
+
+    remove_file(PATH, file);
+}
@@ -0,0 +1,66 @@
+use gd_props::codec::{GdPropCodec, RonCodec};
+use gd_props::serde_gd::GdExtRef;
+use gd_rehearse::itest::gditest;
+use godot::builtin::GString;
+use godot::classes::FileAccess;
+use serde::{Deserialize, Serialize};
+
+use crate::structs::resource::{TestGodotResource, TestResource, WithExtGd};
+
+const EXT_PATH: &str = "res://ext_test/test_ext.gdron";
+
+/// Same schema as [`WithExtGd`], with lazily loaded External Resources.
+#[derive(Serialize, Deserialize)]
+struct WithExtRef {
+    first: GdExtRef<TestResource>,
+    second: GdExtRef<TestGodotResource>,
+}
+
+#[gditest]
+fn ext_ref_loads_on_access() {
+    let file = FileAccess::get_file_as_bytes(EXT_PATH.into());
+    let mut reader = file.as_slice();
+    RonCodec::read_header(&mut reader).unwrap();
+    let lazy = RonCodec::read_body::<WithExtRef, _>(&mut reader).unwrap();
+
+    assert!(!lazy.first.is_loaded());
+    assert_eq!(lazy.first.path(), "res://ext_test/test_resource.gdron");
+
+    let first = lazy.first.get().expect("can't load first resource");
+    assert!(lazy.first.is_loaded());
+    assert_eq!(
+        first.get_path(),
+        GString::from("res://ext_test/test_resource.gdron")
+    );
+
+    lazy.second.preload();
+    let second = lazy.second.get().expect("can't load preloaded resource");
+    assert_eq!(
+        second.get_path(),
+        GString::from("res://ext_test/test_godot_res.tres")
+    );
+
+    lazy.first.unload();
+    assert!(!lazy.first.is_loaded());
+}
+
+#[gditest]
+fn ext_ref_serialized_as_ext() {
+    let lazy = WithExtRef {
+        first: GdExtRef::from_path("res://ext_test/test_resource.gdron"),
+        second: GdExtRef::from_path("res://ext_test/test_godot_res.tres"),
+    };
+
+    let mut payload = Vec::new();
+    RonCodec::write_body(&lazy, &mut payload).unwrap();
+    let eager = RonCodec::read_body::<WithExtGd, _>(&mut payload.as_slice()).unwrap();
+
+    assert_eq!(
+        eager.first.get_path(),
+        GString::from("res://ext_test/test_resource.gdron")
+    );
+    assert_eq!(
+        eager.second.unwrap().get_path(),
+        GString::from("res://ext_test/test_godot_res.tres")
+    );
+}
@@ -0,0 +1,49 @@
+use gd_rehearse::itest::gditest;
+use godot::tools::{load, save};
+
+use crate::remove_file;
+use crate::structs::resource::TestResource;
+
+const PATH: &str = "res://";
+
+fn saved_and_loaded_identical(file: &str) {
+    let file_path = &format!("{}{}", PATH, file);
+
+    let saved = TestResource::new_random(4, 4);
+    let saved_set = saved.bind().get_set().clone();
+    let saved_vec = saved.bind().get_vec().clone();
+
+    save(saved, file_path);
+    let loaded = load::<TestResource>(file_path);
+
+    assert!(TestResource::check_set_eq(
+        &saved_set,
+        loaded.bind().get_set()
+    ));
+    assert!(TestResource::check_vec_eq(
+        &saved_vec,
+        loaded.bind().get_vec()
+    ));
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn gdjson_saved_and_loaded() {
+    saved_and_loaded_identical("test.gdjson");
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn gdcbor_saved_and_loaded() {
+    saved_and_loaded_identical("test.gdcbor");
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn gdbc_saved_and_loaded() {
+    saved_and_loaded_identical("test.gdbc");
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn gdpc_saved_and_loaded() {
+    saved_and_loaded_identical("test.gdpc");
+}
@@ -1,10 +1,12 @@
+use gd_props::errors::GdPropError;
+use gd_props::traits::GdProp;
 use gd_rehearse::itest::gditest;
 use godot::builtin::GString;
 use godot::classes::{DirAccess, ResourceLoader};
 use godot::tools::{load, save};
 
 use crate::remove_file;
-use crate::structs::resource::TestResource;
+use crate::structs::resource::{TestResource, WithBundledGd};
 
 #[gditest(scene_path = "res://dev_test.tscn")]
 fn can_save() {
@@ -78,3 +80,65 @@ fn uid_is_stable() {
 
     remove_file(path, file);
 }
+
+#[gditest]
+fn try_load_reports_class_mismatch() {
+    let result = WithBundledGd::try_load_bin("res://load_bench/test.gdbin".into());
+
+    assert!(matches!(result, Err(GdPropError::ClassMismatch { .. })));
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn translated_to_ron_keeps_header() {
+    use gd_props::codec::{GdPropCodec, RonCodec};
+
+    let path = "res://";
+    let file = "test_translate.gdbin";
+    let file_path = &format!("{}{}", path, file);
+
+    let saved = TestResource::new_random(4, 4);
+    let saved_set = saved.bind().get_set().clone();
+    save(saved, file_path);
+
+    let bin_uid = ResourceLoader::singleton().get_resource_uid(file_path.into());
+    let translated = TestResource::try_translate_bin_to_ron(file_path.into()).unwrap();
+
+    let (header, translated) =
+        RonCodec::decode::<TestResource, _>(&mut translated.to_string().as_bytes()).unwrap();
+    let ron_uid = godot::classes::ResourceUid::singleton().text_to_id(header.uid.into());
+
+    assert_eq!(bin_uid, ron_uid);
+    assert!(TestResource::check_set_eq(&saved_set, translated.get_set()));
+
+    remove_file(path, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn truncated_file_reported_corrupt() {
+    use std::io::Write;
+
+    use godot::classes::file_access::ModeFlags;
+    use godot::classes::FileAccess;
+    use godot::global::Error;
+    use godot::tools::GFile;
+
+    let path = "res://";
+    let file = "test_truncated.gdbin";
+    let file_path = &format!("{}{}", path, file);
+
+    save(TestResource::new_random(4, 4), file_path);
+
+    let content = FileAccess::get_file_as_bytes(file_path.into()).to_vec();
+    let mut gfile = GFile::open(file_path, ModeFlags::WRITE).unwrap();
+    gfile.write_all(&content[..content.len() / 2]).unwrap();
+    drop(gfile);
+
+    let result = TestResource::try_load_bin(file_path.into());
+    assert!(matches!(result, Err(GdPropError::LengthMismatch { .. })));
+    assert_eq!(
+        result.unwrap_err().to_godot_error(),
+        Error::ERR_FILE_CORRUPT
+    );
+
+    remove_file(path, file);
+}
@@ -1,3 +1,5 @@
+use gd_props::errors::GdPropError;
+use gd_props::traits::GdProp;
 use gd_rehearse::itest::gditest;
 use godot::builtin::GString;
 use godot::classes::{DirAccess, ResourceLoader, ResourceSaver};
@@ -174,3 +176,10 @@ fn can_load_external() {
     let casted = res.unwrap().try_cast::<WithExtGd>();
     assert!(casted.is_ok());
 }
+
+#[gditest]
+fn try_load_reports_class_mismatch() {
+    let result = WithBundledGd::try_load_ron("res://load_bench/test.gdron".into());
+
+    assert!(matches!(result, Err(GdPropError::ClassMismatch { .. })));
+}
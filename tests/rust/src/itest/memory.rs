@@ -0,0 +1,65 @@
+use gd_props::errors::GdPropError;
+use gd_props::traits::GdProp;
+use gd_rehearse::itest::gditest;
+use godot::builtin::{GString, PackedByteArray};
+
+use crate::structs::resource::{TestResource, WithBundledGd};
+
+#[gditest]
+fn bin_bytes_roundtrip() {
+    let saved = TestResource::new_random(4, 4);
+    let bytes = saved.bind().try_to_bin_bytes();
+    assert!(bytes.is_ok(), "can't serialize to bytes");
+
+    let loaded = TestResource::try_from_bin_bytes(bytes.unwrap());
+    assert!(loaded.is_ok(), "can't deserialize from bytes");
+    let loaded = loaded.unwrap();
+
+    assert!(TestResource::check_set_eq(
+        saved.bind().get_set(),
+        loaded.bind().get_set()
+    ));
+    assert!(TestResource::check_vec_eq(
+        saved.bind().get_vec(),
+        loaded.bind().get_vec()
+    ));
+}
+
+#[gditest]
+fn ron_string_roundtrip() {
+    let saved = TestResource::new_random(3, 2);
+    let content = saved.bind().to_ron_string();
+    assert!(content
+        .to_string()
+        .starts_with("(gd_class:\"TestResource\""));
+
+    let loaded = TestResource::try_from_ron_string(content);
+    assert!(loaded.is_ok(), "can't deserialize from string");
+    let loaded = loaded.unwrap();
+
+    assert!(TestResource::check_set_eq(
+        saved.bind().get_set(),
+        loaded.bind().get_set()
+    ));
+    assert!(TestResource::check_vec_eq(
+        saved.bind().get_vec(),
+        loaded.bind().get_vec()
+    ));
+}
+
+#[gditest]
+fn bytes_of_other_class_fail() {
+    let bytes = TestResource::new_random(1, 1).bind().to_bin_bytes();
+
+    let result = WithBundledGd::try_from_bin_bytes(bytes);
+    assert!(matches!(result, Err(GdPropError::ClassMismatch { .. })));
+}
+
+#[gditest]
+fn malformed_content_fails() {
+    let result = TestResource::try_from_bin_bytes(PackedByteArray::from(&[1, 2, 3][..]));
+    assert!(result.is_err());
+
+    let result = TestResource::try_from_ron_string(GString::from("(gd_class:"));
+    assert!(result.is_err());
+}
@@ -0,0 +1,49 @@
+use std::io::Write;
+
+use gd_rehearse::itest::gditest;
+use godot::classes::file_access::ModeFlags;
+use godot::tools::{try_load, GFile};
+
+use crate::remove_file;
+use crate::structs::resource::VersionedResource;
+
+const PATH: &str = "res://";
+
+fn write_ron_file(file: &str, version: Option<u32>, payload: &str) {
+    let mut gfile = GFile::open(format!("{PATH}{file}"), ModeFlags::WRITE).unwrap();
+    let version = version
+        .map(|version| format!(",version:{version}"))
+        .unwrap_or_default();
+    writeln!(
+        gfile,
+        "(gd_class:\"VersionedResource\",uid:\"uid://cgmqkr8i6rr5v\"{version})"
+    )
+    .unwrap();
+    gfile.write_all(payload.as_bytes()).unwrap();
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn unversioned_file_is_migrated() {
+    let file = "test_migration.gdron";
+    write_ron_file(file, None, "(name: \"Player\", hp: 15)");
+
+    let loaded = try_load::<VersionedResource>(format!("{PATH}{file}"));
+    assert!(loaded.is_ok(), "can't load migrated resource");
+
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.bind().name, "Player");
+    assert_eq!(loaded.bind().health, 15);
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn newer_version_is_rejected() {
+    let file = "test_migration_newer.gdron";
+    write_ron_file(file, Some(2), "(name: \"Player\", health: 15)");
+
+    let loaded = try_load::<VersionedResource>(format!("{PATH}{file}"));
+    assert!(loaded.is_err());
+
+    remove_file(PATH, file);
+}
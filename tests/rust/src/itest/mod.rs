@@ -1,5 +1,19 @@
+mod atomic;
+mod compression;
+mod dependencies;
 mod export;
+mod export_processing;
+mod ext_missing;
+mod ext_ref;
+mod formats;
 mod gdbin;
 mod gdron;
+mod memory;
+mod migration;
+mod network;
+mod poly;
 mod saver_loader;
+mod security;
 mod serde_gd;
+mod subresource;
+mod threaded;
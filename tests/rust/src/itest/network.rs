@@ -0,0 +1,72 @@
+use gd_props::errors::GdPropError;
+use gd_props::network::GdPropSync;
+use gd_rehearse::itest::gditest;
+
+use crate::structs::resource::{TestResource, WithBundledGd};
+
+#[gditest]
+fn full_packet_roundtrip() {
+    let sent = TestResource::new_random(4, 4);
+    let packet = GdPropSync::new().pack(&*sent.bind());
+    assert!(packet.is_ok(), "can't pack resource");
+
+    let received = GdPropSync::<TestResource>::new().unpack(&packet.unwrap());
+    assert!(received.is_ok(), "can't unpack resource");
+    let received = received.unwrap();
+
+    assert!(TestResource::check_set_eq(
+        sent.bind().get_set(),
+        received.bind().get_set()
+    ));
+    assert!(TestResource::check_vec_eq(
+        sent.bind().get_vec(),
+        received.bind().get_vec()
+    ));
+}
+
+#[gditest]
+fn delta_packet_holds_changes() {
+    let mut sender = GdPropSync::new();
+    let mut receiver = GdPropSync::<TestResource>::new();
+
+    let mut sent = TestResource::new_random(16, 2);
+    let full = sender.pack_delta(&*sent.bind()).unwrap();
+    receiver.unpack(&full).unwrap();
+
+    sent.bind_mut().push_random();
+    let delta = sender.pack_delta(&*sent.bind()).unwrap();
+    assert!(delta.len() < full.len(), "delta should skip unchanged set");
+
+    let received = receiver.unpack(&delta).unwrap();
+    assert_eq!(received.bind().get_vec().len(), 3);
+    assert!(TestResource::check_set_eq(
+        sent.bind().get_set(),
+        received.bind().get_set()
+    ));
+    assert!(TestResource::check_vec_eq(
+        sent.bind().get_vec(),
+        received.bind().get_vec()
+    ));
+}
+
+#[gditest]
+fn delta_without_base_fails() {
+    let mut sender = GdPropSync::new();
+    let mut sent = TestResource::new_random(2, 2);
+    sender.pack(&*sent.bind()).unwrap();
+    sent.bind_mut().push_random();
+    let delta = sender.pack_delta(&*sent.bind()).unwrap();
+
+    let result = GdPropSync::<TestResource>::new().unpack(&delta);
+    assert!(matches!(result, Err(GdPropError::DeltaBaseMismatch { .. })));
+}
+
+#[gditest]
+fn packet_of_other_class_fails() {
+    let packet = GdPropSync::new()
+        .pack(&*TestResource::new_random(1, 1).bind())
+        .unwrap();
+
+    let result = GdPropSync::<WithBundledGd>::new().unpack(&packet);
+    assert!(matches!(result, Err(GdPropError::ClassMismatch { .. })));
+}
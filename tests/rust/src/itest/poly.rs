@@ -0,0 +1,76 @@
+use gd_props::traits::GdProp;
+use gd_rehearse::itest::gditest;
+use godot::classes::Resource;
+use godot::obj::{Gd, NewGd};
+
+use crate::remove_file;
+use crate::structs::resource::{FireEffect, IceEffect, WithPolyEffects};
+
+const PATH: &str = "res://";
+
+fn with_poly_effects() -> Gd<WithPolyEffects> {
+    let mut fire = FireEffect::new_gd();
+    fire.bind_mut().damage = 5;
+    let mut ice = IceEffect::new_gd();
+    ice.bind_mut().slow = 0.5;
+
+    let mut resource = WithPolyEffects::new_gd();
+    resource.bind_mut().effects = vec![fire.clone().upcast(), ice.upcast()];
+    resource.bind_mut().fire = Some(fire);
+    resource
+}
+
+fn check_poly_effects(effects: &[Gd<Resource>], fire: &Option<Gd<FireEffect>>) {
+    assert_eq!(effects.len(), 2);
+    let first = effects[0].clone().try_cast::<FireEffect>();
+    assert!(first.is_ok(), "first effect is not FireEffect");
+    assert_eq!(first.unwrap().bind().damage, 5);
+    let second = effects[1].clone().try_cast::<IceEffect>();
+    assert!(second.is_ok(), "second effect is not IceEffect");
+    assert_eq!(second.unwrap().bind().slow, 0.5);
+    assert_eq!(fire.as_ref().unwrap().bind().damage, 5);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn poly_effects_gdron() {
+    let file = "test_poly.gdron";
+    let file_path = format!("{PATH}{file}");
+    let resource = with_poly_effects();
+    assert!(resource
+        .bind()
+        .try_save_ron(file_path.clone().into())
+        .is_ok());
+
+    let loaded = WithPolyEffects::try_load_ron(file_path.into());
+    assert!(loaded.is_ok(), "can't load polymorphic effects");
+    let loaded = loaded.unwrap();
+    check_poly_effects(&loaded.bind().effects, &loaded.bind().fire);
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn poly_effects_gdbin() {
+    let file = "test_poly.gdbin";
+    let file_path = format!("{PATH}{file}");
+    let resource = with_poly_effects();
+    assert!(resource
+        .bind()
+        .try_save_bin(file_path.clone().into())
+        .is_ok());
+
+    let loaded = WithPolyEffects::try_load_bin(file_path.into());
+    assert!(loaded.is_ok(), "can't load polymorphic effects");
+    let loaded = loaded.unwrap();
+    check_poly_effects(&loaded.bind().effects, &loaded.bind().fire);
+
+    remove_file(PATH, file);
+}
+
+#[gditest]
+fn poly_unregistered_class_fails() {
+    let mut resource = WithPolyEffects::new_gd();
+    resource.bind_mut().effects = vec![Resource::new_gd()];
+
+    assert!(ron::to_string(&*resource.bind()).is_err());
+}
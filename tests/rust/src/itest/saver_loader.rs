@@ -98,3 +98,34 @@ fn save_and_load_global() {
 
     da.remove(GString::from(file));
 }
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn loader_decompiles_bin() {
+    use godot::builtin::meta::ToGodot;
+    use godot::global::Error;
+
+    let bin_path = format!("{}{}", RES_PATH, "test_decompile.gdbin");
+    let ron_name = "test_decompile_from_bin.gdron";
+    let ron_path = format!("{}{}", RES_PATH, ron_name);
+
+    let resource = TestResource::new_random(3, 3);
+    let saved_vec = resource.bind().get_vec().clone();
+    save(resource, &bin_path);
+
+    let mut loader = PropPluginLoader::singleton_refcount();
+    let result = loader.call(
+        "decompile_bin".into(),
+        &[bin_path.to_variant(), ron_path.to_variant()],
+    );
+    assert_eq!(result.to::<Error>(), Error::OK);
+
+    let loaded = TestResource::try_load_ron(ron_path.into()).unwrap();
+    assert!(TestResource::check_vec_eq(
+        &saved_vec,
+        loaded.bind().get_vec()
+    ));
+
+    let mut da = DirAccess::open(RES_PATH.into()).unwrap();
+    da.remove("test_decompile.gdbin".into());
+    da.remove(ron_name.into());
+}
@@ -0,0 +1,136 @@
+use std::io::Write;
+
+use gd_props::codec::GdPropKey;
+use gd_props::errors::GdPropError;
+use gd_props::traits::{GdProp, GdPropLoader, GdPropSaver, RefCountedSingleton};
+use gd_rehearse::itest::gditest;
+use godot::classes::file_access::ModeFlags;
+use godot::classes::{FileAccess, ResourceLoader};
+use godot::tools::{load, save, GFile};
+
+use crate::remove_file;
+use crate::structs::prop_handlers::{PropPluginLoader, PropPluginSaver};
+use crate::structs::resource::TestResource;
+
+const PATH: &str = "res://";
+const KEY: GdPropKey = GdPropKey::ChaCha20Poly1305([42; 32]);
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn sealed_bin_saved_and_loaded() {
+    let file = "test_sealed.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    let saved = TestResource::new_random(4, 4);
+    let saved_vec = saved.bind().get_vec().clone();
+    saved
+        .bind()
+        .try_save_bin_with_key(file_path.clone().into(), &KEY)
+        .unwrap();
+
+    let loaded = TestResource::try_load_bin_with_key(file_path.clone().into(), &KEY).unwrap();
+    assert!(TestResource::check_vec_eq(
+        &saved_vec,
+        loaded.bind().get_vec()
+    ));
+
+    let without_key = TestResource::try_load_bin(file_path.into());
+    assert!(matches!(without_key, Err(GdPropError::KeyMissing)));
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn tampered_bin_rejected() {
+    let file = "test_tampered.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    let saved = TestResource::new_random(4, 4);
+    saved
+        .bind()
+        .try_save_bin_with_key(file_path.clone().into(), &KEY)
+        .unwrap();
+
+    let mut content = FileAccess::get_file_as_bytes(file_path.clone().into()).to_vec();
+    *content.last_mut().unwrap() ^= 0xff;
+    let mut gfile = GFile::open(&file_path, ModeFlags::WRITE).unwrap();
+    gfile.write_all(&content).unwrap();
+    drop(gfile);
+
+    let tampered = TestResource::try_load_bin_with_key(file_path.into(), &KEY);
+    assert!(matches!(tampered, Err(GdPropError::Tampered)));
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn keyed_saver_and_loader_seal_bin() {
+    let file = "test_keyed_plugin.gdbin";
+    let file_path = format!("{PATH}{file}");
+
+    let mut saver = PropPluginSaver::singleton_refcount();
+    let mut loader = PropPluginLoader::singleton_refcount();
+    *saver.bind_mut()._int_key_mut() = Some(KEY);
+    *loader.bind_mut()._int_key_mut() = Some(KEY);
+
+    let saved = TestResource::new_random(4, 4);
+    let saved_vec = saved.bind().get_vec().clone();
+    save(saved, &file_path);
+    let loaded = load::<TestResource>(&file_path);
+    assert!(TestResource::check_vec_eq(
+        &saved_vec,
+        loaded.bind().get_vec()
+    ));
+
+    *saver.bind_mut()._int_key_mut() = None;
+    *loader.bind_mut()._int_key_mut() = None;
+
+    let without_key = TestResource::try_load_bin(file_path.into());
+    assert!(matches!(without_key, Err(GdPropError::KeyMissing)));
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn keyed_loader_decompiles_sealed_bin() {
+    use godot::builtin::meta::ToGodot;
+    use godot::global::Error;
+
+    let file = "test_keyed_decompile.gdbin";
+    let ron_file = "test_keyed_decompile_from_bin.gdron";
+    let file_path = format!("{PATH}{file}");
+    let ron_path = format!("{PATH}{ron_file}");
+
+    let saved = TestResource::new_random(3, 3);
+    let saved_vec = saved.bind().get_vec().clone();
+    saved
+        .bind()
+        .try_save_bin_with_key(file_path.clone().into(), &KEY)
+        .unwrap();
+
+    let mut loader = PropPluginLoader::singleton_refcount();
+    *loader.bind_mut()._int_key_mut() = Some(KEY);
+    let result = loader.call(
+        "decompile_bin".into(),
+        &[file_path.to_variant(), ron_path.to_variant()],
+    );
+    *loader.bind_mut()._int_key_mut() = None;
+    assert_eq!(result.to::<Error>(), Error::OK);
+
+    let content =
+        TestResource::try_translate_bin_to_ron_with_key(file_path.clone().into(), Some(&KEY));
+    assert!(content.is_ok(), "can't translate sealed file with the key");
+
+    let loaded = TestResource::try_load_ron(ron_path.clone().into()).unwrap();
+    assert!(TestResource::check_vec_eq(
+        &saved_vec,
+        loaded.bind().get_vec()
+    ));
+
+    let mut loader = ResourceLoader::singleton();
+    let bin_uid = loader.get_resource_uid(file_path.into());
+    let ron_uid = loader.get_resource_uid(ron_path.into());
+    assert_ne!(bin_uid, ron_uid, "decompiled file should have its own UID");
+
+    remove_file(PATH, file);
+    remove_file(PATH, ron_file);
+}
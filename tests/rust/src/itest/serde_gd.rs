@@ -1,10 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::BufWriter;
 
 use gd_rehearse::itest::gditest;
-use godot::builtin::{Array, GString};
-use godot::classes::ResourceLoader;
-use godot::obj::Gd;
+use godot::builtin::{
+    varray, Array, Basis, Color, Dictionary, EulerOrder, GString, NodePath, PackedVector3Array,
+    Rect2, StringName, Transform3D, Variant, Vector2, Vector2i, Vector3,
+};
+use godot::classes::{Curve, Gradient, GradientTexture1D, ResourceLoader};
+use godot::obj::{Gd, NewGd};
 
 use ron::Serializer;
 use serde::Serialize;
@@ -213,3 +216,226 @@ fn serde_external_hashmap() {
         remove_file(path, file);
     }
 }
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn serde_adapters() {
+    let path = "res://";
+    let subresources = TestGodotResource::new_saved_multiple(path, 2);
+    let mut dictionary = Dictionary::new();
+    dictionary.set("first", TestResource::new_random(2, 2));
+
+    let resource = WithAdapters {
+        slots: vec![Some(TestResource::new_random(3, 4)), None],
+        stashes: HashMap::from([(
+            "stash".to_string(),
+            vec![
+                TestResource::new_random(1, 2),
+                TestResource::new_random(2, 1),
+            ],
+        )]),
+        ext: subresources
+            .values()
+            .cloned()
+            .enumerate()
+            .map(|(index, subresource)| (index as u32, subresource))
+            .collect(),
+        dictionary,
+    };
+
+    let mut buffer = Vec::new();
+    let mut serializer = Serializer::new(&mut buffer, None).unwrap();
+    let result = resource.serialize(&mut serializer);
+    assert!(result.is_ok());
+    drop(serializer);
+
+    let result = ron::de::from_bytes::<WithAdapters>(&buffer);
+    assert!(result.is_ok());
+    let deserialized = result.unwrap();
+
+    assert_eq!(deserialized.slots.len(), 2);
+    assert!(TestResource::check_vec_eq(
+        resource.slots[0].as_ref().unwrap().bind().get_vec(),
+        deserialized.slots[0].as_ref().unwrap().bind().get_vec()
+    ));
+    assert!(deserialized.slots[1].is_none());
+    assert_eq!(deserialized.stashes.get("stash").unwrap().len(), 2);
+    for (index, subresource) in resource.ext.iter() {
+        assert_eq!(
+            deserialized.ext.get(index).unwrap().get_path(),
+            subresource.get_path()
+        );
+    }
+    let first = deserialized
+        .dictionary
+        .get("first")
+        .unwrap()
+        .to::<Gd<TestResource>>();
+    assert!(TestResource::check_set_eq(
+        resource
+            .dictionary
+            .get("first")
+            .unwrap()
+            .to::<Gd<TestResource>>()
+            .bind()
+            .get_set(),
+        first.bind().get_set()
+    ));
+
+    for file in subresources.keys() {
+        remove_file(path, file);
+    }
+}
+
+#[gditest]
+fn serde_adapters_reject_non_string_keys() {
+    let with_dictionary = |dictionary: Dictionary| WithAdapters {
+        slots: Vec::new(),
+        stashes: HashMap::new(),
+        ext: BTreeMap::new(),
+        dictionary,
+    };
+    let serialize = |resource: &WithAdapters| {
+        let mut buffer = Vec::new();
+        let mut serializer = Serializer::new(&mut buffer, None).unwrap();
+        resource.serialize(&mut serializer).map(|_| ())
+    };
+
+    let mut dictionary = Dictionary::new();
+    dictionary.set(StringName::from("named"), TestResource::new_random(1, 1));
+    assert!(serialize(&with_dictionary(dictionary)).is_ok());
+
+    let mut dictionary = Dictionary::new();
+    dictionary.set(1, TestResource::new_random(1, 1));
+    assert!(serialize(&with_dictionary(dictionary)).is_err());
+
+    let mut dictionary = Dictionary::new();
+    dictionary.set(Vector2i::new(1, 2), TestResource::new_random(1, 1));
+    assert!(serialize(&with_dictionary(dictionary)).is_err());
+}
+
+fn with_builtins() -> WithBuiltins {
+    let mut dictionary = Dictionary::new();
+    dictionary.set("speed", 4.5);
+    dictionary.set(3, Vector2i::new(1, -1));
+
+    WithBuiltins {
+        position: Vector2::new(1.5, -2.0),
+        tint: Color::from_rgba(0.2, 0.4, 0.6, 1.0),
+        rect: Rect2::new(Vector2::new(0.0, 1.0), Vector2::new(2.0, 3.0)),
+        basis: Basis::from_euler(EulerOrder::XYZ, Vector3::new(0.1, 0.2, 0.3)),
+        transform: Transform3D::new(Basis::IDENTITY, Vector3::new(4.0, 5.0, 6.0)),
+        name: StringName::from("spawn"),
+        node: NodePath::from("Level/Spawn"),
+        points: PackedVector3Array::from(&[Vector3::ZERO, Vector3::ONE][..]),
+        variant: Variant::from(Color::from_rgb(1.0, 0.0, 0.0)),
+        array: varray![1, "two", Vector3::UP],
+        dictionary,
+        path: vec![Vector2::ZERO, Vector2::new(3.0, 4.0)],
+    }
+}
+
+fn check_builtins(resource: &WithBuiltins, deserialized: &WithBuiltins) {
+    assert_eq!(resource.position, deserialized.position);
+    assert_eq!(resource.tint, deserialized.tint);
+    assert_eq!(resource.rect, deserialized.rect);
+    assert_eq!(resource.basis, deserialized.basis);
+    assert_eq!(resource.transform, deserialized.transform);
+    assert_eq!(resource.name, deserialized.name);
+    assert_eq!(resource.node, deserialized.node);
+    assert_eq!(resource.points, deserialized.points);
+    assert_eq!(resource.variant, deserialized.variant);
+    assert_eq!(resource.array, deserialized.array);
+    assert_eq!(resource.dictionary, deserialized.dictionary);
+    assert_eq!(resource.path, deserialized.path);
+}
+
+#[gditest]
+fn serde_builtins_ron() {
+    let resource = with_builtins();
+
+    let serialized = ron::to_string(&resource);
+    assert!(serialized.is_ok());
+    let serialized = serialized.unwrap();
+    assert!(serialized.contains("position:(x:1.5,y:-2.0)"));
+
+    let result = ron::from_str::<WithBuiltins>(&serialized);
+    assert!(result.is_ok());
+    check_builtins(&resource, &result.unwrap());
+}
+
+#[gditest]
+fn serde_builtins_bin() {
+    let resource = with_builtins();
+
+    let serialized = rmp_serde::to_vec(&resource);
+    assert!(serialized.is_ok());
+
+    let result = rmp_serde::from_slice::<WithBuiltins>(&serialized.unwrap());
+    assert!(result.is_ok());
+    check_builtins(&resource, &result.unwrap());
+}
+
+fn with_native() -> WithNative {
+    let mut curve = Curve::new_gd();
+    curve.add_point(Vector2::new(0.0, 1.0));
+    curve.add_point(Vector2::new(1.0, 0.25));
+
+    let mut gradient = Gradient::new_gd();
+    gradient.set_color(1, Color::from_rgba(1.0, 0.0, 0.0, 1.0));
+
+    let mut texture = GradientTexture1D::new_gd();
+    texture.set_gradient(gradient.clone());
+    texture.set_width(64);
+
+    WithNative {
+        curve,
+        gradient: Some(gradient),
+        textures: vec![texture],
+    }
+}
+
+fn check_native(resource: &WithNative, deserialized: &WithNative) {
+    assert_eq!(deserialized.curve.get_point_count(), 2);
+    assert_eq!(
+        resource.curve.get_point_position(1),
+        deserialized.curve.get_point_position(1)
+    );
+
+    let gradient = deserialized.gradient.as_ref().unwrap();
+    assert_eq!(
+        resource.gradient.as_ref().unwrap().get_colors(),
+        gradient.get_colors()
+    );
+
+    assert_eq!(deserialized.textures.len(), 1);
+    let texture = &deserialized.textures[0];
+    assert_eq!(texture.get_width(), 64);
+    let embedded = texture.get_gradient().unwrap();
+    assert_eq!(embedded.get_colors(), gradient.get_colors());
+}
+
+#[gditest]
+fn serde_native_ron() {
+    let resource = with_native();
+
+    let serialized = ron::to_string(&resource);
+    assert!(serialized.is_ok());
+    let serialized = serialized.unwrap();
+    assert!(serialized.contains("class:\"Curve\""));
+
+    let result = ron::from_str::<WithNative>(&serialized);
+    assert!(result.is_ok());
+    check_native(&resource, &result.unwrap());
+}
+
+#[gditest]
+fn serde_native_bin() {
+    let resource = with_native();
+
+    let serialized = rmp_serde::to_vec(&resource);
+    assert!(serialized.is_ok());
+
+    let result = rmp_serde::from_slice::<WithNative>(&serialized.unwrap());
+    assert!(result.is_ok());
+    check_native(&resource, &result.unwrap());
+}
@@ -0,0 +1,95 @@
+use gd_props::traits::GdProp;
+use gd_rehearse::itest::gditest;
+use godot::builtin::Array;
+use godot::classes::FileAccess;
+use godot::obj::{Gd, NewGd};
+
+use crate::remove_file;
+use crate::structs::resource::{SharedGraph, SharedNode};
+
+const PATH: &str = "res://";
+
+fn node(line: &str) -> Gd<SharedNode> {
+    let mut node = SharedNode::new_gd();
+    node.bind_mut().line = line.to_string();
+    node
+}
+
+/// Graph of two nodes referencing each other, with the first one as the start.
+fn cyclic_graph() -> Gd<SharedGraph> {
+    let mut first = node("first");
+    let mut second = node("second");
+    first.bind_mut().next.push(second.clone());
+    second.bind_mut().next.push(first.clone());
+
+    let mut graph = SharedGraph::new_gd();
+    graph.bind_mut().nodes = Array::from(&[first.clone(), second]);
+    graph.bind_mut().start = Some(first);
+    graph
+}
+
+/// Break the cycles, so the nodes can be freed.
+fn clear_graph(graph: &Gd<SharedGraph>) {
+    for mut node in graph.bind().nodes.iter_shared() {
+        node.bind_mut().next.clear();
+    }
+}
+
+fn check_graph(graph: &Gd<SharedGraph>) {
+    let graph = graph.bind();
+    assert_eq!(graph.nodes.len(), 2);
+    let first = graph.nodes.get(0).unwrap();
+    let second = graph.nodes.get(1).unwrap();
+
+    assert_eq!(first.bind().line, "first");
+    assert_eq!(second.bind().line, "second");
+    assert_eq!(
+        graph.start.as_ref().unwrap().instance_id(),
+        first.instance_id()
+    );
+    assert_eq!(
+        first.bind().next.get(0).unwrap().instance_id(),
+        second.instance_id()
+    );
+    assert_eq!(
+        second.bind().next.get(0).unwrap().instance_id(),
+        first.instance_id()
+    );
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn shared_subresources_gdron() {
+    let file = "test_shared.gdron";
+    let file_path = format!("{PATH}{file}");
+    let graph = cyclic_graph();
+    assert!(graph.bind().try_save_ron(file_path.clone().into()).is_ok());
+    clear_graph(&graph);
+
+    let saved = FileAccess::get_file_as_string(file_path.clone().into()).to_string();
+    assert_eq!(saved.matches("SubResource(").count(), 2);
+
+    let loaded = SharedGraph::try_load_ron(file_path.into());
+    assert!(loaded.is_ok(), "can't load shared subresources");
+    let loaded = loaded.unwrap();
+    check_graph(&loaded);
+    clear_graph(&loaded);
+
+    remove_file(PATH, file);
+}
+
+#[gditest(scene_path = "res://dev_test.tscn")]
+fn shared_subresources_gdbin() {
+    let file = "test_shared.gdbin";
+    let file_path = format!("{PATH}{file}");
+    let graph = cyclic_graph();
+    assert!(graph.bind().try_save_bin(file_path.clone().into()).is_ok());
+    clear_graph(&graph);
+
+    let loaded = SharedGraph::try_load_bin(file_path.into());
+    assert!(loaded.is_ok(), "can't load shared subresources");
+    let loaded = loaded.unwrap();
+    check_graph(&loaded);
+    clear_graph(&loaded);
+
+    remove_file(PATH, file);
+}
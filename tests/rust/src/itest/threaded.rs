@@ -0,0 +1,52 @@
+use gd_rehearse::itest::gditest;
+use godot::builtin::GString;
+use godot::classes::resource_loader::CacheMode;
+use godot::classes::ResourceLoader;
+use godot::global::Error;
+use godot::tools::load;
+
+use crate::structs::resource::WithExtGd;
+
+const EXT_PATH: &str = "res://ext_test/test_ext.gdron";
+
+#[gditest]
+fn threaded_load_with_sub_threads() {
+    let mut loader = ResourceLoader::singleton();
+    let error = loader
+        .load_threaded_request_ex(EXT_PATH.into())
+        .use_sub_threads(true)
+        .done();
+    assert_eq!(error, Error::OK);
+
+    let loaded = loader
+        .load_threaded_get(EXT_PATH.into())
+        .expect("threaded load failed")
+        .cast::<WithExtGd>();
+
+    assert_eq!(
+        loaded.bind().first.get_path(),
+        GString::from("res://ext_test/test_resource.gdron")
+    );
+    assert_eq!(
+        loaded.bind().second.as_ref().unwrap().get_path(),
+        GString::from("res://ext_test/test_godot_res.tres")
+    );
+}
+
+#[gditest]
+fn ignored_cache_reuses_external_resources() {
+    let cached = load::<WithExtGd>(EXT_PATH);
+
+    let ignored = ResourceLoader::singleton()
+        .load_ex(EXT_PATH.into())
+        .cache_mode(CacheMode::IGNORE)
+        .done()
+        .expect("load ignoring cache failed")
+        .cast::<WithExtGd>();
+
+    assert_ne!(cached.instance_id(), ignored.instance_id());
+    assert_eq!(
+        cached.bind().first.instance_id(),
+        ignored.bind().first.instance_id()
+    );
+}
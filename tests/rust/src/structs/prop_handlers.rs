@@ -3,4 +3,7 @@ use gd_props::gd_props_plugin;
 
 #[gd_props_plugin]
 #[register(TestResource, WithBundledGd, WithExtGd, WithBundleArray)]
+#[register(VersionedResource, CompressedResource, BackedUpResource)]
+#[register(FireEffect, IceEffect, WithPolyEffects)]
+#[formats(Ron, Bin, Json, Cbor, Bincode, Postcard)]
 pub(crate) struct PropPlugin;
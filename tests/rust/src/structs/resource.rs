@@ -1,10 +1,17 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+use gd_props::errors::GdPropError;
+use gd_props::migration::GdPropPayload;
+use gd_props::serde_gd::{GdAs, GdBuiltin, GdBundled, GdDictionary, GdExt, GdNative, GdPoly};
+use gd_props::traits::GdPropMigrate;
 use gd_props::GdProp;
 
-use godot::builtin::{Array, GString};
-use godot::classes::{IResource, ResourceSaver};
-use godot::obj::Gd;
+use godot::builtin::{
+    Array, Basis, Color, Dictionary, GString, NodePath, PackedVector3Array, Rect2, StringName,
+    Transform3D, Variant, VariantArray, Vector2,
+};
+use godot::classes::{Curve, Gradient, GradientTexture1D, IResource, Resource, ResourceSaver};
+use godot::obj::{Gd, NewGd};
 use godot::prelude::{godot_api, GodotClass};
 
 use rand::Rng;
@@ -12,6 +19,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::random_string;
 
+use super::prop_handlers::PropPlugin;
 use super::singleton::GodotSingleton;
 
 #[derive(Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
@@ -63,6 +71,11 @@ impl TestResource {
         &self.vec
     }
 
+    pub fn push_random(&mut self) {
+        self.vec
+            .push(InnerThing::new_random(&mut rand::thread_rng()));
+    }
+
     pub fn check_set_eq(first: &HashSet<InnerThing>, other: &HashSet<InnerThing>) -> bool {
         let mut first_set = first.clone();
         let mut second_set = other.clone();
@@ -240,3 +253,145 @@ pub struct WithExtHashMap {
     #[serde(with = "gd_props::serde_gd::ext_hashmap")]
     pub map: HashMap<String, Gd<TestGodotResource>>,
 }
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct WithAdapters {
+    #[serde(with = "GdAs::<Vec<Option<GdBundled>>>")]
+    pub slots: Vec<Option<Gd<TestResource>>>,
+    #[serde(with = "GdAs::<HashMap<String, Vec<GdBundled>>>")]
+    pub stashes: HashMap<String, Vec<Gd<TestResource>>>,
+    #[serde(with = "GdAs::<BTreeMap<u32, GdExt>>")]
+    pub ext: BTreeMap<u32, Gd<TestGodotResource>>,
+    #[serde(with = "GdAs::<GdDictionary<TestResource, GdBundled>>")]
+    pub dictionary: Dictionary,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct WithBuiltins {
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub position: Vector2,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub tint: Color,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub rect: Rect2,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub basis: Basis,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub transform: Transform3D,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub name: StringName,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub node: NodePath,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub points: PackedVector3Array,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub variant: Variant,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub array: VariantArray,
+    #[serde(with = "gd_props::serde_gd::builtin")]
+    pub dictionary: Dictionary,
+    #[serde(with = "GdAs::<Vec<GdBuiltin>>")]
+    pub path: Vec<Vector2>,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct WithNative {
+    #[serde(with = "gd_props::serde_gd::native")]
+    #[init(default = Curve::new_gd())]
+    pub curve: Gd<Curve>,
+    #[serde(with = "GdAs::<Option<GdNative>>")]
+    pub gradient: Option<Gd<Gradient>>,
+    #[serde(with = "GdAs::<Vec<GdNative>>")]
+    pub textures: Vec<Gd<GradientTexture1D>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VersionedResourceV0 {
+    pub name: String,
+    pub hp: u32,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+#[gdprop(version = 1, migrate)]
+pub struct VersionedResource {
+    pub name: String,
+    pub health: u32,
+}
+
+impl GdPropMigrate for VersionedResource {
+    fn migrate(version: u32, payload: GdPropPayload) -> Result<GdPropPayload, GdPropError> {
+        match version {
+            0 => payload.map(|old: VersionedResourceV0| VersionedResource {
+                name: old.name,
+                health: old.hp,
+            }),
+            _ => Ok(payload),
+        }
+    }
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+#[gdprop(compression = Zstd)]
+pub struct CompressedResource {
+    pub values: Vec<u32>,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+#[gdprop(backups = 2)]
+pub struct BackedUpResource {
+    pub value: u32,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+#[gdprop(missing_ext = Skip)]
+pub struct WithMissingExt {
+    #[serde(with = "gd_props::serde_gd::ext_array")]
+    pub vec: Array<Gd<TestGodotResource>>,
+    #[serde(with = "gd_props::serde_gd::ext_option")]
+    pub optional: Option<Gd<TestGodotResource>>,
+}
+
+#[derive(GodotClass, Serialize, Deserialize)]
+#[class(base=Resource, init)]
+pub struct SharedNode {
+    pub line: String,
+    #[serde(with = "gd_props::serde_gd::gd_shared_array")]
+    pub next: Array<Gd<SharedNode>>,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct SharedGraph {
+    #[serde(with = "gd_props::serde_gd::gd_shared_array")]
+    pub nodes: Array<Gd<SharedNode>>,
+    #[serde(with = "gd_props::serde_gd::gd_shared_option")]
+    pub start: Option<Gd<SharedNode>>,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct FireEffect {
+    pub damage: u32,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct IceEffect {
+    pub slow: f32,
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp)]
+#[class(base=Resource, init)]
+pub struct WithPolyEffects {
+    #[serde(with = "GdAs::<Vec<GdPoly<PropPlugin>>>")]
+    pub effects: Vec<Gd<Resource>>,
+    #[serde(with = "GdAs::<Option<GdPoly<PropPlugin>>>")]
+    pub fire: Option<Gd<FireEffect>>,
+}
@@ -60,3 +60,454 @@ fn gdres_trait_can_be_implemented() {
 
     assert_eq!(TestStruct::HEAD_IDENT, "TestStruct");
 }
+
+#[test]
+fn gdres_version_can_be_set() {
+    #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+    #[class(init, base=Resource)]
+    struct Unversioned;
+
+    #[derive(GodotClass, Serialize, Deserialize, GdProp)]
+    #[class(init, base=Resource)]
+    #[gdprop(version = 3)]
+    struct Versioned;
+
+    assert_eq!(Unversioned::VERSION, 0);
+    assert_eq!(Versioned::VERSION, 3);
+}
+
+#[test]
+fn payload_can_be_migrated() {
+    use gd_props::migration::GdPropPayload;
+
+    #[derive(Serialize, Deserialize)]
+    struct Old {
+        hp: u32,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct New {
+        health: u32,
+    }
+
+    let ron_payload = GdPropPayload::Ron("(hp: 5)".to_string());
+    let migrated = ron_payload.map(|old: Old| New { health: old.hp }).unwrap();
+    assert_eq!(migrated.deserialize::<New>().unwrap(), New { health: 5 });
+
+    let bin_payload = GdPropPayload::Bin(rmp_serde::to_vec(&Old { hp: 7 }).unwrap());
+    let migrated = bin_payload.map(|old: Old| New { health: old.hp }).unwrap();
+    assert_eq!(migrated.deserialize::<New>().unwrap(), New { health: 7 });
+}
+
+#[test]
+fn error_keeps_cause() {
+    use gd_props::errors::GdPropError;
+    use godot::global::Error;
+    use std::error::Error as _;
+
+    let mismatch = GdPropError::ClassMismatch {
+        expected: "TestStruct".to_string(),
+        found: "OtherStruct".to_string(),
+    };
+    assert_eq!(mismatch.to_godot_error(), Error::ERR_FILE_CORRUPT);
+    assert!(mismatch.source().is_none());
+
+    let spanned = ron::from_str::<(u32, u32)>("(1,\n x)").unwrap_err();
+    let deserialize = GdPropError::RonDeserialize(spanned);
+    assert_eq!(deserialize.to_godot_error(), Error::ERR_FILE_CANT_READ);
+    assert_eq!(
+        deserialize.position().map(|position| position.line),
+        Some(2)
+    );
+    assert!(deserialize.source().is_some());
+}
+
+#[test]
+fn export_issue_lists_file() {
+    use gd_props::errors::GdExportIssue;
+
+    let issue = GdExportIssue {
+        path: "res://broken.gdron".to_string(),
+        class: "TestStruct".to_string(),
+        message: "can't deserialize from ron".to_string(),
+    };
+    assert_eq!(
+        issue.to_string(),
+        "res://broken.gdron (TestStruct): can't deserialize from ron"
+    );
+}
+
+#[test]
+fn ron_export_ord_roundtrip() {
+    use gd_props::private::GdRonExport;
+
+    for variant in [
+        GdRonExport::Keep,
+        GdRonExport::Convert,
+        GdRonExport::ConvertCompressed,
+    ] {
+        assert_eq!(GdRonExport::from_ord(variant.ord()), Some(variant));
+    }
+    assert_eq!(GdRonExport::from_ord(-1), None);
+    assert_eq!(GdRonExport::from_ord(3), None);
+    assert_eq!(GdRonExport::from_ord(i64::MAX), None);
+}
+
+#[test]
+fn ron_export_default_follows_export_compression() {
+    use crate::structs::prop_handlers::PropPluginExporter;
+    use gd_props::codec::GdPropCompression;
+    use gd_props::private::GdRonExport;
+
+    assert_eq!(
+        GdRonExport::default_for(GdPropCompression::None),
+        GdRonExport::Convert
+    );
+    assert_eq!(
+        GdRonExport::default_for(GdPropCompression::Zstd),
+        GdRonExport::ConvertCompressed
+    );
+    // `PropPlugin` doesn't set `#[export_compression]`.
+    assert_eq!(
+        <PropPluginExporter as GdPropExporter>::_int_default_ron_export(),
+        GdRonExport::Convert
+    );
+}
+
+#[test]
+fn file_actions_apply_to_handled_formats() {
+    use gd_props::codec::GdPropFormat;
+    use gd_props::private::GdPropFileAction;
+
+    let formats = GdPropFormat::DEFAULT;
+    assert!(GdPropFileAction::ConvertToBin.applies_to("res://stats.gdron", formats));
+    assert!(!GdPropFileAction::ConvertToBin.applies_to("res://stats.gdbin", formats));
+    assert!(GdPropFileAction::ConvertToRon.applies_to("res://stats.gdbin", formats));
+    assert!(!GdPropFileAction::ConvertToRon.applies_to("res://stats.gdron", formats));
+    assert!(!GdPropFileAction::Validate.applies_to("res://stats.tres", formats));
+    assert!(!GdPropFileAction::ConvertToRon.applies_to("res://stats.gdbin", &[GdPropFormat::GdBin]));
+
+    for action in GdPropFileAction::ALL {
+        assert_eq!(GdPropFileAction::from_id(action.id() as i64), Some(action));
+    }
+    assert_eq!(GdPropFileAction::from_id(0), None);
+}
+
+#[derive(GodotClass, Serialize, Deserialize, GdProp, PartialEq, Debug)]
+#[class(init, base=Resource)]
+struct CodecStruct {
+    name: String,
+    values: Vec<i32>,
+}
+
+fn codec_header() -> gd_props::codec::GdMetaHeader {
+    gd_props::codec::GdMetaHeader {
+        gd_class: CodecStruct::HEAD_IDENT.to_string(),
+        uid: "uid://cgmqkr8i6rr5v".to_string(),
+        version: CodecStruct::VERSION,
+        compression: gd_props::codec::GdPropCompression::None,
+        seal: None,
+        checksum: None,
+    }
+}
+
+/// Header read from the file, without checksum of the payload.
+fn without_checksum(header: gd_props::codec::GdMetaHeader) -> gd_props::codec::GdMetaHeader {
+    gd_props::codec::GdMetaHeader {
+        checksum: None,
+        ..header
+    }
+}
+
+fn codec_struct() -> CodecStruct {
+    CodecStruct {
+        name: "codec".to_string(),
+        values: vec![-1, 0, 1],
+    }
+}
+
+#[test]
+fn codec_roundtrip_without_engine() {
+    use gd_props::codec::{BinCodec, GdPropCodec, RonCodec};
+
+    let mut ron_file = Vec::new();
+    RonCodec::encode(&codec_header(), &codec_struct(), &mut ron_file).unwrap();
+    let (header, decoded) = RonCodec::decode::<CodecStruct, _>(&mut ron_file.as_slice()).unwrap();
+    assert_eq!(header, codec_header());
+    assert_eq!(decoded, codec_struct());
+
+    let mut bin_file = Vec::new();
+    BinCodec::encode(&codec_header(), &codec_struct(), &mut bin_file).unwrap();
+    let (header, decoded) = BinCodec::decode::<CodecStruct, _>(&mut bin_file.as_slice()).unwrap();
+    assert!(header.checksum.is_some());
+    assert_eq!(without_checksum(header), codec_header());
+    assert_eq!(decoded, codec_struct());
+}
+
+#[test]
+fn codec_reads_legacy_bin_header() {
+    use gd_props::codec::{BinCodec, GdPropCodec};
+
+    let header = codec_header();
+    let mut bin_file = Vec::new();
+    for string in [&header.gd_class, &header.uid] {
+        bin_file.extend((string.len() as u32).to_le_bytes());
+        bin_file.extend(string.as_bytes());
+    }
+    bin_file.extend(rmp_serde::to_vec(&codec_struct()).unwrap());
+
+    let (read_header, decoded) =
+        BinCodec::decode::<CodecStruct, _>(&mut bin_file.as_slice()).unwrap();
+    assert_eq!(read_header, header);
+    assert_eq!(decoded, codec_struct());
+}
+
+#[test]
+fn payload_lists_ext_references() {
+    use gd_props::migration::GdPropPayload;
+
+    let ron_payload = GdPropPayload::Ron(
+        r#"(
+    name: "ext",
+    item: ExtResource((gd_class: "TestResource", uid: "uid://abc", path: "res://test.gdron")),
+    other: None,
+)"#
+        .to_string(),
+    );
+    let found = ron_payload.ext_references().unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].gd_class, "TestResource");
+    assert_eq!(found[0].uid, "uid://abc");
+    assert_eq!(found[0].path, "res://test.gdron");
+
+    #[derive(Serialize)]
+    enum Ext {
+        ExtResource((String, String, String)),
+    }
+    let bin_payload = GdPropPayload::Bin(
+        rmp_serde::to_vec(&(
+            "ext",
+            Ext::ExtResource((
+                "TestResource".to_string(),
+                "uid://abc".to_string(),
+                "res://test.gdbin".to_string(),
+            )),
+        ))
+        .unwrap(),
+    );
+    let found = bin_payload.ext_references().unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].path, "res://test.gdbin");
+}
+
+#[test]
+fn payload_renames_ext_references() {
+    use gd_props::migration::GdPropPayload;
+
+    let renames = HashMap::from([(
+        "res://test.gdron".to_string(),
+        "res://moved/test.gdron".to_string(),
+    )]);
+
+    let ron_payload = GdPropPayload::Ron(
+        r#"(
+    name: "res://test.gdron",
+    item: ExtResource((gd_class: "TestResource", uid: "uid://abc", path: "res://test.gdron")),
+    other: ExtResource((gd_class: "TestResource", uid: "uid://def", path: "res://other.gdron")),
+)"#
+        .to_string(),
+    );
+    let (renamed, count) = ron_payload.rename_ext_references(&renames).unwrap();
+    assert_eq!(count, 1);
+    let GdPropPayload::Ron(serialized) = &renamed else {
+        panic!("payload format changed");
+    };
+    // Strings other than reference paths are kept.
+    assert!(serialized.contains(r#"name: "res://test.gdron""#));
+    let paths: Vec<_> = renamed
+        .ext_references()
+        .unwrap()
+        .into_iter()
+        .map(|found| found.path)
+        .collect();
+    assert_eq!(paths, ["res://moved/test.gdron", "res://other.gdron"]);
+
+    #[derive(Serialize)]
+    enum Ext {
+        ExtResource((String, String, String)),
+    }
+    let bin_payload = GdPropPayload::Bin(
+        rmp_serde::to_vec(&(
+            "res://test.gdron",
+            Ext::ExtResource((
+                "TestResource".to_string(),
+                "uid://abc".to_string(),
+                "res://test.gdron".to_string(),
+            )),
+        ))
+        .unwrap(),
+    );
+    let (renamed, count) = bin_payload.rename_ext_references(&renames).unwrap();
+    assert_eq!(count, 1);
+    let found = renamed.ext_references().unwrap();
+    assert_eq!(found[0].uid, "uid://abc");
+    assert_eq!(found[0].path, "res://moved/test.gdron");
+}
+
+fn codec_roundtrip<C: gd_props::codec::GdPropCodec>() {
+    let mut file = Vec::new();
+    C::encode(&codec_header(), &codec_struct(), &mut file).unwrap();
+    let (header, decoded) = C::decode::<CodecStruct, _>(&mut file.as_slice()).unwrap();
+    assert_eq!(
+        without_checksum(header),
+        codec_header(),
+        "header mismatch in: {}",
+        C::EXTENSION
+    );
+    assert_eq!(
+        decoded,
+        codec_struct(),
+        "payload mismatch in: {}",
+        C::EXTENSION
+    );
+}
+
+#[test]
+fn additional_codecs_roundtrip() {
+    use gd_props::codec::{BincodeCodec, CborCodec, JsonCodec, PostcardCodec};
+
+    codec_roundtrip::<JsonCodec>();
+    codec_roundtrip::<CborCodec>();
+    codec_roundtrip::<BincodeCodec>();
+    codec_roundtrip::<PostcardCodec>();
+}
+
+#[test]
+fn format_is_recognized_among_selected() {
+    use gd_props::codec::GdPropFormat;
+
+    assert_eq!(
+        GdPropFormat::recognize_format("res://test.gdjson"),
+        GdPropFormat::GdJson
+    );
+    assert_eq!(
+        GdPropFormat::recognize_format("res://test.gdpc"),
+        GdPropFormat::GdPostcard
+    );
+    assert_eq!(
+        GdPropFormat::recognize_among("res://test.gdjson", GdPropFormat::DEFAULT),
+        GdPropFormat::None
+    );
+    assert_eq!(
+        GdPropFormat::recognize_format("res://testgdbin"),
+        GdPropFormat::None
+    );
+}
+
+#[test]
+fn compressed_bin_roundtrip() {
+    use gd_props::codec::{BinCodec, GdPropCodec, GdPropCompression};
+
+    let mut plain = Vec::new();
+    BinCodec::encode(&codec_header(), &codec_struct(), &mut plain).unwrap();
+
+    for compression in [
+        GdPropCompression::Zstd,
+        GdPropCompression::Lz4,
+        GdPropCompression::Deflate,
+    ] {
+        let mut header = codec_header();
+        header.compression = compression;
+
+        let mut file = Vec::new();
+        BinCodec::encode(&header, &codec_struct(), &mut file).unwrap();
+        let (read_header, decoded) =
+            BinCodec::decode::<CodecStruct, _>(&mut file.as_slice()).unwrap();
+        assert_eq!(
+            without_checksum(read_header),
+            header,
+            "header mismatch in: {compression}"
+        );
+        assert_eq!(
+            decoded,
+            codec_struct(),
+            "payload mismatch in: {compression}"
+        );
+
+        let recompressed = BinCodec::recompress(&plain, compression).unwrap();
+        let (read_header, decoded) =
+            BinCodec::decode::<CodecStruct, _>(&mut recompressed.as_slice()).unwrap();
+        assert_eq!(read_header.compression, compression);
+        assert_eq!(decoded, codec_struct());
+    }
+}
+
+#[test]
+fn sealed_bin_rejects_tampering() {
+    use gd_props::codec::{BinCodec, GdPropCodec, GdPropKey};
+    use gd_props::errors::GdPropError;
+
+    let keys = [
+        GdPropKey::HmacSha256([7; 32]),
+        GdPropKey::Aes256Gcm([7; 32]),
+        GdPropKey::ChaCha20Poly1305([7; 32]),
+    ];
+
+    for key in keys.iter() {
+        let mut file = Vec::new();
+        BinCodec::encode_with_key(&codec_header(), &codec_struct(), Some(key), &mut file).unwrap();
+
+        let (header, decoded) =
+            BinCodec::decode_with_key::<CodecStruct, _>(&mut file.as_slice(), Some(key)).unwrap();
+        assert!(header.seal.is_some());
+        assert_eq!(decoded, codec_struct());
+
+        let missing = BinCodec::decode::<CodecStruct, _>(&mut file.as_slice());
+        assert!(matches!(missing, Err(GdPropError::KeyMissing)));
+
+        let mut tampered = file.clone();
+        *tampered.last_mut().unwrap() ^= 0xff;
+        let tampered =
+            BinCodec::decode_with_key::<CodecStruct, _>(&mut tampered.as_slice(), Some(key));
+        assert!(matches!(tampered, Err(GdPropError::Tampered)));
+    }
+
+    // Unsealed file can't be passed off as sealed one.
+    let mut unsealed = Vec::new();
+    BinCodec::encode(&codec_header(), &codec_struct(), &mut unsealed).unwrap();
+    let unsealed =
+        BinCodec::decode_with_key::<CodecStruct, _>(&mut unsealed.as_slice(), Some(&keys[0]));
+    assert!(matches!(unsealed, Err(GdPropError::Tampered)));
+
+    // Key of other algorithm doesn't open the file.
+    let mut file = Vec::new();
+    BinCodec::encode_with_key(&codec_header(), &codec_struct(), Some(&keys[1]), &mut file).unwrap();
+    let other = BinCodec::decode_with_key::<CodecStruct, _>(&mut file.as_slice(), Some(&keys[2]));
+    assert!(matches!(other, Err(GdPropError::Tampered)));
+}
+
+#[test]
+fn damaged_bin_is_detected() {
+    use gd_props::codec::{BinCodec, GdPropCodec};
+    use gd_props::errors::GdPropError;
+    use godot::global::Error;
+
+    let mut file = Vec::new();
+    BinCodec::encode(&codec_header(), &codec_struct(), &mut file).unwrap();
+
+    let truncated = &file[..file.len() - 1];
+    let result = BinCodec::decode::<CodecStruct, _>(&mut &truncated[..]);
+    assert!(matches!(result, Err(GdPropError::LengthMismatch { .. })));
+    assert_eq!(
+        result.unwrap_err().to_godot_error(),
+        Error::ERR_FILE_CORRUPT
+    );
+
+    let mut damaged = file.clone();
+    *damaged.last_mut().unwrap() ^= 0xff;
+    let result = BinCodec::decode::<CodecStruct, _>(&mut damaged.as_slice());
+    assert!(matches!(result, Err(GdPropError::ChecksumMismatch { .. })));
+
+    let (header, stored) = BinCodec::read_parts(&mut file.as_slice()).unwrap();
+    assert_eq!(header.checksum.unwrap().len, stored.len() as u64);
+}